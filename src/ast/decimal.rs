@@ -0,0 +1,612 @@
+#![allow(dead_code)]
+
+use std::{cmp::Ordering, fmt};
+
+/// Fractional digits kept by divisions whose result doesn't end, like `1 / 7`. Digits past them are truncated.
+pub const DIVISION_DIGITS: u32 = 50;
+
+/// Largest exponent, in absolute value, of the powers computed exactly and of the numbers parsed in scientific
+/// notation, since their digits grow linearly with it.
+pub const MAX_EXPONENT: u64 = 10_000;
+
+/// Decimal number of arbitrary precision, used for evaluating with `Precision::Big`.
+///
+/// It is stored as its digits without the decimal mark along with how many of them are fractional, so
+/// `-12.5` is `-125` with scale `1`. Numbers are kept normalized, without leading zeros nor trailing
+/// fractional zeros, so equal numbers are equal decimals.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decimal {
+    negative: bool,
+    /// Digits from the least significant one.
+    digits: Vec<u8>,
+    scale: u32,
+}
+
+impl Decimal {
+    pub fn zero() -> Self {
+        Self::new(false, Vec::new(), 0)
+    }
+
+    fn new(negative: bool, digits: Vec<u8>, scale: u32) -> Self {
+        let mut decimal = Self {
+            negative,
+            digits,
+            scale,
+        };
+
+        decimal.normalize();
+        decimal
+    }
+
+    /// Parses a number written in decimal notation, like `12_345.678e-2`, taking both `.` and `,` as decimal
+    /// mark. It is `None` for any other notation, like hexadecimal numbers or `inf`, and for exponents above
+    /// `MAX_EXPONENT`, like `1e900000000000000`, whose digits wouldn't fit in memory.
+    pub fn parse(lexeme: &str) -> Option<Self> {
+        let (mantissa, exponent) = match lexeme.find(['e', 'E']) {
+            Some(index) => (&lexeme[..index], lexeme[index + 1..].parse::<i64>().ok()?),
+            None => (lexeme, 0),
+        };
+
+        if exponent.unsigned_abs() > MAX_EXPONENT {
+            return None;
+        }
+
+        let (integer, fraction) = match mantissa.find(['.', ',']) {
+            Some(index) => (&mantissa[..index], &mantissa[index + 1..]),
+            None => (mantissa, ""),
+        };
+
+        let digits = integer
+            .chars()
+            .chain(fraction.chars())
+            .filter(|c| *c != '_')
+            .map(|c| c.to_digit(10).map(|digit| digit as u8))
+            .rev()
+            .collect::<Option<Vec<u8>>>()?;
+
+        if digits.is_empty() {
+            return None;
+        }
+
+        let fraction_digits = fraction.chars().filter(|c| *c != '_').count() as i64;
+        let scale = fraction_digits - exponent;
+
+        // A negative scale means the exponent moves the decimal mark past the last digit, adding zeros to the right
+        let decimal = match u32::try_from(scale) {
+            Ok(scale) => Self::new(false, digits, scale),
+            Err(_) => {
+                let zeros = usize::try_from(-scale).ok()?;
+                Self::new(false, shift(&digits, zeros), 0)
+            }
+        };
+
+        Some(decimal)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Whether the number has no fractional part.
+    pub fn is_integer(&self) -> bool {
+        self.scale == 0
+    }
+
+    /// Number as an `i64`, if it is an integer fitting in one.
+    pub fn to_i64(&self) -> Option<i64> {
+        if !self.is_integer() {
+            return None;
+        }
+
+        self.to_string().parse().ok()
+    }
+
+    /// Nearest float to the number.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string()
+            .parse()
+            .expect("decimals should be displayed as valid floats")
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::new(!self.negative, self.digits.clone(), self.scale)
+    }
+
+    pub fn add(&self, other: &Decimal) -> Self {
+        let scale = self.scale.max(other.scale);
+        let (left, right) = (self.digits_at(scale), other.digits_at(scale));
+
+        if self.negative == other.negative {
+            return Self::new(self.negative, add(&left, &right), scale);
+        }
+
+        // Signs are different, so the result is the difference of the magnitudes with the sign of the bigger one
+        match compare(&left, &right) {
+            Ordering::Less => Self::new(other.negative, sub(&right, &left), scale),
+            _ => Self::new(self.negative, sub(&left, &right), scale),
+        }
+    }
+
+    pub fn sub(&self, other: &Decimal) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &Decimal) -> Self {
+        Self::new(
+            self.negative != other.negative,
+            mul(&self.digits, &other.digits),
+            self.scale + other.scale,
+        )
+    }
+
+    /// Divides the number by `other`, truncating the result to `DIVISION_DIGITS` fractional digits. It is `None`
+    /// if `other` is zero.
+    pub fn div(&self, other: &Decimal) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+
+        // `(a / 10^sa) / (b / 10^sb)` is `(a * 10^(sb + d) / b) / 10^(sa + d)`, having at least `d` fractional digits
+        let numerator = shift(&self.digits, (other.scale + DIVISION_DIGITS) as usize);
+        let (quotient, _) = div_rem(&numerator, &other.digits);
+
+        Some(Self::new(
+            self.negative != other.negative,
+            quotient,
+            self.scale + DIVISION_DIGITS,
+        ))
+    }
+
+    /// Quotient of the division truncated towards zero and its remainder, whose sign is the one of the number.
+    /// It is `None` if `other` is zero.
+    pub fn div_rem(&self, other: &Decimal) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let scale = self.scale.max(other.scale);
+        let (quotient, remainder) = div_rem(&self.digits_at(scale), &other.digits_at(scale));
+
+        Some((
+            Self::new(self.negative != other.negative, quotient, 0),
+            Self::new(self.negative, remainder, scale),
+        ))
+    }
+
+    /// Quotient of the division rounded towards negative infinity. It is `None` if `other` is zero.
+    pub fn div_floor(&self, other: &Decimal) -> Option<Self> {
+        let (quotient, remainder) = self.div_rem(other)?;
+
+        match !remainder.is_zero() && self.negative != other.negative {
+            true => Some(quotient.sub(&Decimal::from(1))),
+            false => Some(quotient),
+        }
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::new(false, self.digits.clone(), self.scale)
+    }
+
+    /// Integer part of the number, rounding it towards zero.
+    pub fn trunc(&self) -> Self {
+        let integer = self.digits.get(self.scale as usize..).unwrap_or_default();
+        Self::new(self.negative, integer.to_vec(), 0)
+    }
+
+    /// Number rounded towards negative infinity.
+    pub fn floor(&self) -> Self {
+        match self.negative && !self.is_integer() {
+            true => self.trunc().sub(&Decimal::from(1)),
+            false => self.trunc(),
+        }
+    }
+
+    /// Number rounded towards positive infinity.
+    pub fn ceil(&self) -> Self {
+        match !self.negative && !self.is_integer() {
+            true => self.trunc().add(&Decimal::from(1)),
+            false => self.trunc(),
+        }
+    }
+
+    /// Number rounded to the nearest integer, rounding halves away from zero like `f64::round`.
+    pub fn round(&self) -> Self {
+        let half = Self::new(self.negative, vec![5], 1);
+        self.add(&half).trunc()
+    }
+
+    /// Raises the number to an integer `exponent`. Negative exponents divide one by the power, so it is `None` if
+    /// the number is zero, and so it is for exponents above `MAX_EXPONENT`.
+    pub fn pow(&self, exponent: i64) -> Option<Self> {
+        let mut result = Decimal::from(1);
+        let mut base = self.clone();
+        let mut remaining = exponent.unsigned_abs();
+
+        if remaining > MAX_EXPONENT {
+            return None;
+        }
+
+        while remaining > 0 {
+            if remaining % 2 == 1 {
+                result = result.mul(&base);
+            }
+
+            base = base.mul(&base);
+            remaining /= 2;
+        }
+
+        match exponent < 0 {
+            true => Decimal::from(1).div(&result),
+            false => Some(result),
+        }
+    }
+
+    /// Compares the numbers exactly.
+    pub fn compare(&self, other: &Decimal) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        let magnitude = compare(&self.digits_at(scale), &other.digits_at(scale));
+
+        match (self.negative, other.negative) {
+            (false, false) => magnitude,
+            (true, true) => magnitude.reverse(),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+
+    /// Digits of the number when it has the given `scale`, which must not be smaller than its own.
+    fn digits_at(&self, scale: u32) -> Vec<u8> {
+        shift(&self.digits, (scale - self.scale) as usize)
+    }
+
+    fn normalize(&mut self) {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+
+        let trailing_zeros = self
+            .digits
+            .iter()
+            .take_while(|digit| **digit == 0)
+            .count()
+            .min(self.scale as usize);
+
+        self.digits.drain(..trailing_zeros);
+        self.scale -= trailing_zeros as u32;
+
+        // Zero has no sign
+        if self.digits.is_empty() {
+            self.negative = false;
+            self.scale = 0;
+        }
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(value: i64) -> Self {
+        let mut digits = Vec::new();
+        let mut magnitude = value.unsigned_abs();
+
+        while magnitude > 0 {
+            digits.push((magnitude % 10) as u8);
+            magnitude /= 10;
+        }
+
+        Self::new(value < 0, digits, 0)
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        let scale = self.scale as usize;
+
+        // Numbers below one have no integer digits, so they start with `0.`, and zeros may follow the mark
+        let integer = self.digits.iter().skip(scale).rev();
+        let fraction = self.digits.iter().take(scale).rev();
+        let leading_zeros = scale.saturating_sub(self.digits.len());
+
+        if self.digits.len() <= scale {
+            write!(f, "0")?;
+        }
+
+        for digit in integer {
+            write!(f, "{digit}")?;
+        }
+
+        if scale > 0 {
+            write!(f, ".{}", "0".repeat(leading_zeros))?;
+
+            for digit in fraction {
+                write!(f, "{digit}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Multiplies the magnitude by `10^zeros`.
+fn shift(digits: &[u8], zeros: usize) -> Vec<u8> {
+    if digits.is_empty() {
+        return Vec::new();
+    }
+
+    let mut shifted = vec![0; zeros];
+    shifted.extend_from_slice(digits);
+    shifted
+}
+
+fn compare(left: &[u8], right: &[u8]) -> Ordering {
+    let significant =
+        |digits: &[u8]| digits.len() - digits.iter().rev().take_while(|d| **d == 0).count();
+
+    significant(left).cmp(&significant(right)).then_with(|| {
+        let length = significant(left);
+        left[..length]
+            .iter()
+            .rev()
+            .cmp(right[..length].iter().rev())
+    })
+}
+
+fn add(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(left.len().max(right.len()) + 1);
+    let mut carry = 0;
+
+    for i in 0..left.len().max(right.len()) {
+        let sum = left.get(i).unwrap_or(&0) + right.get(i).unwrap_or(&0) + carry;
+        result.push(sum % 10);
+        carry = sum / 10;
+    }
+
+    if carry > 0 {
+        result.push(carry);
+    }
+
+    result
+}
+
+/// Subtracts magnitudes, where `left` must not be smaller than `right`.
+fn sub(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(left.len());
+    let mut borrow = 0;
+
+    for (i, digit) in left.iter().enumerate() {
+        let subtrahend = right.get(i).unwrap_or(&0) + borrow;
+
+        if *digit >= subtrahend {
+            result.push(digit - subtrahend);
+            borrow = 0;
+        } else {
+            result.push(digit + 10 - subtrahend);
+            borrow = 1;
+        }
+    }
+
+    trim(result)
+}
+
+fn mul(left: &[u8], right: &[u8]) -> Vec<u8> {
+    if left.is_empty() || right.is_empty() {
+        return Vec::new();
+    }
+
+    // Products are accumulated before carrying, which can't overflow for any realistic number of digits
+    let mut sums = vec![0u64; left.len() + right.len()];
+
+    for (i, left_digit) in left.iter().enumerate() {
+        for (j, right_digit) in right.iter().enumerate() {
+            sums[i + j] += u64::from(*left_digit) * u64::from(*right_digit);
+        }
+    }
+
+    let mut result = Vec::with_capacity(sums.len());
+    let mut carry = 0;
+
+    for sum in sums {
+        let total = sum + carry;
+        result.push((total % 10) as u8);
+        carry = total / 10;
+    }
+
+    trim(result)
+}
+
+/// Long division of magnitudes, where `right` must not be zero.
+fn div_rem(left: &[u8], right: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let right = trim(right.to_vec());
+    let mut quotient = vec![0; left.len()];
+    let mut remainder = Vec::new();
+
+    for (i, digit) in left.iter().enumerate().rev() {
+        remainder = trim(shift(&remainder, 1));
+
+        match remainder.first_mut() {
+            Some(lowest) => *lowest = *digit,
+            None if *digit != 0 => remainder.push(*digit),
+            None => {}
+        }
+
+        // Remainder is below ten times the divisor, so the digit of the quotient is found subtracting it
+        while compare(&remainder, &right) != Ordering::Less {
+            remainder = sub(&remainder, &right);
+            quotient[i] += 1;
+        }
+    }
+
+    (trim(quotient), remainder)
+}
+
+/// Removes the leading zeros of the magnitude.
+fn trim(mut digits: Vec<u8>) -> Vec<u8> {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    digits
+}
+
+#[cfg(test)]
+mod ast_decimal_tests {
+    use std::cmp::Ordering;
+
+    use super::Decimal;
+
+    /// Parses the decimal, which may be negative unlike lexemes.
+    fn decimal(text: &str) -> Decimal {
+        match text.strip_prefix('-') {
+            Some(lexeme) => Decimal::parse(lexeme).unwrap().neg(),
+            None => Decimal::parse(text).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        // Arrange
+        // Each lexeme is paired with its expected text
+        let cases = [
+            ("12345678901234567890", "12345678901234567890"),
+            ("0.5", "0.5"),
+            (".25", "0.25"),
+            ("5.", "5"),
+            ("1_000.500", "1000.5"),
+            ("0012.0300", "12.03"),
+            ("1.5e3", "1500"),
+            ("25e-4", "0.0025"),
+            ("1,5", "1.5"),
+            ("0.000", "0"),
+        ];
+
+        for (lexeme, expected_text) in cases {
+            // Act & Assert
+            assert_eq!(
+                decimal(lexeme).to_string(),
+                expected_text,
+                "should parse decimal notation and display it normalized"
+            )
+        }
+
+        for lexeme in ["0xFF", "inf", "nan", "", "1e"] {
+            assert_eq!(
+                Decimal::parse(lexeme),
+                None,
+                "should not parse notations other than the decimal one"
+            )
+        }
+
+        for lexeme in ["1e900000000000000", "1e10001", "1e-4000000000", "5e-10001"] {
+            assert_eq!(
+                Decimal::parse(lexeme),
+                None,
+                "should not parse exponents above the maximum one"
+            )
+        }
+
+        assert_eq!(
+            decimal("1e10000").to_string().len(),
+            10_001,
+            "should parse exponents up to the maximum one"
+        );
+
+        assert_eq!(
+            decimal("1e-10000").to_string().len(),
+            10_002,
+            "should parse negative exponents down to the maximum one"
+        )
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        // Arrange
+        // Each operation is paired with its expected text
+        let cases = [
+            (
+                decimal("12345678901234567890").add(&decimal("1")),
+                "12345678901234567891",
+            ),
+            (decimal("0.1").add(&decimal("0.2")), "0.3"),
+            (decimal("1").sub(&decimal("1.25")), "-0.25"),
+            (decimal("-2.5").mul(&decimal("4")), "-10"),
+            (
+                decimal("99999999999").mul(&decimal("99999999999")),
+                "9999999999800000000001",
+            ),
+            (decimal("6").div(&decimal("3")).unwrap(), "2"),
+            (decimal("1").div(&decimal("8")).unwrap(), "0.125"),
+            (decimal("-7").div_floor(&decimal("2")).unwrap(), "-4"),
+            (decimal("7.5").div_rem(&decimal("2")).unwrap().1, "1.5"),
+            (decimal("-7").div_rem(&decimal("3")).unwrap().1, "-1"),
+            (
+                decimal("2").pow(100).unwrap(),
+                "1267650600228229401496703205376",
+            ),
+            (decimal("2").pow(-2).unwrap(), "0.25"),
+            (
+                decimal("-12345678901234567890.5").abs(),
+                "12345678901234567890.5",
+            ),
+            (decimal("-2.75").trunc(), "-2"),
+            (decimal("-2.25").floor(), "-3"),
+            (decimal("2.25").ceil(), "3"),
+            (decimal("-3").floor(), "-3"),
+            (decimal("2.5").round(), "3"),
+            (decimal("-2.5").round(), "-3"),
+            (decimal("-0.4").round(), "0"),
+            (
+                decimal("12345678901234567890.49").round(),
+                "12345678901234567890",
+            ),
+        ];
+
+        for (result, expected_text) in cases {
+            // Act & Assert
+            assert_eq!(
+                result.to_string(),
+                expected_text,
+                "should compute exact results"
+            )
+        }
+
+        assert_eq!(
+            decimal("1").div(&decimal("7")).unwrap().to_string(),
+            format!("0.{}14", "142857".repeat(8)),
+            "should keep fifty fractional digits of divisions which don't end"
+        );
+
+        assert_eq!(
+            decimal("1").div(&Decimal::zero()),
+            None,
+            "should not divide by zero"
+        )
+    }
+
+    #[test]
+    fn test_compare() {
+        // Arrange
+        // Each pair of lexemes is paired with their expected ordering
+        let cases = [
+            ("1.5", "1.50", Ordering::Equal),
+            ("-2", "1", Ordering::Less),
+            ("-2", "-3", Ordering::Greater),
+            (
+                "12345678901234567891",
+                "12345678901234567890",
+                Ordering::Greater,
+            ),
+            ("0.001", "0.01", Ordering::Less),
+        ];
+
+        for (left, right, expected_ordering) in cases {
+            // Act & Assert
+            assert_eq!(
+                decimal(left).compare(&decimal(right)),
+                expected_ordering,
+                "should compare decimals exactly"
+            )
+        }
+    }
+}
@@ -0,0 +1,245 @@
+#![allow(dead_code)]
+
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use crate::tokenizer::symbol::Symbol;
+
+use super::{
+    functions::{FunctionRegistry, UserFunction},
+    options::EvalOptions,
+    value::Value,
+};
+
+/// Values of the variables and functions defined by the user available while evaluating an expression, along
+/// with the registry of the other functions calls can reach and the options of the evaluation. Names are case
+/// sensitive.
+///
+/// Variables are kept in nested scopes, where the innermost one shadows the others. Scopes are shared between
+/// clones, so cloning the environment or creating a child scope doesn't copy any variable.
+#[derive(Debug, Clone)]
+pub struct Environment {
+    // Scopes from the outermost to the innermost, which is the only one written. It is copied on write if a clone
+    // still shares it
+    scopes: Vec<Rc<HashMap<Symbol, Value>>>,
+    // Functions are shared, so cloning the environment for the scope of a call doesn't copy their bodies
+    functions: Rc<HashMap<Symbol, Rc<UserFunction>>>,
+    registry: Rc<FunctionRegistry>,
+    options: EvalOptions,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            scopes: vec![Rc::default()],
+            functions: Rc::default(),
+            registry: Rc::new(FunctionRegistry::builtins()),
+            options: EvalOptions::default(),
+        }
+    }
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an environment without variables nor functions, evaluating with the given `options`.
+    pub fn with_options(options: EvalOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a scope inside the environment, like the one binding the arguments of a call. Variables defined in
+    /// it shadow the ones of the environment, which is left untouched.
+    pub fn child(&self) -> Self {
+        let mut child = self.clone();
+        child.scopes.push(Rc::default());
+
+        child
+    }
+
+    pub fn options(&self) -> &EvalOptions {
+        &self.options
+    }
+
+    /// Changes the options used by the following evaluations, keeping variables and functions.
+    pub fn set_options(&mut self, options: EvalOptions) {
+        self.options = options;
+    }
+
+    /// Binds the variable `name` to `value` in the innermost scope, replacing its previous value there if any.
+    pub fn define(&mut self, name: &str, value: impl Into<Value>) {
+        let scope = self
+            .scopes
+            .last_mut()
+            .expect("environment should have at least one scope");
+
+        Rc::make_mut(scope).insert(Symbol::intern(name), value.into());
+    }
+
+    /// Value of the variable `name` in the innermost scope defining it.
+    pub fn get(&self, name: Symbol) -> Option<Value> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name))
+            .cloned()
+    }
+
+    /// Variables visible in the environment along with their values, in no particular order. Shadowed variables
+    /// are skipped.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &Value)> {
+        let mut seen = HashSet::new();
+
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter())
+            .filter(move |(name, _)| seen.insert(**name))
+            .map(|(name, value)| (*name, value))
+    }
+
+    /// Defines the function `name`, replacing its previous definition if any.
+    pub fn set_function(&mut self, name: &str, function: UserFunction) {
+        Rc::make_mut(&mut self.functions).insert(Symbol::intern(name), Rc::new(function));
+    }
+
+    pub fn function(&self, name: Symbol) -> Option<&UserFunction> {
+        self.functions.get(&name).map(Rc::as_ref)
+    }
+
+    /// Registry of the functions which are not defined by the user, which has the built-in ones by default.
+    pub fn registry(&self) -> &FunctionRegistry {
+        &self.registry
+    }
+
+    /// Mutable registry of the functions, so closures can be registered or replace the built-in ones, like
+    /// `env.registry_mut().register_fn("double", Arity::Exact(1), ...)`.
+    pub fn registry_mut(&mut self) -> &mut FunctionRegistry {
+        Rc::make_mut(&mut self.registry)
+    }
+}
+
+#[cfg(test)]
+mod ast_environment_tests {
+    use crate::{
+        ast::{expressions::Expression, functions::UserFunction, value::Value},
+        tokenizer::{symbol::Symbol, tokens::Token},
+    };
+
+    use super::Environment;
+
+    #[test]
+    fn test_define_and_get() {
+        // Arrange
+        let mut env = Environment::new();
+
+        // Act
+        env.define("x", 1.0);
+        env.define("x", 2.0);
+
+        // Assert
+        assert_eq!(
+            env.get(Symbol::intern("x")),
+            Some(Value::Float(2.0)),
+            "should keep the last value bound to the variable"
+        );
+
+        assert_eq!(
+            env.get(Symbol::intern("X")),
+            None,
+            "should tell variables apart by case"
+        )
+    }
+
+    #[test]
+    fn test_set_function() {
+        // Arrange
+        let mut env = Environment::new();
+        let one = UserFunction::new(vec![], Expression::literal(Token::integer(1)));
+        let two = UserFunction::new(vec![], Expression::literal(Token::integer(2)));
+
+        // Act
+        env.set_function("f", one);
+        env.set_function("f", two.clone());
+        let mut scope = env.clone();
+        scope.define("x", 1.0);
+
+        // Assert
+        assert_eq!(
+            env.function(Symbol::intern("f")),
+            Some(&two),
+            "should keep the last definition of the function"
+        );
+
+        assert_eq!(
+            (
+                scope.function(Symbol::intern("f")),
+                env.get(Symbol::intern("x"))
+            ),
+            (Some(&two), None),
+            "should share functions with clones without sharing their variables"
+        )
+    }
+
+    #[test]
+    fn test_child_scope() {
+        // Arrange
+        let mut env = Environment::new();
+        env.define("x", 1);
+        env.define("y", 2);
+
+        // Act
+        let mut child = env.child();
+        child.define("x", 10);
+        child.define("z", 30);
+
+        // Assert
+        assert_eq!(
+            ["x", "y", "z"].map(|name| child.get(Symbol::intern(name))),
+            [
+                Some(Value::Integer(10)),
+                Some(Value::Integer(2)),
+                Some(Value::Integer(30))
+            ],
+            "should shadow the variables of the parent while still seeing the other ones"
+        );
+
+        assert_eq!(
+            ["x", "y", "z"].map(|name| env.get(Symbol::intern(name))),
+            [Some(Value::Integer(1)), Some(Value::Integer(2)), None],
+            "should leave the parent untouched"
+        )
+    }
+
+    #[test]
+    fn test_iter() {
+        // Arrange
+        let mut env = Environment::new();
+        env.define("x", 1);
+        env.define("y", 2);
+
+        let mut child = env.child();
+        child.define("x", 10);
+
+        // Act
+        let mut variables = child
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .collect::<Vec<_>>();
+
+        variables.sort_by_key(|(name, _)| *name);
+
+        // Assert
+        assert_eq!(
+            variables,
+            [("x", Value::Integer(10)), ("y", Value::Integer(2))],
+            "should list every visible variable once, with its innermost value"
+        )
+    }
+}
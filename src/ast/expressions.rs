@@ -1,63 +1,1276 @@
 #![allow(dead_code)]
 
-use std::fmt;
+use std::{cmp::Ordering, collections::HashSet, error::Error, fmt, mem};
 
-use crate::tokenizer::tokens::{Operator, Token};
+use crate::tokenizer::{
+    symbol::Symbol,
+    tokens::{Operator, Position, Span, Token},
+};
+
+use super::{
+    environment::Environment,
+    functions::{self, Arity, UserFunction},
+    operators::CustomOperator,
+    options::{AngleMode, EvalOptions, IntegerOverflow, Precision},
+    value::Value,
+};
+
+#[cfg(feature = "big-decimal")]
+use super::decimal::Decimal;
 
 /// Represents the set of expressions used to build the nodes for the AST.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Walking the tree never recurses, so deep expressions can be evaluated, displayed, cloned, compared and dropped,
+/// except for `Debug`, which is derived and only meant for expressions of a reasonable depth.
+#[derive(Debug)]
 pub enum Expression {
     Binary(BinaryExpr),
     Unary(UnaryExpr),
-    Literal(Token),
+    Postfix(UnaryExpr),
+    Literal(LiteralExpr, NodeSpan),
+    /// Custom operator registered in the parser applied to its operands, like `1 @ 3`.
+    CustomBinary {
+        operator: CustomOperator,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    /// Call of the function `name`, like `max(1, 2)`.
+    Call {
+        name: Symbol,
+        args: Vec<Expression>,
+        span: NodeSpan,
+    },
+    /// Absolute value written between bars, like `|x - 1|`. It is syntax, so it is computed by the built-in `abs`
+    /// even if the user or the registry replaced it.
+    AbsoluteValue {
+        expr: Box<Expression>,
+        span: NodeSpan,
+    },
+    /// Variable whose value is taken from the environment, like `x`.
+    Variable(Symbol, NodeSpan),
+    /// Conditional like `x > 0 ? x : -x`, whose value is `then` if `condition` is not zero, or `otherwise` if it is.
+    Conditional {
+        condition: Box<Expression>,
+        then: Box<Expression>,
+        otherwise: Box<Expression>,
+    },
+    /// Assignment of `value` to the variable `name`, like `x = 3 * 4`. It is only allowed as a statement.
+    Assign {
+        name: Symbol,
+        value: Box<Expression>,
+        span: NodeSpan,
+    },
+    /// Definition of the function `name`, like `f(x) = x * x + 1`. It is only allowed as a statement.
+    FunctionDef {
+        name: Symbol,
+        params: Vec<Symbol>,
+        body: Box<Expression>,
+        span: NodeSpan,
+    },
+    /// Expression between parentheses in the source, like `(1 + 2)`. It only keeps the parentheses written by
+    /// the user, so its value is the one of the grouped expression.
+    Grouping(Box<Expression>),
 }
 
 impl fmt::Display for Expression {
+    /// Expressions are written with an explicit stack of pieces instead of recursion, like they are evaluated, so
+    /// displaying a deep expression can't exhaust the call stack.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use DisplayPiece::{Expr, Text};
+
+        let mut pieces = vec![Expr(self)];
+
+        while let Some(piece) = pieces.pop() {
+            let expr = match piece {
+                Text(text) => {
+                    write!(f, "{text}")?;
+                    continue;
+                }
+                Expr(expr) => expr,
+            };
+
+            // Pieces of each expression are listed in the order they are written
+            let expr_pieces = match expr {
+                Expression::Binary(binary) => vec![
+                    Text(&"("),
+                    Expr(&binary.left),
+                    Text(&" "),
+                    Text(&binary.operator),
+                    Text(&" "),
+                    Expr(&binary.right),
+                    Text(&")"),
+                ],
+                Expression::CustomBinary {
+                    operator,
+                    left,
+                    right,
+                } => vec![
+                    Text(&"("),
+                    Expr(left),
+                    Text(&" "),
+                    Text(&operator.symbol),
+                    Text(&" "),
+                    Expr(right),
+                    Text(&")"),
+                ],
+                // Word operators are kept apart from their operand, like `(not x)`
+                Expression::Unary(unary) => match unary.operator {
+                    Token::Operator(Operator::Not) => {
+                        vec![Text(&"(not "), Expr(&unary.expr), Text(&")")]
+                    }
+                    _ => vec![
+                        Text(&"("),
+                        Text(&unary.operator),
+                        Expr(&unary.expr),
+                        Text(&")"),
+                    ],
+                },
+                Expression::Postfix(postfix) => vec![
+                    Text(&"("),
+                    Expr(&postfix.expr),
+                    Text(&postfix.operator),
+                    Text(&")"),
+                ],
+                Expression::Literal(literal, _) => vec![Text(literal)],
+                Expression::Call { name, args, .. } => {
+                    let args = args
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(index, arg)| match index {
+                            0 => vec![Expr(arg)],
+                            _ => vec![Text(&", "), Expr(arg)],
+                        });
+
+                    [Text(name), Text(&"(")]
+                        .into_iter()
+                        .chain(args)
+                        .chain([Text(&")")])
+                        .collect()
+                }
+                Expression::AbsoluteValue { expr, .. } => vec![Text(&"|"), Expr(expr), Text(&"|")],
+                Expression::Variable(name, _) => vec![Text(name)],
+                Expression::Conditional {
+                    condition,
+                    then,
+                    otherwise,
+                } => vec![
+                    Text(&"("),
+                    Expr(condition),
+                    Text(&" ? "),
+                    Expr(then),
+                    Text(&" : "),
+                    Expr(otherwise),
+                    Text(&")"),
+                ],
+                Expression::Assign { name, value, .. } => {
+                    vec![Text(name), Text(&" = "), Expr(value)]
+                }
+                Expression::FunctionDef {
+                    name, params, body, ..
+                } => {
+                    let params = params.iter().map(Symbol::to_string).collect::<Vec<_>>();
+                    write!(f, "{name}({}) = ", params.join(", "))?;
+
+                    vec![Expr(body)]
+                }
+                Expression::Grouping(expr) => match **expr {
+                    // Operations are already printed between parentheses, which are the grouping ones
+                    Expression::Binary(_)
+                    | Expression::CustomBinary { .. }
+                    | Expression::Unary(_)
+                    | Expression::Postfix(_)
+                    | Expression::Conditional { .. } => vec![Expr(expr)],
+                    _ => vec![Text(&"("), Expr(expr), Text(&")")],
+                },
+            };
+
+            // Pieces are popped in reverse order, so the first one is pushed last
+            pieces.extend(expr_pieces.into_iter().rev());
+        }
+
+        Ok(())
+    }
+}
+
+/// Piece of the text of an expression, which is either written as it is or expanded into more pieces.
+enum DisplayPiece<'a> {
+    Text(&'a dyn fmt::Display),
+    Expr(&'a Expression),
+}
+
+/// Subexpressions are dropped with an explicit stack instead of recursion, so dropping a deep expression can't
+/// exhaust the call stack.
+impl Drop for Expression {
+    fn drop(&mut self) {
+        let mut pending = self.take_subexpressions();
+
+        // Each expression is dropped once its subexpressions are taken out, so dropping it doesn't recurse
+        while let Some(mut expr) = pending.pop() {
+            pending.extend(expr.take_subexpressions());
+        }
+    }
+}
+
+/// Expressions are cloned with an explicit stack instead of recursion, like they are dropped, so cloning a deep
+/// expression can't exhaust the call stack.
+impl Clone for Expression {
+    fn clone(&self) -> Self {
+        let mut root = self.shallow_clone();
+        let mut pending = vec![(self, &mut root)];
+
+        // Each clone is built without its subexpressions, whose clones fill the holes left in their place later
+        while let Some((expr, clone)) = pending.pop() {
+            for (subexpr, hole) in expr
+                .subexpressions()
+                .into_iter()
+                .zip(clone.subexpressions_mut())
+            {
+                *hole = subexpr.shallow_clone();
+                pending.push((subexpr, hole));
+            }
+        }
+
+        root
+    }
+}
+
+/// Expressions are compared with an explicit stack instead of recursion, so comparing deep expressions can't
+/// exhaust the call stack.
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        let mut pending = vec![(self, other)];
+
+        while let Some((expr, other)) = pending.pop() {
+            if !expr.same_node(other) {
+                return false;
+            }
+
+            pending.extend(
+                expr.subexpressions()
+                    .into_iter()
+                    .zip(other.subexpressions()),
+            );
+        }
+
+        true
+    }
+}
+
+/// Range of the source an expression was built from, which is only known if it was parsed from spanned tokens.
+///
+/// Spans just tell where expressions come from, so they are ignored when comparing them: an expression built by
+/// hand is equal to the same expression parsed from a source.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NodeSpan(Option<Span>);
+
+impl NodeSpan {
+    pub fn new(span: Option<Span>) -> Self {
+        Self(span)
+    }
+
+    pub fn get(&self) -> Option<Span> {
+        self.0
+    }
+
+    /// Smallest span covering both spans, which is unknown if any of them is.
+    pub fn to(self, other: NodeSpan) -> NodeSpan {
+        match (self.0, other.0) {
+            (Some(span), Some(other)) => NodeSpan(Some(span.to(other))),
+            _ => NodeSpan(None),
+        }
+    }
+}
+
+impl PartialEq for NodeSpan {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Statements of a source, which are evaluated one after another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program(Vec<Expression>);
+
+impl Program {
+    pub fn new(statements: Vec<Expression>) -> Self {
+        Self(statements)
+    }
+
+    pub fn statements(&self) -> &[Expression] {
+        &self.0
+    }
+
+    /// Evaluates every statement starting without any variable, returning the value of the last one, or `None`
+    /// if there are no statements or the last one is a function definition.
+    pub fn eval(&self) -> Result<Option<Value>, EvalError> {
+        self.eval_with(&mut Environment::new())
+    }
+
+    /// Executes every statement over `env` from left to right, returning the value of the last one, or `None` if
+    /// there are no statements or the last one is a function definition. Assignments and definitions are written
+    /// into `env`, so the following statements can use them.
+    ///
+    /// It stops at the first statement failing.
+    pub fn eval_with(&self, env: &mut Environment) -> Result<Option<Value>, EvalError> {
+        let mut value = None;
+
+        for statement in &self.0 {
+            value = statement.execute(env)?;
+        }
+
+        Ok(value)
+    }
+
+    /// Same as `eval`, but returning the values of every statement in order. Function definitions have no
+    /// value, so they are skipped.
+    pub fn eval_all(&self) -> Result<Vec<Value>, EvalError> {
+        self.eval_all_with(&mut Environment::new())
+    }
+
+    /// Same as `eval_with`, but returning the values of every statement in order. Function definitions have no
+    /// value, so they are skipped.
+    pub fn eval_all_with(&self, env: &mut Environment) -> Result<Vec<Value>, EvalError> {
+        self.0
+            .iter()
+            .filter_map(|statement| statement.execute(env).transpose())
+            .collect()
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let statements = self.0.iter().map(Expression::to_string).collect::<Vec<_>>();
+
+        write!(f, "{}", statements.join("; "))
+    }
+}
+
+/// Error found while evaluating an expression which was parsed successfully.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    UnknownFunction(Symbol),
+    UnknownVariable(Symbol),
+    /// Factorial of a negative or non-integer number.
+    InvalidFactorial(f64),
+    /// Factorial too big to be represented, which is any past `170!`.
+    FactorialOverflow(f64),
+    WrongArity {
+        name: Symbol,
+        expected: Arity,
+        found: usize,
+    },
+    /// Operand of a bitwise operator which is not an integer fitting in 64 bits.
+    InvalidBitwiseOperand {
+        operator: Operator,
+        value: f64,
+    },
+    /// Argument of the function `name` which is not an integer fitting in 64 bits, like the `2.5` in `gcd(2.5, 5)`.
+    NonIntegerArgument {
+        name: Symbol,
+        value: f64,
+    },
+    /// Shift by a negative amount or by more bits than the shifted integer has.
+    InvalidShift(f64),
+    /// Division, floor division or modulo whose divisor is zero, like `1 / 0`.
+    DivisionByZero,
+    /// Integer result of the `operation` which doesn't fit in an `i64`, like the `^` in `2 ^ 63`. It is only
+    /// reported with `IntegerOverflow::Fail`.
+    IntegerOverflow {
+        operation: Token,
+    },
+    /// Infinity or NaN given by the `operation` from finite operands, like the `*` in `1e308 * 10`, or by a number
+    /// literal too big for a float, like `1e400`, whose token is the `operation`. It is only reported unless
+    /// `EvalOptions::allow_non_finite` is set.
+    NonFiniteResult {
+        operation: Token,
+        value: f64,
+    },
+    /// Call of the function `name` with arguments out of its domain, like `asin(2)`.
+    DomainError {
+        name: Symbol,
+        args: Vec<f64>,
+    },
+    /// Call of the function `name` with arguments it doesn't accept for the given `reason`, like `ncr(3, 5)`, which
+    /// can't choose more items than there are.
+    InvalidArguments {
+        name: Symbol,
+        reason: String,
+    },
+    /// Definition of a function calling itself, even through other functions, which could never end.
+    RecursiveFunction(Symbol),
+    /// Function definition evaluated as a value instead of executed as a statement.
+    DefinitionWithoutValue(Symbol),
+}
+
+impl fmt::Display for EvalError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Expression::Binary(binary) => write!(
+            Self::UnknownFunction(name) => write!(f, "[EVAL ERROR]: unknown function '{name}'"),
+            Self::UnknownVariable(name) => write!(f, "[EVAL ERROR]: unknown variable '{name}'"),
+            Self::InvalidFactorial(value) => write!(
+                f,
+                "[EVAL ERROR]: factorial is only defined for non-negative integers, found {value}"
+            ),
+            Self::FactorialOverflow(value) => write!(
+                f,
+                "[EVAL ERROR]: factorial of {value} is too big to be represented"
+            ),
+            Self::WrongArity {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "[EVAL ERROR]: function '{name}' expects {expected}, found {found}"
+            ),
+            Self::InvalidBitwiseOperand { operator, value } => write!(
+                f,
+                "[EVAL ERROR]: operator '{operator}' only works on integers fitting in 64 bits, found {value}"
+            ),
+            Self::NonIntegerArgument { name, value } => write!(
+                f,
+                "[EVAL ERROR]: function '{name}' only works on integers fitting in 64 bits, found {value}"
+            ),
+            Self::InvalidShift(amount) => write!(
+                f,
+                "[EVAL ERROR]: shift amount must be between 0 and 63, found {amount}"
+            ),
+            Self::DivisionByZero => write!(f, "[EVAL ERROR]: division by zero"),
+            Self::IntegerOverflow { operation } => write!(
+                f,
+                "[EVAL ERROR]: operation '{operation}' overflows a 64 bits integer"
+            ),
+            Self::NonFiniteResult {
+                operation: Token::Float(_),
+                value,
+            } => write!(f, "[EVAL ERROR]: number literal overflows to {value}"),
+            Self::NonFiniteResult { operation, value } => write!(
+                f,
+                "[EVAL ERROR]: operation '{operation}' gives {value} from finite operands"
+            ),
+            Self::DomainError { name, args } => {
+                let args = args.iter().map(f64::to_string).collect::<Vec<_>>();
+                write!(
+                    f,
+                    "[EVAL ERROR]: function '{name}' is not defined for {}",
+                    args.join(", ")
+                )
+            }
+            Self::InvalidArguments { name, reason } => {
+                write!(f, "[EVAL ERROR]: function '{name}' {reason}")
+            }
+            Self::RecursiveFunction(name) => write!(
                 f,
-                "({left} {operator} {right})",
-                left = binary.left,
-                operator = binary.operator,
-                right = binary.right
+                "[EVAL ERROR]: function '{name}' can't call itself"
             ),
-            Expression::Unary(unary) => write!(
+            Self::DefinitionWithoutValue(name) => write!(
                 f,
-                "({operator}{expr})",
-                operator = unary.operator,
-                expr = unary.expr
+                "[EVAL ERROR]: definition of function '{name}' has no value"
             ),
-            Expression::Literal(literal) => write!(f, "{}", literal),
         }
     }
 }
 
+impl Error for EvalError {}
+
 impl Expression {
-    pub fn eval(self) -> f64 {
+    /// Creates a literal written as its value whose span is unknown, like the ones built by hand instead of
+    /// parsed.
+    pub fn literal(token: Token) -> Self {
+        Expression::Literal(LiteralExpr::new(token), NodeSpan::default())
+    }
+
+    /// Creates a variable whose span is unknown, like the ones built by hand instead of parsed.
+    pub fn variable(name: Symbol) -> Self {
+        Expression::Variable(name, NodeSpan::default())
+    }
+
+    /// Range of the source the expression was built from, covering all its tokens but the parentheses around it.
+    /// It is `None` if the expression wasn't parsed from spanned tokens.
+    pub fn span(&self) -> Option<Span> {
+        self.node_span().get()
+    }
+
+    pub(super) fn node_span(&self) -> NodeSpan {
         match self {
-            Expression::Binary(binary) => match binary.operator {
-                Token::Operator(operator) => match operator {
-                    Operator::Plus => binary.left.eval() + binary.right.eval(),
-                    Operator::Minus => binary.left.eval() - binary.right.eval(),
-                    Operator::Star => binary.left.eval() * binary.right.eval(),
-                    Operator::Slash => binary.left.eval() / binary.right.eval(),
-                },
-                _ => unreachable!(),
+            Expression::Binary(binary) => binary.span,
+            Expression::Unary(unary) | Expression::Postfix(unary) => unary.span,
+            Expression::Literal(_, span)
+            | Expression::Variable(_, span)
+            | Expression::Call { span, .. }
+            | Expression::AbsoluteValue { span, .. }
+            | Expression::Assign { span, .. }
+            | Expression::FunctionDef { span, .. } => *span,
+            Expression::CustomBinary { left, right, .. } => left.node_span().to(right.node_span()),
+            Expression::Conditional {
+                condition,
+                otherwise,
+                ..
+            } => condition.node_span().to(otherwise.node_span()),
+            Expression::Grouping(expr) => expr.node_span(),
+        }
+    }
+
+    /// Removes the groupings of the expression and its subexpressions, leaving the grouped expressions in their
+    /// place. The resulting tree is the same for sources only differing in redundant parentheses, like `(1 + 2)`
+    /// and `((1) + 2)`.
+    pub fn strip_groupings(mut self) -> Self {
+        let mut pending = vec![&mut self];
+
+        while let Some(expr) = pending.pop() {
+            while let Expression::Grouping(grouped) = expr {
+                let grouped = mem::replace(&mut **grouped, Expression::hole());
+                *expr = grouped;
+            }
+
+            pending.extend(expr.subexpressions_mut());
+        }
+
+        self
+    }
+
+    /// Direct subexpressions of the expression, like the operands of an operator.
+    fn subexpressions(&self) -> Vec<&Expression> {
+        match self {
+            Expression::Binary(BinaryExpr { left, right, .. })
+            | Expression::CustomBinary { left, right, .. } => vec![left, right],
+            Expression::Unary(unary) | Expression::Postfix(unary) => vec![&unary.expr],
+            Expression::Call { args, .. } => args.iter().collect(),
+            Expression::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => vec![condition, then, otherwise],
+            Expression::AbsoluteValue { expr, .. }
+            | Expression::Assign { value: expr, .. }
+            | Expression::FunctionDef { body: expr, .. }
+            | Expression::Grouping(expr) => vec![expr],
+            Expression::Literal(..) | Expression::Variable(..) => Vec::new(),
+        }
+    }
+
+    /// Same as `subexpressions`, but mutable.
+    fn subexpressions_mut(&mut self) -> Vec<&mut Expression> {
+        match self {
+            Expression::Binary(BinaryExpr { left, right, .. })
+            | Expression::CustomBinary { left, right, .. } => vec![left, right],
+            Expression::Unary(unary) | Expression::Postfix(unary) => vec![&mut unary.expr],
+            Expression::Call { args, .. } => args.iter_mut().collect(),
+            Expression::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => vec![condition, then, otherwise],
+            Expression::AbsoluteValue { expr, .. }
+            | Expression::Assign { value: expr, .. }
+            | Expression::FunctionDef { body: expr, .. }
+            | Expression::Grouping(expr) => vec![expr],
+            Expression::Literal(..) | Expression::Variable(..) => Vec::new(),
+        }
+    }
+
+    /// Takes the direct subexpressions out of the expression, leaving holes in their place.
+    fn take_subexpressions(&mut self) -> Vec<Expression> {
+        self.subexpressions_mut()
+            .into_iter()
+            .map(|expr| mem::replace(expr, Expression::hole()))
+            .collect()
+    }
+
+    /// Copy of the expression with holes in place of its subexpressions, which are left to be filled.
+    fn shallow_clone(&self) -> Self {
+        let hole = || Box::new(Expression::hole());
+
+        match self {
+            Expression::Binary(binary) => Expression::Binary(BinaryExpr {
+                left: hole(),
+                operator: binary.operator,
+                right: hole(),
+                span: binary.span,
+            }),
+            Expression::Unary(unary) | Expression::Postfix(unary) => {
+                let unary = UnaryExpr {
+                    operator: unary.operator,
+                    expr: hole(),
+                    span: unary.span,
+                };
+
+                match self {
+                    Expression::Unary(_) => Expression::Unary(unary),
+                    _ => Expression::Postfix(unary),
+                }
+            }
+            Expression::Literal(literal, span) => Expression::Literal(literal.clone(), *span),
+            Expression::CustomBinary { operator, .. } => Expression::CustomBinary {
+                operator: *operator,
+                left: hole(),
+                right: hole(),
             },
-            Expression::Unary(unary) => match unary.operator {
-                Token::Operator(operator) => match operator {
-                    Operator::Minus => unary.expr.eval() * (-1.0),
-                    _ => unreachable!(),
-                },
-                _ => unreachable!(),
+            Expression::Call { name, args, span } => Expression::Call {
+                name: *name,
+                args: args.iter().map(|_| Expression::hole()).collect(),
+                span: *span,
+            },
+            Expression::AbsoluteValue { span, .. } => Expression::AbsoluteValue {
+                expr: hole(),
+                span: *span,
+            },
+            Expression::Variable(name, span) => Expression::Variable(*name, *span),
+            Expression::Conditional { .. } => Expression::Conditional {
+                condition: hole(),
+                then: hole(),
+                otherwise: hole(),
             },
-            Expression::Literal(number) => match number {
-                Token::Number(n) => n,
-                _ => unreachable!(),
+            Expression::Assign { name, span, .. } => Expression::Assign {
+                name: *name,
+                value: hole(),
+                span: *span,
+            },
+            Expression::FunctionDef {
+                name, params, span, ..
+            } => Expression::FunctionDef {
+                name: *name,
+                params: params.clone(),
+                body: hole(),
+                span: *span,
+            },
+            Expression::Grouping(_) => Expression::Grouping(hole()),
+        }
+    }
+
+    /// Whether both expressions are the same, leaving out their subexpressions. Spans are ignored, like in
+    /// `NodeSpan`.
+    fn same_node(&self, other: &Expression) -> bool {
+        match (self, other) {
+            (Expression::Binary(binary), Expression::Binary(other)) => {
+                binary.operator == other.operator
+            }
+            (Expression::Unary(unary), Expression::Unary(other))
+            | (Expression::Postfix(unary), Expression::Postfix(other)) => {
+                unary.operator == other.operator
+            }
+            (Expression::Literal(literal, _), Expression::Literal(other, _)) => literal == other,
+            (
+                Expression::CustomBinary { operator, .. },
+                Expression::CustomBinary {
+                    operator: other, ..
+                },
+            ) => operator == other,
+            (
+                Expression::Call { name, args, .. },
+                Expression::Call {
+                    name: other_name,
+                    args: other_args,
+                    ..
+                },
+            ) => name == other_name && args.len() == other_args.len(),
+            (Expression::Variable(name, _), Expression::Variable(other, _))
+            | (Expression::Assign { name, .. }, Expression::Assign { name: other, .. }) => {
+                name == other
+            }
+            (
+                Expression::FunctionDef { name, params, .. },
+                Expression::FunctionDef {
+                    name: other_name,
+                    params: other_params,
+                    ..
+                },
+            ) => name == other_name && params == other_params,
+            (Expression::AbsoluteValue { .. }, Expression::AbsoluteValue { .. })
+            | (Expression::Conditional { .. }, Expression::Conditional { .. })
+            | (Expression::Grouping(_), Expression::Grouping(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Leaf left in place of a subexpression taken out, which is never evaluated nor displayed.
+    fn hole() -> Self {
+        Expression::literal(Token::Eof(Position::default()))
+    }
+
+    /// Evaluates the expression without any variable.
+    pub fn eval(&self) -> Result<Value, EvalError> {
+        self.eval_with(&Environment::new())
+    }
+
+    /// Evaluates the expression as a statement, binding the value of an assignment or the function of a
+    /// definition in `env`. Definitions have no value, so they give `None`.
+    pub fn execute(&self, env: &mut Environment) -> Result<Option<Value>, EvalError> {
+        match self {
+            Expression::Assign { name, value, .. } => {
+                let value = value.eval_with(env)?;
+                env.define(name.as_str(), value.clone());
+
+                Ok(Some(value))
+            }
+            Expression::FunctionDef {
+                name, params, body, ..
+            } => {
+                // Functions can't reach themselves through the functions they call, so calling them always ends
+                let mut pending = body.called_functions();
+                let mut visited = HashSet::new();
+
+                while let Some(called) = pending.pop() {
+                    if called == *name {
+                        return Err(EvalError::RecursiveFunction(*name));
+                    }
+
+                    if let Some(function) = env.function(called).filter(|_| visited.insert(called))
+                    {
+                        pending.extend(function.body.called_functions());
+                    }
+                }
+
+                env.set_function(
+                    name.as_str(),
+                    UserFunction::new(params.clone(), (**body).clone()),
+                );
+
+                Ok(None)
+            }
+            expr => expr.eval_with(env).map(Some),
+        }
+    }
+
+    /// Names of the functions called anywhere in the expression.
+    fn called_functions(&self) -> Vec<Symbol> {
+        let mut pending = vec![self];
+        let mut names = Vec::new();
+
+        while let Some(expr) = pending.pop() {
+            if let Expression::Call { name, .. } = expr {
+                names.push(*name);
+            }
+
+            pending.extend(expr.subexpressions());
+        }
+
+        names
+    }
+
+    /// Evaluates the expression taking the values of its variables from `env`.
+    ///
+    /// Environment is not modified, so an assignment just evaluates to its value. Use `execute` to bind it.
+    ///
+    /// Expressions are walked with an explicit stack of steps instead of recursion, so evaluating a deep
+    /// expression, like a long chain of operators, can't exhaust the call stack.
+    pub fn eval_with(&self, env: &Environment) -> Result<Value, EvalError> {
+        let mut steps = vec![EvalStep::Eval(self)];
+        let mut values = Vec::new();
+
+        while let Some(step) = steps.pop() {
+            match step {
+                // Steps are popped in reverse order, so operands are pushed after the operation applied to them,
+                // and the leftmost one is pushed last to be evaluated first
+                EvalStep::Eval(expr) => match expr {
+                    // Right side of boolean operators is only evaluated if the left one doesn't give the result
+                    Expression::Binary(binary)
+                        if matches!(
+                            binary.operator,
+                            Token::Operator(Operator::And | Operator::Or)
+                        ) =>
+                    {
+                        steps.extend([EvalStep::ShortCircuit(expr), EvalStep::Eval(&binary.left)])
+                    }
+                    Expression::Binary(binary) => steps.extend([
+                        EvalStep::Apply(expr),
+                        EvalStep::Eval(&binary.right),
+                        EvalStep::Eval(&binary.left),
+                    ]),
+                    Expression::CustomBinary { left, right, .. } => steps.extend([
+                        EvalStep::Apply(expr),
+                        EvalStep::Eval(right),
+                        EvalStep::Eval(left),
+                    ]),
+                    Expression::Unary(UnaryExpr { expr: operand, .. })
+                    | Expression::Postfix(UnaryExpr { expr: operand, .. })
+                    | Expression::AbsoluteValue { expr: operand, .. } => {
+                        steps.extend([EvalStep::Apply(expr), EvalStep::Eval(operand)])
+                    }
+                    Expression::Call { args, .. } => {
+                        steps.push(EvalStep::Apply(expr));
+                        steps.extend(args.iter().rev().map(EvalStep::Eval));
+                    }
+                    Expression::Literal(literal, _) => {
+                        let value = number(literal, env.options().precision);
+
+                        if !value.is_finite()
+                            && literal.is_numeral()
+                            && !env.options().allow_non_finite
+                        {
+                            return Err(EvalError::NonFiniteResult {
+                                operation: literal.token,
+                                value: value.to_f64(),
+                            });
+                        }
+
+                        values.push(value)
+                    }
+                    Expression::Variable(name, _) => {
+                        values.push(env.get(*name).ok_or(EvalError::UnknownVariable(*name))?)
+                    }
+                    Expression::Assign { value, .. } | Expression::Grouping(value) => {
+                        steps.push(EvalStep::Eval(value))
+                    }
+                    Expression::FunctionDef { name, .. } => {
+                        return Err(EvalError::DefinitionWithoutValue(*name))
+                    }
+                    // Only the branch taken is evaluated, so the other one can't fail
+                    Expression::Conditional { condition, .. } => {
+                        steps.extend([EvalStep::Branch(expr), EvalStep::Eval(condition)])
+                    }
+                },
+                EvalStep::ShortCircuit(expr) => {
+                    if let Expression::Binary(binary) = expr {
+                        let left = pop_operand(&mut values).is_true();
+
+                        match (&binary.operator, left) {
+                            (Token::Operator(Operator::And), false) => {
+                                values.push(Value::from_bool(false))
+                            }
+                            (Token::Operator(Operator::Or), true) => {
+                                values.push(Value::from_bool(true))
+                            }
+                            _ => {
+                                steps.extend([EvalStep::Apply(expr), EvalStep::Eval(&binary.right)])
+                            }
+                        }
+                    }
+                }
+                EvalStep::Branch(expr) => {
+                    if let Expression::Conditional {
+                        then, otherwise, ..
+                    } = expr
+                    {
+                        // Any value but zero is true, even NaN
+                        let branch = match pop_operand(&mut values).is_true() {
+                            true => then,
+                            false => otherwise,
+                        };
+
+                        steps.push(EvalStep::Eval(branch));
+                    }
+                }
+                EvalStep::Apply(expr) => {
+                    let (operation, operands) = operation(expr);
+
+                    // Operands are still on top of the values stack, so a non-finite result is only reported by
+                    // the operation producing it, rather than by every one it spreads through
+                    let finite_operands = values[values.len() - operands..]
+                        .iter()
+                        .all(|value| value.is_finite());
+
+                    let value = match expr {
+                        // Left side was already taken by the short circuit, so the result is the right side
+                        Expression::Binary(binary)
+                            if matches!(
+                                binary.operator,
+                                Token::Operator(Operator::And | Operator::Or)
+                            ) =>
+                        {
+                            Value::from_bool(pop_operand(&mut values).is_true())
+                        }
+                        Expression::Binary(binary) => {
+                            let right = pop_operand(&mut values);
+                            let left = pop_operand(&mut values);
+                            let ordering = left.compare(&right);
+
+                            match binary.operator {
+                                Token::Operator(operator) => match operator {
+                                    // Infinite or NaN results would spread silently through the rest of the
+                                    // expression, so dividing by zero fails right away
+                                    Operator::Slash | Operator::DoubleSlash | Operator::Percent
+                                        if right.is_zero() =>
+                                    {
+                                        return Err(EvalError::DivisionByZero)
+                                    }
+                                    Operator::Plus
+                                    | Operator::Minus
+                                    | Operator::Star
+                                    | Operator::Slash
+                                    | Operator::DoubleSlash
+                                    | Operator::Percent
+                                    | Operator::Caret => {
+                                        arithmetic(operator, left, right, env.options())?
+                                    }
+                                    // Comparisons are `1` when true and `0` when false, so results are still numbers
+                                    Operator::Less => {
+                                        Value::from_bool(ordering == Some(Ordering::Less))
+                                    }
+                                    Operator::LessEqual => Value::from_bool(matches!(
+                                        ordering,
+                                        Some(Ordering::Less | Ordering::Equal)
+                                    )),
+                                    Operator::Greater => {
+                                        Value::from_bool(ordering == Some(Ordering::Greater))
+                                    }
+                                    Operator::GreaterEqual => Value::from_bool(matches!(
+                                        ordering,
+                                        Some(Ordering::Greater | Ordering::Equal)
+                                    )),
+                                    Operator::EqualEqual => {
+                                        Value::from_bool(ordering == Some(Ordering::Equal))
+                                    }
+                                    Operator::BangEqual => {
+                                        Value::from_bool(ordering != Some(Ordering::Equal))
+                                    }
+                                    Operator::LessLess
+                                    | Operator::GreaterGreater
+                                    | Operator::Ampersand
+                                    | Operator::BitOr => {
+                                        bitwise(operator, left, right, env.options())?
+                                    }
+                                    _ => unreachable!(),
+                                },
+                                _ => unreachable!(),
+                            }
+                        }
+                        Expression::CustomBinary { operator, .. } => {
+                            let right = pop_operand(&mut values);
+                            let left = pop_operand(&mut values);
+
+                            Value::Float((operator.eval)(left.to_f64(), right.to_f64())?)
+                        }
+                        Expression::Unary(unary) => match unary.operator {
+                            Token::Operator(Operator::Minus) => {
+                                negate(pop_operand(&mut values), env.options())?
+                            }
+                            Token::Operator(Operator::Plus) => pop_operand(&mut values),
+                            Token::Operator(Operator::Not) => {
+                                Value::from_bool(!pop_operand(&mut values).is_true())
+                            }
+                            _ => unreachable!(),
+                        },
+                        Expression::Postfix(postfix) => match postfix.operator {
+                            // Percents divide evenly like any other division, so `200%` is the integer `2`
+                            Token::Operator(Operator::PercentSuffix) => arithmetic(
+                                Operator::Slash,
+                                pop_operand(&mut values),
+                                Value::Integer(100),
+                                env.options(),
+                            )?,
+                            // Angles are converted to the unit of the angle mode, so `sin(90deg)` is `1` in both
+                            Token::Operator(Operator::Degrees) => {
+                                let angle = pop_operand(&mut values);
+
+                                match env.options().angle_mode {
+                                    AngleMode::Radians => Value::Float(angle.to_f64().to_radians()),
+                                    AngleMode::Degrees => angle,
+                                }
+                            }
+                            Token::Operator(Operator::Radians) => {
+                                let angle = pop_operand(&mut values);
+
+                                match env.options().angle_mode {
+                                    AngleMode::Radians => angle,
+                                    AngleMode::Degrees => Value::Float(angle.to_f64().to_degrees()),
+                                }
+                            }
+                            Token::Operator(Operator::Bang) => factorial(pop_operand(&mut values))?,
+                            _ => unreachable!(),
+                        },
+                        Expression::AbsoluteValue { .. } => {
+                            functions::absolute_value(pop_operand(&mut values), env.options())?
+                        }
+                        Expression::Call { name, args, .. } => {
+                            let args = values.split_off(values.len() - args.len());
+
+                            // Functions defined by the user take precedence over the registered ones
+                            match env.function(*name) {
+                                Some(function) => function.call(*name, &args, env)?,
+                                None => env.registry().call(*name, &args, env.options())?,
+                            }
+                        }
+                        // Leaves are evaluated right away, while assignments and groupings are their value, so they
+                        // are never applied
+                        Expression::Literal(..)
+                        | Expression::Variable(..)
+                        | Expression::Assign { .. }
+                        | Expression::FunctionDef { .. }
+                        | Expression::Grouping(_)
+                        | Expression::Conditional { .. } => unreachable!(),
+                    };
+
+                    if !value.is_finite() && finite_operands && !env.options().allow_non_finite {
+                        return Err(EvalError::NonFiniteResult {
+                            operation,
+                            value: value.to_f64(),
+                        });
+                    }
+
+                    values.push(value);
+                }
+            }
+        }
+
+        Ok(pop_operand(&mut values))
+    }
+}
+
+/// Token of the operation applied by the expression and the number of operand values it takes from the values
+/// stack. Boolean operators only take their right side, since the left one is taken by their short circuit.
+fn operation(expr: &Expression) -> (Token, usize) {
+    match expr {
+        Expression::Binary(binary)
+            if matches!(
+                binary.operator,
+                Token::Operator(Operator::And | Operator::Or)
+            ) =>
+        {
+            (binary.operator, 1)
+        }
+        Expression::Binary(binary) => (binary.operator, 2),
+        Expression::CustomBinary { operator, .. } => (Token::CustomOperator(operator.symbol), 2),
+        Expression::Unary(unary) | Expression::Postfix(unary) => (unary.operator, 1),
+        Expression::Call { name, args, .. } => (Token::Identifier(*name), args.len()),
+        Expression::AbsoluteValue { .. } => (Token::Pipe, 1),
+        // Leaves, assignments, definitions, groupings and conditionals are never applied
+        Expression::Literal(..)
+        | Expression::Variable(..)
+        | Expression::Assign { .. }
+        | Expression::FunctionDef { .. }
+        | Expression::Grouping(_)
+        | Expression::Conditional { .. } => unreachable!(),
+    }
+}
+
+/// Pending work while evaluating an expression.
+enum EvalStep<'a> {
+    /// Evaluates the expression, leaving its value on top of the values stack.
+    Eval(&'a Expression),
+    /// Applies the operation of the expression to the values of its operands, which are on top of the values
+    /// stack.
+    Apply(&'a Expression),
+    /// Evaluates one of the branches of the conditional expression, depending on the value of its condition,
+    /// which is on top of the values stack.
+    Branch(&'a Expression),
+    /// Evaluates the right side of the boolean operator, unless the value of its left side, which is on top of
+    /// the values stack, already gives the result.
+    ShortCircuit(&'a Expression),
+}
+
+/// Value of a number literal. With `Precision::Big`, numbers are decimals built from their lexeme, so no digit is
+/// lost, except for the ones with no decimal notation, like `inf`, which are floats.
+fn number(literal: &LiteralExpr, precision: Precision) -> Value {
+    match (literal.token, precision) {
+        #[cfg(feature = "big-decimal")]
+        (Token::Integer(value), Precision::Big) => {
+            Value::Decimal(Decimal::parse(&literal.lexeme).unwrap_or_else(|| Decimal::from(value)))
+        }
+        #[cfg(feature = "big-decimal")]
+        (Token::Float(value), Precision::Big) => {
+            Decimal::parse(&literal.lexeme).map_or(Value::Float(value), Value::Decimal)
+        }
+        (Token::Integer(value), _) => Value::Integer(value),
+        (Token::Float(value), _) => Value::Float(value),
+        (Token::Constant(constant), _) => Value::Float(constant.value()),
+        _ => unreachable!(),
+    }
+}
+
+/// Applies an arithmetic operator to operands which are decimals or integers, with at least one decimal. It is
+/// `None` if the result can't be computed exactly, like for `2 ^ 0.5`, so it must be computed over floats.
+#[cfg(feature = "big-decimal")]
+fn decimal_arithmetic(operator: Operator, left: &Value, right: &Value) -> Option<Value> {
+    if !matches!(
+        (left, right),
+        (Value::Decimal(_), _) | (_, Value::Decimal(_))
+    ) {
+        return None;
+    }
+
+    let (left, right) = (left.to_decimal()?, right.to_decimal()?);
+
+    let value = match operator {
+        Operator::Plus => left.add(&right),
+        Operator::Minus => left.sub(&right),
+        Operator::Star => left.mul(&right),
+        Operator::Slash => left.div(&right)?,
+        Operator::DoubleSlash => left.div_floor(&right)?,
+        Operator::Percent => left.div_rem(&right)?.1,
+        Operator::Caret => left.pow(right.to_i64()?)?,
+        _ => unreachable!(),
+    };
+
+    Some(Value::Decimal(value))
+}
+
+/// Applies an arithmetic operator to operands whose divisor, if any, is not zero.
+///
+/// Operations over integers are exact, unless their result is not an integer, like `1 / 3` or `2 ^ -1`, or it
+/// doesn't fit in an `i64`, which is handled as told by the `options`. Operations over decimals are exact, except
+/// for divisions, truncated to `DIVISION_DIGITS`. Otherwise, they are computed over floats.
+fn arithmetic(
+    operator: Operator,
+    left: Value,
+    right: Value,
+    options: &EvalOptions,
+) -> Result<Value, EvalError> {
+    #[cfg(feature = "big-decimal")]
+    if let Some(value) = decimal_arithmetic(operator, &left, &right) {
+        return Ok(value);
+    }
+
+    if let (Value::Integer(left), Value::Integer(right)) = (&left, &right) {
+        let (left, right) = (*left, *right);
+        let exact = match operator {
+            Operator::Plus => left.checked_add(right),
+            Operator::Minus => left.checked_sub(right),
+            Operator::Star => left.checked_mul(right),
+            // Only `i64::MIN / -1` has no remainder, since its result overflows
+            Operator::Slash => match left.checked_rem(right) {
+                Some(0) => left.checked_div(right),
+                Some(_) => return Ok(Value::Float(left as f64 / right as f64)),
+                None => None,
             },
+            // Truncated quotient is one above the floor when operands have different signs and there is remainder
+            Operator::DoubleSlash => left.checked_div(right).map(|quotient| {
+                match left % right != 0 && (left < 0) != (right < 0) {
+                    true => quotient - 1,
+                    false => quotient,
+                }
+            }),
+            // Remainder of `i64::MIN % -1` is `0`, even if the quotient it comes from overflows
+            Operator::Percent => Some(left.wrapping_rem(right)),
+            Operator::Caret if right < 0 => {
+                return Ok(Value::Float((left as f64).powf(right as f64)))
+            }
+            Operator::Caret => u32::try_from(right)
+                .ok()
+                .and_then(|exponent| left.checked_pow(exponent)),
+            _ => unreachable!(),
+        };
+
+        match (exact, options.integer_overflow) {
+            (Some(value), _) => return Ok(Value::Integer(value)),
+            (None, IntegerOverflow::Fail) => {
+                return Err(EvalError::IntegerOverflow {
+                    operation: Token::Operator(operator),
+                })
+            }
+            (None, IntegerOverflow::Promote) => {}
         }
     }
+
+    let (left, right) = (left.to_f64(), right.to_f64());
+
+    let value = match operator {
+        Operator::Plus => left + right,
+        Operator::Minus => left - right,
+        Operator::Star => left * right,
+        Operator::Slash => left / right,
+        Operator::DoubleSlash => (left / right).floor(),
+        // Remainder of the truncated division, so its sign is the one of the dividend: `-7 % 3` is `-1`
+        Operator::Percent => left % right,
+        Operator::Caret => left.powf(right),
+        _ => unreachable!(),
+    };
+
+    Ok(Value::Float(value))
+}
+
+/// Negates the value, which overflows for `i64::MIN`.
+fn negate(value: Value, options: &EvalOptions) -> Result<Value, EvalError> {
+    match value {
+        Value::Integer(integer) => match (integer.checked_neg(), options.integer_overflow) {
+            (Some(negated), _) => Ok(Value::Integer(negated)),
+            (None, IntegerOverflow::Promote) => Ok(Value::Float(-(integer as f64))),
+            (None, IntegerOverflow::Fail) => Err(EvalError::IntegerOverflow {
+                operation: Token::Operator(Operator::Minus),
+            }),
+        },
+        Value::Float(float) => Ok(Value::Float(-float)),
+        #[cfg(feature = "big-decimal")]
+        Value::Decimal(decimal) => Ok(Value::Decimal(decimal.neg())),
+    }
+}
+
+/// Computes the factorial of a non-negative integer, which is an integer up to `20!`, the biggest one fitting in an
+/// `i64`.
+fn factorial(value: Value) -> Result<Value, EvalError> {
+    let value = value.to_f64();
+
+    // Non-finite values have no integer part, so they fail too
+    if value < 0.0 || value.fract() != 0.0 || !value.is_finite() {
+        return Err(EvalError::InvalidFactorial(value));
+    }
+
+    // Largest factorial fitting in a `f64`, since `171!` is already infinity
+    if value > 170.0 {
+        return Err(EvalError::FactorialOverflow(value));
+    }
+
+    if value <= 20.0 {
+        return Ok(Value::Integer((2..=value as i64).product()));
+    }
+
+    Ok(Value::Float(
+        (2..=value as u64).map(|factor| factor as f64).product(),
+    ))
+}
+
+/// Applies a bitwise operator to operands which must be integers fitting in an `i64`.
+///
+/// Left shifts losing bits, like `1 << 63`, overflow like any other operation over integers, which is handled as
+/// told by the `options`.
+fn bitwise(
+    operator: Operator,
+    left: Value,
+    right: Value,
+    options: &EvalOptions,
+) -> Result<Value, EvalError> {
+    let integer = |value: &Value| {
+        value.to_i64().ok_or(EvalError::InvalidBitwiseOperand {
+            operator,
+            value: value.to_f64(),
+        })
+    };
+
+    let (left_integer, right_integer) = (integer(&left)?, integer(&right)?);
+
+    let shift_amount = || {
+        u32::try_from(right_integer)
+            .ok()
+            .filter(|amount| *amount < i64::BITS)
+            .ok_or(EvalError::InvalidShift(right.to_f64()))
+    };
+
+    let result = match operator {
+        Operator::Ampersand => left_integer & right_integer,
+        Operator::BitOr => left_integer | right_integer,
+        Operator::LessLess => {
+            let amount = shift_amount()?;
+
+            // Shifting back gives the same integer only if no bits (nor the sign) were lost
+            match (
+                left_integer
+                    .checked_shl(amount)
+                    .filter(|shifted| shifted >> amount == left_integer),
+                options.integer_overflow,
+            ) {
+                (Some(shifted), _) => shifted,
+                (None, IntegerOverflow::Promote) => {
+                    return Ok(Value::Float(left_integer as f64 * 2f64.powi(amount as i32)))
+                }
+                (None, IntegerOverflow::Fail) => {
+                    return Err(EvalError::IntegerOverflow {
+                        operation: Token::Operator(operator),
+                    })
+                }
+            }
+        }
+        // Shift is arithmetic, so negative numbers keep their sign: `-8 >> 1` is `-4`
+        Operator::GreaterGreater => left_integer >> shift_amount()?,
+        _ => unreachable!(),
+    };
+
+    Ok(Value::Integer(result))
+}
+
+fn pop_operand(values: &mut Vec<Value>) -> Value {
+    values
+        .pop()
+        .expect("operands should be evaluated before the operation applied to them")
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -65,11 +1278,14 @@ pub struct BinaryExpr {
     left: Box<Expression>,
     operator: Token,
     right: Box<Expression>,
+    span: NodeSpan,
 }
 
 impl BinaryExpr {
+    /// Creates a binary expression, whose span covers both operands.
     pub fn new(left: Expression, operator: Token, right: Expression) -> Self {
         Self {
+            span: left.node_span().to(right.node_span()),
             left: Box::new(left),
             right: Box::new(right),
             operator,
@@ -81,24 +1297,85 @@ impl BinaryExpr {
 pub struct UnaryExpr {
     operator: Token,
     expr: Box<Expression>,
+    span: NodeSpan,
 }
 
 impl UnaryExpr {
+    /// Creates a unary expression whose operator span is unknown, so its span is unknown too.
     pub fn new(operator: Token, expr: Expression) -> Self {
+        Self::spanned(operator, NodeSpan::default(), expr)
+    }
+
+    /// Creates a unary expression whose span covers both the operator and the operand.
+    pub fn spanned(operator: Token, operator_span: NodeSpan, expr: Expression) -> Self {
         Self {
+            span: operator_span.to(expr.node_span()),
             operator,
             expr: Box::new(expr),
         }
     }
 }
 
-pub struct LiteralExpr(Token);
+/// Number or constant, keeping the `lexeme` it was written with, so printing it back reproduces the source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiteralExpr {
+    token: Token,
+    lexeme: Box<str>,
+}
+
+impl LiteralExpr {
+    /// Creates a literal written as its token is displayed, like the ones built by hand instead of parsed.
+    pub fn new(token: Token) -> Self {
+        Self::written(token, &token.to_string())
+    }
+
+    /// Creates a literal written with the given lexeme, which is taken from the source of its token.
+    pub fn written(token: Token, lexeme: &str) -> Self {
+        Self {
+            token,
+            lexeme: lexeme.into(),
+        }
+    }
+
+    /// Whether the literal is written with digits, unlike the words `inf` and `nan`, so a non-finite value means
+    /// it overflows. Literals built by hand are written as displayed, so they are never numerals if non-finite.
+    fn is_numeral(&self) -> bool {
+        self.lexeme
+            .starts_with(|c: char| c.is_ascii_digit() || c == '.')
+    }
+}
+
+impl fmt::Display for LiteralExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.lexeme)
+    }
+}
 
 #[cfg(test)]
 mod ast_expressions_tests {
-    use crate::tokenizer::tokens::{Operator, Token};
+    use crate::{
+        ast::{
+            environment::Environment,
+            functions::Arity,
+            options::{EvalOptions, IntegerOverflow},
+            parser::Parser,
+        },
+        tokenizer::{
+            builder::TokenizerBuilder,
+            parser::Tokenizer,
+            symbol::Symbol,
+            tokens::{Operator, Span, Token},
+        },
+    };
+
+    use super::{
+        super::operators::{Associativity, CustomOperator, Precedence},
+        super::value::Value,
+        factorial, BinaryExpr, EvalError, Expression, UnaryExpr,
+    };
 
-    use super::{BinaryExpr, Expression, UnaryExpr};
+    #[cfg(feature = "big-decimal")]
+    use crate::ast::options::Precision;
 
     const LEFT_NUMBER: f64 = 10.0;
     const RIGHT_NUMBER: f64 = 5.0;
@@ -111,6 +1388,9 @@ mod ast_expressions_tests {
             Token::Operator(Operator::Minus),
             Token::Operator(Operator::Star),
             Token::Operator(Operator::Slash),
+            Token::Operator(Operator::DoubleSlash),
+            Token::Operator(Operator::Caret),
+            Token::Operator(Operator::Percent),
         ];
 
         // `expected_results` are based on the `operators` slice order.
@@ -121,47 +1401,1132 @@ mod ast_expressions_tests {
             (LEFT_NUMBER - RIGHT_NUMBER),
             (LEFT_NUMBER * RIGHT_NUMBER),
             (LEFT_NUMBER / RIGHT_NUMBER),
+            (LEFT_NUMBER / RIGHT_NUMBER).floor(),
+            LEFT_NUMBER.powf(RIGHT_NUMBER),
+            (LEFT_NUMBER % RIGHT_NUMBER),
         ];
 
-        for (i, op) in operators.into_iter().enumerate() {
+        for (i, op) in operators.iter().enumerate() {
             let binary_expr = Expression::Binary(BinaryExpr::new(
-                Expression::Literal(Token::Number(LEFT_NUMBER)),
-                op.clone(),
-                Expression::Literal(Token::Number(RIGHT_NUMBER)),
+                Expression::literal(Token::float(LEFT_NUMBER)),
+                *op,
+                Expression::literal(Token::float(RIGHT_NUMBER)),
             ));
 
             // Act & Assert
-            assert_eq!(binary_expr.eval(), expected_results[i], "should evaluate binary expression based on its operator and return the corresponding result")
+            assert_eq!(binary_expr.eval().unwrap(), expected_results[i], "should evaluate binary expression based on its operator and return the corresponding result")
         }
     }
 
     #[test]
-    fn test_unary_expr_eval() {
-        // Notice currently unary expressions just supports `minus` operator in front of the number
-        // to negate it. So the test is hardcoded in order to evaluate just this case
-        // Once new unary operators were added, this test should be improved to cover all possible cases
-
+    fn test_floor_division_eval() {
         // Arrange
-        let operator = Token::Operator(Operator::Minus);
-        let unary_expr = Expression::Unary(UnaryExpr::new(
-            operator,
-            Expression::Literal(Token::Number(LEFT_NUMBER)),
-        ));
+        // Each pair of operands is paired with its expected result
+        let cases = [
+            (7.0, 2.0, 3.0),
+            (-7.0, 2.0, -4.0),
+            (7.0, -2.0, -4.0),
+            (-7.0, -2.0, 3.0),
+        ];
 
-        // Act & Assert
-        assert_eq!(unary_expr.eval(), -LEFT_NUMBER, "should evauluate unary expression based on its operator and return the corresponding result")
+        for (left, right, expected_result) in cases {
+            let binary_expr = Expression::Binary(BinaryExpr::new(
+                Expression::literal(Token::float(left)),
+                Token::Operator(Operator::DoubleSlash),
+                Expression::literal(Token::float(right)),
+            ));
+
+            // Act & Assert
+            assert_eq!(
+                binary_expr.eval().unwrap(),
+                expected_result,
+                "should round division result towards negative infinity"
+            )
+        }
     }
 
     #[test]
-    fn test_literal_expr_eval() {
+    fn test_modulo_eval() {
         // Arrange
-        let literal_expr = Expression::Literal(Token::Number(LEFT_NUMBER));
+        // Each pair of operands is paired with its expected result. Notice sign of the result is the one of the
+        // left operand, and modulo by zero is an error, the same way division by zero is
+        let cases = [
+            (7.0, 3.0, Ok(Value::Float(1.0))),
+            (-7.0, 3.0, Ok(Value::Float(-1.0))),
+            (7.0, -3.0, Ok(Value::Float(1.0))),
+            (5.5, 2.0, Ok(Value::Float(1.5))),
+            (7.0, 0.0, Err(EvalError::DivisionByZero)),
+        ];
 
-        // Act & Assert
-        assert_eq!(
-            literal_expr.eval(),
-            LEFT_NUMBER,
-            "should evaluate literal expression and just unwraps its value and return it"
-        )
+        for (left, right, expected_result) in cases {
+            let binary_expr = Expression::Binary(BinaryExpr::new(
+                Expression::literal(Token::float(left)),
+                Token::Operator(Operator::Percent),
+                Expression::literal(Token::float(right)),
+            ));
+
+            // Act
+            let result = binary_expr.eval();
+
+            // Assert
+            assert_eq!(
+                result, expected_result,
+                "should compute the remainder of the truncated division"
+            )
+        }
+    }
+
+    #[test]
+    fn test_unary_expr_eval() {
+        // Arrange
+        // Each unary operator is paired with its expected result
+        let cases = [
+            (Operator::Minus, -LEFT_NUMBER),
+            (Operator::Plus, LEFT_NUMBER),
+        ];
+
+        for (operator, expected_result) in cases {
+            let unary_expr = Expression::Unary(UnaryExpr::new(
+                Token::Operator(operator),
+                Expression::literal(Token::float(LEFT_NUMBER)),
+            ));
+
+            // Act & Assert
+            assert_eq!(unary_expr.eval().unwrap(), expected_result, "should evauluate unary expression based on its operator and return the corresponding result")
+        }
+    }
+
+    #[test]
+    fn test_literal_expr_eval() {
+        // Arrange
+        let literal_expr = Expression::literal(Token::float(LEFT_NUMBER));
+
+        // Act & Assert
+        assert_eq!(
+            literal_expr.eval().unwrap(),
+            LEFT_NUMBER,
+            "should evaluate literal expression and just unwraps its value and return it"
+        )
+    }
+
+    /// Parses the given source into an expression.
+    fn parse(source: &str) -> Expression {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        Parser::new(tokens.iter().copied()).program().unwrap()
+    }
+
+    #[test]
+    fn test_variable_expr_eval_with() {
+        // Arrange
+        let mut env = Environment::new();
+        env.define("x", 3.0);
+        env.define("y", 1.0);
+
+        let expr = parse("x * 2 + y");
+
+        // Act & Assert
+        assert_eq!(
+            expr.eval_with(&env).unwrap(),
+            7.0,
+            "should take the values of the variables from the environment"
+        )
+    }
+
+    #[test]
+    fn test_variable_expr_eval_fails_if_unknown() {
+        // Arrange
+        let mut env = Environment::new();
+        env.define("x", 3.0);
+
+        // Act & Assert
+        assert_eq!(
+            parse("X + 1").eval_with(&env).unwrap_err(),
+            EvalError::UnknownVariable(Symbol::intern("X")),
+            "should tell variables apart by case"
+        );
+
+        assert_eq!(
+            parse("x + z").eval().unwrap_err().to_string(),
+            "[EVAL ERROR]: unknown variable 'x'",
+            "should fail naming the variable when evaluating without environment"
+        );
+
+        assert_eq!(
+            parse("x + z").eval_with(&env).unwrap_err().to_string(),
+            "[EVAL ERROR]: unknown variable 'z'",
+            "should fail naming the variable missing from the environment"
+        )
+    }
+
+    #[test]
+    fn test_factorial_eval() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("5!", 120.0),
+            ("(2+3)!", 120.0),
+            ("3!!", 720.0),
+            ("0!", 1.0),
+            ("-3!", -6.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval().unwrap(),
+                expected_result,
+                "should compute the factorial of non-negative integers"
+            )
+        }
+
+        assert!(
+            parse("170!").eval().unwrap().is_finite(),
+            "should compute the biggest factorial fitting in a float"
+        )
+    }
+
+    #[test]
+    fn test_factorial_eval_fails() {
+        // Arrange
+        // Each source is paired with its expected error
+        let cases = [
+            ("(-3)!", EvalError::InvalidFactorial(-3.0)),
+            ("2.5!", EvalError::InvalidFactorial(2.5)),
+            ("171!", EvalError::FactorialOverflow(171.0)),
+        ];
+
+        for (source, expected_error) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval().unwrap_err(),
+                expected_error,
+                "should reject factorials of negative, non-integer and too big numbers"
+            )
+        }
+
+        assert_eq!(
+            parse("(-1)!").eval().unwrap_err().to_string(),
+            "[EVAL ERROR]: factorial is only defined for non-negative integers, found -1",
+            "should describe the number whose factorial can't be computed"
+        )
+    }
+
+    #[test]
+    fn test_comparison_eval() {
+        // Arrange
+        // Each source is paired with its expected result. Notice floats are compared exactly, so rounding errors
+        // make some comparisons false
+        let cases = [
+            ("2^10 == 1024", 1.0),
+            ("1 + 1 < 3", 1.0),
+            ("3 <= 2", 0.0),
+            ("2 >= 2", 1.0),
+            ("-1 > 0", 0.0),
+            ("1 != 2", 1.0),
+            ("(1 < 2) < 3", 1.0),
+            ("0.1 + 0.2 == 0.3", 0.0),
+            ("0.1 + 0.2 != 0.3", 1.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval().unwrap(),
+                expected_result,
+                "should evaluate comparisons to 1 when true and 0 when false"
+            )
+        }
+    }
+
+    #[test]
+    fn test_conditional_eval() {
+        // Arrange
+        let mut env = Environment::new();
+        env.define("x", -3.0);
+
+        // Each source is paired with its expected result
+        let cases = [
+            ("x > 0 ? x : -x", 3.0),
+            ("x < 0 ? 1 : x < 1 ? 2 : 3", 1.0),
+            ("x ? 1 : 2", 1.0),
+            ("0 ? 1 : 2", 2.0),
+            ("(x > 0 ? 10 : 20) + 1", 21.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval_with(&env).unwrap(),
+                expected_result,
+                "should evaluate the first branch if condition is not zero, or the second one otherwise"
+            )
+        }
+    }
+
+    #[test]
+    fn test_conditional_eval_only_taken_branch() {
+        // Arrange
+        // Branches not taken would fail if they were evaluated
+        let sources = [
+            "1 ? 2 : (-1)!",
+            "0 ? unbound : 2",
+            "1 ? 2 : 0 ? unbound : (-1)!",
+        ];
+
+        for source in sources {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval().unwrap(),
+                2.0,
+                "should not evaluate the branch which is not taken"
+            )
+        }
+    }
+
+    #[test]
+    fn test_absolute_value_eval() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [("|3 - 5| * 2", 4.0), ("|(|-2| - 5)|", 3.0), ("|2.5|", 2.5)];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval().unwrap(),
+                expected_result,
+                "should evaluate to the absolute value of the expression between bars"
+            )
+        }
+    }
+
+    #[test]
+    fn test_absolute_value_eval_ignores_abs_functions() {
+        // Arrange
+        let mut env = Environment::new();
+        let program = Parser::statements_from_source("abs(x) = x; abs(-3) + |-3|").unwrap();
+
+        // Act
+        let value = program.eval_with(&mut env);
+
+        // Assert
+        assert_eq!(
+            value,
+            Ok(Some(Value::Integer(0))),
+            "should not evaluate bars with a function defined by the user"
+        );
+
+        let mut replaced = Environment::new();
+        replaced
+            .registry_mut()
+            .register_fn("abs", Arity::Exact(1), |args| Ok(args[0].clone()));
+
+        assert_eq!(
+            parse("|-3|").eval_with(&replaced),
+            Ok(Value::Integer(3)),
+            "should not evaluate bars with a function replacing the built-in one"
+        )
+    }
+
+    #[test]
+    fn test_custom_operator_eval() {
+        // Arrange
+        let average = CustomOperator::new(
+            '@',
+            Precedence::Factor,
+            Associativity::Left,
+            |left, right| Ok((left + right) / 2.0),
+        );
+        let choose = CustomOperator::new('~', Precedence::Term, Associativity::Left, |n, k| {
+            let factorial = |value: f64| factorial(Value::Float(value)).map(|value| value.to_f64());
+            Ok(factorial(n)? / (factorial(k)? * factorial(n - k)?))
+        });
+
+        // Each source is paired with its expected result. Notice custom operators work over floats
+        let cases = [
+            ("1 @ 3 * 2", Ok(Value::Float(4.0))),
+            ("2 * 1 @ 3", Ok(Value::Float(2.5))),
+            ("5 ~ 2 @ 4", Ok(Value::Float(10.0))),
+            ("2 ~ 5", Err(EvalError::InvalidFactorial(-3.0))),
+        ];
+
+        for (source, expected_result) in cases {
+            let tokens = TokenizerBuilder::new()
+                .custom_operator('@')
+                .custom_operator('~')
+                .build(source)
+                .tokenize()
+                .unwrap();
+            let mut parser = Parser::new(tokens.iter().copied())
+                .custom_operator(average)
+                .custom_operator(choose);
+
+            // Act
+            let result = parser.program().unwrap().eval();
+
+            // Assert
+            assert_eq!(
+                result, expected_result,
+                "should evaluate custom operators with their registered function"
+            )
+        }
+    }
+
+    #[test]
+    fn test_spans() {
+        // Arrange
+        let expr = Parser::from_source("1 + 2 * 3").unwrap();
+
+        // Act
+        let Expression::Binary(sum) = &expr else {
+            panic!("should parse a sum")
+        };
+
+        // Assert
+        assert_eq!(
+            expr.span(),
+            Some(Span::new(0, 9)),
+            "should cover the whole sum"
+        );
+        assert_eq!(
+            sum.left.span(),
+            Some(Span::new(0, 1)),
+            "should cover the literal"
+        );
+        assert_eq!(
+            sum.right.span(),
+            Some(Span::new(4, 9)),
+            "should cover both operands of the multiplication"
+        );
+    }
+
+    #[test]
+    fn test_spans_of_every_node() {
+        // Arrange
+        // Each source is paired with the expected span of its whole expression
+        let cases = [
+            ("  -x ", Span::new(2, 4)),
+            ("3! + 1", Span::new(0, 6)),
+            ("--2%", Span::new(0, 4)),
+            ("max(1, 2) ", Span::new(0, 9)),
+            ("rand()", Span::new(0, 6)),
+            ("|x - 1|", Span::new(0, 7)),
+            ("2x", Span::new(0, 2)),
+            ("(1 + 2) * 3", Span::new(1, 11)),
+            ("a ? b : c", Span::new(0, 9)),
+        ];
+
+        for (source, expected_span) in cases {
+            // Act
+            let expr = Parser::from_source(source).unwrap();
+
+            // Assert
+            assert_eq!(
+                expr.span(),
+                Some(expected_span),
+                "should cover every token of the expression but the parentheses around it"
+            )
+        }
+
+        let program = Parser::statements_from_source("y = 2; x = y + 1").unwrap();
+
+        assert_eq!(
+            program.statements()[1].span(),
+            Some(Span::new(7, 16)),
+            "should cover the variable and the value of an assignment"
+        );
+    }
+
+    #[test]
+    fn test_spans_are_unknown_without_spanned_tokens() {
+        // Arrange
+        let expr = parse("1 + 2 * 3");
+
+        // Act & Assert
+        assert_eq!(
+            expr.span(),
+            None,
+            "should not know spans of expressions parsed from bare tokens"
+        );
+        assert_eq!(
+            expr,
+            Parser::from_source("1 + 2 * 3").unwrap(),
+            "should ignore spans when comparing expressions"
+        );
+    }
+
+    #[test]
+    fn test_implicit_multiplication_eval() {
+        // Arrange
+        let mut env = Environment::new();
+        env.define("x", 2.0);
+
+        // Each source is paired with its expected result
+        let cases = [
+            ("3x + 1", 7.0),
+            ("2pi", 2.0 * std::f64::consts::PI),
+            ("2sqrt(2x)", 4.0),
+            ("3(x + 1)", 9.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act
+            let result = parse(source).eval_with(&env).unwrap();
+
+            // Assert
+            assert!(
+                (result.to_f64() - expected_result).abs() < 1e-12,
+                "should multiply a number by the operand right after it"
+            )
+        }
+    }
+
+    #[test]
+    fn test_boolean_operators_eval() {
+        // Arrange
+        let mut env = Environment::new();
+        env.define("x", 5.0);
+
+        // Each source is paired with its expected result
+        let cases = [
+            ("1 or 0 and 0", 1.0),
+            ("x > 0 and x < 10", 1.0),
+            ("x > 0 and x > 10", 0.0),
+            ("2 and -3", 1.0),
+            ("0 or 0", 0.0),
+            ("not 0", 1.0),
+            ("not x", 0.0),
+            ("not x > 10", 1.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval_with(&env).unwrap(),
+                expected_result,
+                "should treat nonzero values as true and evaluate to 1 or 0"
+            )
+        }
+    }
+
+    #[test]
+    fn test_boolean_operators_short_circuit() {
+        // Arrange
+        let mut env = Environment::new();
+        env.define("x", 0.0);
+
+        // Right sides would fail if they were evaluated
+        let cases = [
+            ("x != 0 and (-1)! > 2", 0.0),
+            ("x == 0 or unbound", 1.0),
+            ("0 and 1 or 1", 1.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval_with(&env).unwrap(),
+                expected_result,
+                "should not evaluate the right side when the left one gives the result"
+            )
+        }
+
+        assert_eq!(
+            parse("1 and unbound").eval_with(&env),
+            Err(EvalError::UnknownVariable(Symbol::intern("unbound"))),
+            "should evaluate the right side when the left one doesn't give the result"
+        );
+    }
+
+    #[test]
+    fn test_division_by_zero_eval() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("1 / 0", Err(EvalError::DivisionByZero)),
+            ("0 / 0", Err(EvalError::DivisionByZero)),
+            ("1 / (2 - 2)", Err(EvalError::DivisionByZero)),
+            ("7 // 0", Err(EvalError::DivisionByZero)),
+            ("7 % -0", Err(EvalError::DivisionByZero)),
+            ("0 / 5", Ok(Value::Integer(0))),
+            ("1 / 4", Ok(Value::Float(0.25))),
+            ("0 ? 1 / 0 : 2", Ok(Value::Integer(2))),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval(),
+                expected_result,
+                "should only fail when dividing by zero"
+            )
+        }
+
+        assert_eq!(
+            parse("1 / 0").eval().unwrap_err().to_string(),
+            "[EVAL ERROR]: division by zero",
+            "should describe the division by zero"
+        )
+    }
+
+    #[test]
+    fn test_division_by_zero_in_deep_expression_eval() {
+        // Arrange
+        // The division is the deepest leaf of a long chain of additions
+        let source = format!("1 / 0{}", " + 1".repeat(10_000));
+
+        // Act
+        let result = parse(&source).eval();
+
+        // Assert
+        assert_eq!(
+            result,
+            Err(EvalError::DivisionByZero),
+            "should fail the whole expression when a nested division fails"
+        )
+    }
+
+    #[test]
+    fn test_deep_expression_eval() {
+        // Arrange
+        // Left leaning chain of 100 000 additions, like `((1 + 1) + 1) + ...`, built by hand since the parser limits
+        // the depth of expressions
+        let one = || Expression::literal(Token::integer(1));
+        let expr = (0..100_000).fold(one(), |left, _| {
+            Expression::Binary(BinaryExpr::new(
+                left,
+                Token::Operator(Operator::Plus),
+                one(),
+            ))
+        });
+
+        // Act
+        let value = expr.eval();
+        let display = expr.to_string();
+
+        // Assert
+        assert_eq!(
+            value,
+            Ok(Value::Integer(100_001)),
+            "should evaluate expressions deeper than the call stack"
+        );
+        assert_eq!(
+            display,
+            format!("{}1{}", "(".repeat(100_000), " + 1)".repeat(100_000)),
+            "should display expressions deeper than the call stack"
+        )
+    }
+
+    #[test]
+    fn test_deep_function_definition_eval() {
+        // Arrange
+        // Body of the function is a left leaning chain of 200 000 additions, which is kept by the defined function
+        let source = format!("f(x) = x{}; f(1)", " + 1".repeat(200_000));
+        let program = Parser::statements_from_source(&source).unwrap();
+
+        // Act
+        let value = program.eval();
+
+        // Assert
+        assert_eq!(
+            value,
+            Ok(Some(Value::Integer(200_001))),
+            "should define and call functions whose body is deeper than the call stack"
+        )
+    }
+
+    #[test]
+    fn test_deep_expression_clone_and_eq() {
+        // Arrange
+        // Left leaning chain of 100 000 subtractions, like `((1 - 1) - 1) - ...`, whose deepest leaf is the given one
+        let chain = |leaf| {
+            (0..100_000).fold(Expression::literal(leaf), |left, _| {
+                Expression::Binary(BinaryExpr::new(
+                    left,
+                    Token::Operator(Operator::Minus),
+                    Expression::literal(Token::integer(1)),
+                ))
+            })
+        };
+        let expr = chain(Token::integer(1));
+
+        // Act
+        let clone = expr.clone();
+
+        // Assert
+        // Expressions are compared with `assert!`, since a failing `assert_eq!` prints them with the recursive `Debug`
+        assert!(
+            clone == expr,
+            "should clone and compare expressions deeper than the call stack"
+        );
+        assert!(
+            expr != chain(Token::integer(2)),
+            "should tell apart deep expressions only differing in their deepest leaf"
+        )
+    }
+
+    #[test]
+    fn test_non_finite_result_eval() {
+        // Arrange
+        // Each source is paired with the operation expected to give a non-finite result
+        let cases = [
+            ("1e308 * 10", Token::Operator(Operator::Star)),
+            ("2 ^ 1024 - 1", Token::Operator(Operator::Caret)),
+            ("-1e308 - 1e308", Token::Operator(Operator::Minus)),
+            ("(-8) ^ 0.5", Token::Operator(Operator::Caret)),
+            ("exp(1000) + 1", Token::Identifier(Symbol::intern("exp"))),
+        ];
+
+        for (source, expected_operation) in cases {
+            // Act
+            let err = parse(source).eval().unwrap_err();
+
+            // Assert
+            assert!(
+                matches!(err, EvalError::NonFiniteResult { operation, .. } if operation == expected_operation),
+                "should report the operation giving a non-finite result from finite operands"
+            )
+        }
+
+        assert_eq!(
+            parse("1e308 * 10").eval().unwrap_err().to_string(),
+            "[EVAL ERROR]: operation '*' gives inf from finite operands",
+            "should describe the operation and its result"
+        )
+    }
+
+    #[test]
+    fn test_overflowing_literal_eval() {
+        // Arrange
+        let allowed = Environment::with_options(EvalOptions {
+            allow_non_finite: true,
+            ..EvalOptions::default()
+        });
+
+        for source in ["1e400", "-1e400 + 1", "2 * 99999e305"] {
+            // Act
+            let err = Parser::from_source(source).unwrap().eval().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err,
+                EvalError::NonFiniteResult {
+                    operation: Token::Float(f64::INFINITY),
+                    value: f64::INFINITY
+                },
+                "should report the literal of '{source}' overflowing to infinity"
+            )
+        }
+
+        assert_eq!(
+            Parser::from_source("1e400")
+                .unwrap()
+                .eval()
+                .unwrap_err()
+                .to_string(),
+            "[EVAL ERROR]: number literal overflows to inf",
+            "should describe the overflowing literal"
+        );
+        assert_eq!(
+            Parser::from_source("1e400").unwrap().eval_with(&allowed),
+            Ok(Value::Float(f64::INFINITY)),
+            "should give infinity for overflowing literals if non-finite results are allowed"
+        );
+        assert_eq!(
+            Parser::from_source("inf - 1").unwrap().eval(),
+            Ok(Value::Float(f64::INFINITY)),
+            "should not report the infinity keyword"
+        )
+    }
+
+    #[test]
+    fn test_non_finite_operands_eval() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("inf + 1", Value::Float(f64::INFINITY)),
+            ("-inf * 2", Value::Float(f64::NEG_INFINITY)),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval(),
+                Ok(expected_result),
+                "should not report non-finite results coming from non-finite operands"
+            )
+        }
+
+        assert!(
+            parse("nan * 0 + inf - inf").eval().unwrap().is_nan(),
+            "should let NaN spread through operations"
+        )
+    }
+
+    #[test]
+    fn test_non_finite_result_eval_allowed() {
+        // Arrange
+        let mut env = Environment::with_options(EvalOptions {
+            allow_non_finite: true,
+            ..EvalOptions::default()
+        });
+        env.define("x", 1e308);
+
+        // Act & Assert
+        assert_eq!(
+            parse("x * 10 + 1").eval_with(&env),
+            Ok(Value::Float(f64::INFINITY)),
+            "should return infinite results when they are allowed"
+        );
+
+        assert!(
+            parse("(-8) ^ 0.5 + 1").eval_with(&env).unwrap().is_nan(),
+            "should return NaN results when they are allowed"
+        );
+
+        let program = Parser::statements_from_source("f(y) = y * 10; f(x)").unwrap();
+
+        assert_eq!(
+            program.eval_with(&mut env),
+            Ok(Some(Value::Float(f64::INFINITY))),
+            "should keep allowing non-finite results inside function calls"
+        )
+    }
+
+    #[test]
+    fn test_integer_eval() {
+        // Arrange
+        // Each source is paired with its expected value
+        let cases = [
+            ("3 + 4", Value::Integer(7)),
+            ("6 / 3", Value::Integer(2)),
+            ("7 / 2", Value::Float(3.5)),
+            ("1 / 3 * 3", Value::Float(1.0)),
+            ("-7 // 2", Value::Integer(-4)),
+            ("7 // -2", Value::Integer(-4)),
+            ("-6 // 2", Value::Integer(-3)),
+            ("-7 % 3", Value::Integer(-1)),
+            ("2 ^ 10", Value::Integer(1024)),
+            ("2 ^ -1", Value::Float(0.5)),
+            ("4 ^ 0.5", Value::Float(2.0)),
+            ("1 + 0.5", Value::Float(1.5)),
+            ("2 * 1.5", Value::Float(3.0)),
+            ("2 * pi / pi", Value::Float(2.0)),
+            ("200%", Value::Integer(2)),
+            ("50%", Value::Float(0.5)),
+            ("5!", Value::Integer(120)),
+            ("21!", Value::Float(51090942171709440000.0)),
+            ("1 < 2", Value::Integer(1)),
+            ("3 == 3.0", Value::Integer(1)),
+            ("9007199254740993 == 9007199254740992", Value::Integer(0)),
+            ("sqrt(16)", Value::Float(4.0)),
+        ];
+
+        for (source, expected_value) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval(),
+                Ok(expected_value),
+                "should keep integers exact while operations over them give integers"
+            )
+        }
+    }
+
+    #[test]
+    fn test_integer_overflow_eval() {
+        // Arrange
+        let fail = Environment::with_options(EvalOptions {
+            integer_overflow: IntegerOverflow::Fail,
+            ..EvalOptions::default()
+        });
+
+        // Each source is paired with its overflowing operator and the value it is promoted to
+        let cases = [
+            ("2 ^ 63", Operator::Caret, 2f64.powi(63)),
+            ("9223372036854775807 + 1", Operator::Plus, 2f64.powi(63)),
+            (
+                "-9223372036854775807 - 2",
+                Operator::Minus,
+                -(2f64.powi(63)),
+            ),
+            (
+                "3037000500 * 3037000500",
+                Operator::Star,
+                3037000500f64.powi(2),
+            ),
+            (
+                "-(-9223372036854775807 - 1)",
+                Operator::Minus,
+                2f64.powi(63),
+            ),
+            (
+                "(-9223372036854775807 - 1) / -1",
+                Operator::Slash,
+                2f64.powi(63),
+            ),
+            ("1 << 63", Operator::LessLess, 2f64.powi(63)),
+            ("3 << 62", Operator::LessLess, 3.0 * 2f64.powi(62)),
+            ("-3 << 62", Operator::LessLess, -3.0 * 2f64.powi(62)),
+        ];
+
+        for (source, expected_operator, expected_value) in cases {
+            // Act
+            let promoted = parse(source).eval();
+            let failed = parse(source).eval_with(&fail);
+
+            // Assert
+            assert_eq!(
+                promoted,
+                Ok(Value::Float(expected_value)),
+                "should promote integers overflowing to floats by default"
+            );
+
+            assert_eq!(
+                failed,
+                Err(EvalError::IntegerOverflow {
+                    operation: Token::Operator(expected_operator)
+                }),
+                "should fail when integers overflow if asked to"
+            )
+        }
+
+        assert_eq!(
+            parse("-9223372036854775807 - 1").eval_with(&fail),
+            Ok(Value::Integer(i64::MIN)),
+            "should keep integers fitting in an i64"
+        );
+
+        assert_eq!(
+            (
+                parse("(-9223372036854775807 - 1) % -1").eval(),
+                parse("(-9223372036854775807 - 1) % -1").eval_with(&fail)
+            ),
+            (Ok(Value::Integer(0)), Ok(Value::Integer(0))),
+            "should give 0 as the remainder of the smallest integer by -1, whose quotient overflows"
+        );
+
+        assert_eq!(
+            parse("2.0 ^ 63").eval_with(&fail),
+            Ok(Value::Float(2f64.powi(63))),
+            "should not fail when operating over floats"
+        );
+
+        assert_eq!(
+            parse("2 ^ 63").eval_with(&fail).unwrap_err().to_string(),
+            "[EVAL ERROR]: operation '^' overflows a 64 bits integer",
+            "should describe the overflowing operation"
+        )
+    }
+
+    #[test]
+    #[cfg(feature = "big-decimal")]
+    fn test_big_precision_eval() {
+        // Arrange
+        let big = Environment::with_options(EvalOptions {
+            precision: Precision::Big,
+            ..EvalOptions::default()
+        });
+
+        // Each source is paired with the digits of its value
+        let cases = [
+            (
+                "12345678901234567890 + 1",
+                "12345678901234567891".to_string(),
+            ),
+            ("1 / 7", format!("0.{}14", "142857".repeat(8))),
+            ("-7 // 2", "-4".to_string()),
+            ("-7.5 % 2", "-1.5".to_string()),
+            ("1.5 ^ 2", "2.25".to_string()),
+            ("2 ^ -2", "0.25".to_string()),
+            ("1 / 1e-400", format!("1{}", "0".repeat(400))),
+            (
+                "abs(-12345678901234567890)",
+                "12345678901234567890".to_string(),
+            ),
+            (
+                "round(12345678901234567890.5)",
+                "12345678901234567891".to_string(),
+            ),
+            ("floor(-2.5) + sign(-0.1)", "-4".to_string()),
+            ("1_000.5 * 3", "3001.5".to_string()),
+            ("50%", "0.5".to_string()),
+            ("0.1 + 0.2 == 0.3", "1".to_string()),
+            (
+                "99999999999999999999 > 99999999999999999998",
+                "1".to_string(),
+            ),
+        ];
+
+        for (source, expected_digits) in cases {
+            // Act
+            let value = Parser::from_source(source)
+                .unwrap()
+                .eval_with(&big)
+                .unwrap();
+
+            // Assert
+            assert!(
+                matches!(value, Value::Decimal(_) | Value::Integer(_)),
+                "should not evaluate '{source}' over floats"
+            );
+            assert_eq!(
+                value.to_string(),
+                expected_digits,
+                "should evaluate '{source}' exactly"
+            )
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "big-decimal")]
+    fn test_big_precision_fallback_eval() {
+        // Arrange
+        let big = Environment::with_options(EvalOptions {
+            precision: Precision::Big,
+            ..EvalOptions::default()
+        });
+
+        // Each source is paired with the float it falls back to
+        let cases = [
+            ("sqrt(16) + 1", 5.0),
+            ("pi * 2", std::f64::consts::PI * 2.0),
+            ("4 ^ 0.5", 2.0),
+            ("inf - 1", f64::INFINITY),
+            ("1e-4000000000", 0.0),
+        ];
+
+        for (source, expected_value) in cases {
+            // Act
+            let value = Parser::from_source(source).unwrap().eval_with(&big);
+
+            // Assert
+            assert_eq!(
+                value,
+                Ok(Value::Float(expected_value)),
+                "should fall back to floats for '{source}', which can't be computed exactly or is too big"
+            )
+        }
+
+        assert_eq!(
+            Parser::from_source("1 / 0").unwrap().eval_with(&big),
+            Err(EvalError::DivisionByZero),
+            "should report division by zero of decimals"
+        );
+        assert!(
+            matches!(
+                Parser::from_source("1e900000000000000")
+                    .unwrap()
+                    .eval_with(&big),
+                Err(EvalError::NonFiniteResult { .. })
+            ),
+            "should report literals too big even for decimals"
+        )
+    }
+
+    #[test]
+    fn test_bitwise_operators_eval() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("1 << 20", Ok(Value::Integer(1048576))),
+            ("0xFF & 0x0F", Ok(Value::Integer(15))),
+            ("0xF0 bor 0x0F", Ok(Value::Integer(255))),
+            ("-8 >> 1", Ok(Value::Integer(-4))),
+            ("6 & 3 == 2", Ok(Value::Integer(0))),
+            ("(6 & 3) == 2", Ok(Value::Integer(1))),
+            ("4.0 << 1", Ok(Value::Integer(8))),
+            ("-1 << 63", Ok(Value::Integer(i64::MIN))),
+            (
+                "1.5 & 1",
+                Err(EvalError::InvalidBitwiseOperand {
+                    operator: Operator::Ampersand,
+                    value: 1.5,
+                }),
+            ),
+            (
+                "2.0^63 bor 1",
+                Err(EvalError::InvalidBitwiseOperand {
+                    operator: Operator::BitOr,
+                    value: 2f64.powi(63),
+                }),
+            ),
+            (
+                "1 << inf",
+                Err(EvalError::InvalidBitwiseOperand {
+                    operator: Operator::LessLess,
+                    value: f64::INFINITY,
+                }),
+            ),
+            ("1 << 64", Err(EvalError::InvalidShift(64.0))),
+            ("1 >> -1", Err(EvalError::InvalidShift(-1.0))),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).eval(),
+                expected_result,
+                "should apply bitwise operators to integers only"
+            )
+        }
+    }
+
+    #[test]
+    fn test_grouping_display() {
+        // Arrange
+        // Each source is paired with its expected display
+        let cases = [
+            ("(1+2)*3", "((1 + 2) * 3)"),
+            ("1 + 2 * 3", "(1 + (2 * 3))"),
+            ("(x)", "(x)"),
+            ("((1 + 2))", "((1 + 2))"),
+            ("(-x)!", "((-x)!)"),
+            ("max((1), 2)", "max((1), 2)"),
+        ];
+
+        for (source, expected_display) in cases {
+            // Act & Assert
+            assert_eq!(
+                parse(source).to_string(),
+                expected_display,
+                "should display the parentheses written in the source"
+            )
+        }
+    }
+
+    #[test]
+    fn test_strip_groupings() {
+        // Arrange
+        // Each source is paired with the one without redundant parentheses it should be equal to once stripped
+        let cases = [
+            ("(1+2)*3", "(1+2)*3"),
+            ("((2)) * (x)", "2 * x"),
+            ("-((1)) + max((1), (2 ^ 2))", "-1 + max(1, 2 ^ 2)"),
+            ("(a) ? ((b)) : (c)", "a ? b : c"),
+        ];
+
+        for (source, expected_source) in cases {
+            // Act
+            let expr = parse(source).strip_groupings();
+
+            // Assert
+            assert_eq!(
+                expr,
+                parse(expected_source).strip_groupings(),
+                "should remove every grouping of the expression"
+            )
+        }
+
+        let expr = parse("(1+2)*3");
+
+        assert_eq!(
+            expr.clone().strip_groupings().eval(),
+            expr.eval(),
+            "should not change the value of the expression"
+        );
+        assert_eq!(
+            expr.eval(),
+            Ok(Value::Integer(9)),
+            "should evaluate through groupings"
+        );
     }
 }
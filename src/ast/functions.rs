@@ -0,0 +1,1890 @@
+#![allow(dead_code)]
+
+use std::{cmp::Ordering, collections::HashMap, fmt, sync::Arc};
+
+use crate::tokenizer::{symbol::Symbol, tokens::Token};
+
+use super::{
+    environment::Environment,
+    expressions::{EvalError, Expression},
+    options::{AngleMode, EvalOptions, IntegerOverflow},
+    value::Value,
+};
+
+/// Number of arguments accepted by a function.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arity {
+    Exact(usize),
+    AtLeast(usize),
+    /// Between a minimum and a maximum number of arguments, both included, for functions with optional arguments.
+    Between(usize, usize),
+}
+
+impl Arity {
+    /// Whether a call with `count` arguments is valid.
+    pub fn accepts(&self, count: usize) -> bool {
+        match *self {
+            Arity::Exact(expected) => count == expected,
+            Arity::AtLeast(expected) => count >= expected,
+            Arity::Between(min, max) => (min..=max).contains(&count),
+        }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (prefix, count) = match *self {
+            Arity::Exact(count) => ("", count),
+            Arity::AtLeast(count) => ("at least ", count),
+            Arity::Between(min, max) => return write!(f, "between {min} and {max} arguments"),
+        };
+
+        let noun = if count == 1 { "argument" } else { "arguments" };
+
+        write!(f, "{prefix}{count} {noun}")
+    }
+}
+
+/// How a function relates to angles, which are in the unit of the `AngleMode` for the user but in radians for the
+/// function.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Angles {
+    /// Function not working with angles.
+    Plain,
+    /// Function taking angles, like `sin`, whose arguments are converted to radians.
+    Arguments,
+    /// Function giving an angle, like `asin`, whose result is converted from radians.
+    Result,
+}
+
+/// Check of the arguments of a function, giving the reason why they are not accepted.
+type ArgumentsCheck = fn(&[Value]) -> Result<(), String>;
+
+/// Exact implementation of a function over integers and decimals, like `abs`. It gives `None` for the arguments it
+/// doesn't handle, like floats, which are left to the implementation over floats.
+type ExactFunction = fn(&[Value]) -> Option<Value>;
+
+/// Function available in every expression, working over floats unless its exact implementation handles the
+/// arguments. It gives `None` for arguments out of its domain.
+struct Builtin {
+    name: &'static str,
+    arity: Arity,
+    angles: Angles,
+    /// Whether the function goes to infinity at some finite arguments, like `ln` at `0`. Those arguments are out of
+    /// its domain, unless non-finite results are allowed by `EvalOptions::allow_non_finite`.
+    poles: bool,
+    /// Check of the arguments giving the reason why they are not accepted, like bounds out of order for `clamp`, run
+    /// before the function.
+    check: Option<ArgumentsCheck>,
+    /// Implementation keeping integers and decimals exact, tried before the one over floats.
+    exact: Option<ExactFunction>,
+    function: fn(&[f64]) -> Option<f64>,
+}
+
+impl Builtin {
+    /// Calls the function, named `name`, over `args`, converting the angles it takes or gives from and to the unit
+    /// of the angle mode.
+    fn call(
+        &self,
+        name: Symbol,
+        args: &[Value],
+        options: &EvalOptions,
+    ) -> Result<Value, EvalError> {
+        if let Some(Err(reason)) = self.check.map(|check| check(args)) {
+            return Err(EvalError::InvalidArguments { name, reason });
+        }
+
+        if let Some(value) = self.exact.and_then(|exact| exact(args)) {
+            // Exact results over integers are only floats if they don't fit in an `i64`, like `abs` of `i64::MIN`
+            let overflows = matches!(value, Value::Float(_))
+                && args.iter().all(|arg| matches!(arg, Value::Integer(_)));
+
+            return match overflows && options.integer_overflow == IntegerOverflow::Fail {
+                true => Err(EvalError::IntegerOverflow {
+                    operation: Token::Identifier(name),
+                }),
+                false => Ok(value),
+            };
+        }
+
+        let args = args.iter().map(Value::to_f64).collect::<Vec<_>>();
+        let degrees = options.angle_mode == AngleMode::Degrees;
+
+        let value = match self.angles {
+            Angles::Arguments if degrees => {
+                let radians = args.iter().map(|arg| arg.to_radians()).collect::<Vec<_>>();
+                (self.function)(&radians)
+            }
+            _ => (self.function)(&args),
+        };
+
+        let at_pole = |value: f64| {
+            self.poles
+                && value.is_infinite()
+                && args.iter().all(|arg| arg.is_finite())
+                && !options.allow_non_finite
+        };
+
+        match value {
+            Some(value) if !at_pole(value) => match self.angles {
+                Angles::Result if degrees => Ok(Value::Float(value.to_degrees())),
+                _ => Ok(Value::Float(value)),
+            },
+            _ => Err(EvalError::DomainError { name, args }),
+        }
+    }
+}
+
+const BUILTINS: &[Builtin] = &[
+    Builtin {
+        name: "sqrt",
+        arity: Arity::Exact(1),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: None,
+        function: |args| non_negative(args[0]).then(|| args[0].sqrt()),
+    },
+    Builtin {
+        name: "cbrt",
+        arity: Arity::Exact(1),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: None,
+        function: |args| Some(args[0].cbrt()),
+    },
+    Builtin {
+        name: "root",
+        arity: Arity::Exact(2),
+        angles: Angles::Plain,
+        poles: true,
+        check: None,
+        exact: None,
+        function: |args| nth_root(args[0], args[1]),
+    },
+    Builtin {
+        name: "abs",
+        arity: Arity::Exact(1),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: Some(exact_abs),
+        function: |args| Some(args[0].abs()),
+    },
+    Builtin {
+        name: "sign",
+        arity: Arity::Exact(1),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: Some(exact_sign),
+        function: |args| Some(sign(args[0])),
+    },
+    // Sign of `-0.0` is negative, so `copysign(2, -0.0)` is `-2`
+    Builtin {
+        name: "copysign",
+        arity: Arity::Exact(2),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: None,
+        function: |args| Some(args[0].copysign(args[1])),
+    },
+    Builtin {
+        name: "min",
+        arity: Arity::AtLeast(2),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: Some(|args| select(args, Ordering::Less)),
+        function: |args| no_nan(args).then(|| args.iter().copied().fold(f64::INFINITY, f64::min)),
+    },
+    Builtin {
+        name: "max",
+        arity: Arity::AtLeast(2),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: Some(|args| select(args, Ordering::Greater)),
+        function: |args| {
+            no_nan(args).then(|| args.iter().copied().fold(f64::NEG_INFINITY, f64::max))
+        },
+    },
+    Builtin {
+        name: "clamp",
+        arity: Arity::Exact(3),
+        angles: Angles::Plain,
+        poles: false,
+        check: Some(ordered_bounds),
+        exact: Some(exact_clamp),
+        function: |args| no_nan(args).then(|| args[0].clamp(args[1], args[2])),
+    },
+    Builtin {
+        name: "floor",
+        arity: Arity::Between(1, 2),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: Some(|args| exact_rounding(args, Rounding::Floor)),
+        function: |args| round_digits(args, f64::floor),
+    },
+    Builtin {
+        name: "ceil",
+        arity: Arity::Between(1, 2),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: Some(|args| exact_rounding(args, Rounding::Ceil)),
+        function: |args| round_digits(args, f64::ceil),
+    },
+    Builtin {
+        name: "trunc",
+        arity: Arity::Between(1, 2),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: Some(|args| exact_rounding(args, Rounding::Trunc)),
+        function: |args| round_digits(args, f64::trunc),
+    },
+    // Halves are rounded away from zero, so `round(2.5)` is `3` and `round(-2.5)` is `-3`
+    Builtin {
+        name: "round",
+        arity: Arity::Between(1, 2),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: Some(|args| exact_rounding(args, Rounding::Round)),
+        function: |args| round_digits(args, f64::round),
+    },
+    Builtin {
+        name: "sin",
+        arity: Arity::Exact(1),
+        angles: Angles::Arguments,
+        poles: false,
+        check: None,
+        exact: None,
+        function: |args| Some(args[0].sin()),
+    },
+    Builtin {
+        name: "cos",
+        arity: Arity::Exact(1),
+        angles: Angles::Arguments,
+        poles: false,
+        check: None,
+        exact: None,
+        function: |args| Some(args[0].cos()),
+    },
+    Builtin {
+        name: "tan",
+        arity: Arity::Exact(1),
+        angles: Angles::Arguments,
+        poles: false,
+        check: None,
+        exact: None,
+        function: |args| Some(args[0].tan()),
+    },
+    Builtin {
+        name: "asin",
+        arity: Arity::Exact(1),
+        angles: Angles::Result,
+        poles: false,
+        check: None,
+        exact: None,
+        function: |args| unit_interval(args[0]).then(|| args[0].asin()),
+    },
+    Builtin {
+        name: "acos",
+        arity: Arity::Exact(1),
+        angles: Angles::Result,
+        poles: false,
+        check: None,
+        exact: None,
+        function: |args| unit_interval(args[0]).then(|| args[0].acos()),
+    },
+    Builtin {
+        name: "atan",
+        arity: Arity::Exact(1),
+        angles: Angles::Result,
+        poles: false,
+        check: None,
+        exact: None,
+        function: |args| Some(args[0].atan()),
+    },
+    Builtin {
+        name: "atan2",
+        arity: Arity::Exact(2),
+        angles: Angles::Result,
+        poles: false,
+        check: None,
+        exact: None,
+        function: |args| Some(args[0].atan2(args[1])),
+    },
+    Builtin {
+        name: "ln",
+        arity: Arity::Exact(1),
+        angles: Angles::Plain,
+        poles: true,
+        check: None,
+        exact: None,
+        function: |args| non_negative(args[0]).then(|| args[0].ln()),
+    },
+    Builtin {
+        name: "log10",
+        arity: Arity::Exact(1),
+        angles: Angles::Plain,
+        poles: true,
+        check: None,
+        exact: None,
+        function: |args| non_negative(args[0]).then(|| args[0].log10()),
+    },
+    Builtin {
+        name: "log2",
+        arity: Arity::Exact(1),
+        angles: Angles::Plain,
+        poles: true,
+        check: None,
+        exact: None,
+        function: |args| non_negative(args[0]).then(|| args[0].log2()),
+    },
+    Builtin {
+        name: "log",
+        arity: Arity::Exact(2),
+        angles: Angles::Plain,
+        poles: true,
+        check: None,
+        exact: None,
+        function: |args| {
+            let (value, base) = (args[0], args[1]);
+
+            // Base `1` is a pole, unless the value is `1` too, which has no logarithm in any base
+            match value < 0.0 || base <= 0.0 || (value == 1.0 && base == 1.0) {
+                true => None,
+                false => Some(value.log(base)),
+            }
+        },
+    },
+    Builtin {
+        name: "exp",
+        arity: Arity::Exact(1),
+        angles: Angles::Plain,
+        poles: false,
+        check: None,
+        exact: None,
+        function: |args| Some(args[0].exp()),
+    },
+];
+
+/// Function available in every expression, working over integers fitting in 64 bits, like `gcd`. It gives an exact
+/// integer, or a float if the result doesn't fit in one, and the reason why the arguments are not accepted, like
+/// "expects a non-negative n, found -1", for arguments out of its domain.
+struct IntegerBuiltin {
+    name: &'static str,
+    arity: Arity,
+    function: fn(&[i64]) -> Result<Value, String>,
+}
+
+impl IntegerBuiltin {
+    /// Calls the function, named `name`, over `args`, which must be integers. Results not fitting in an `i64` fail
+    /// with `IntegerOverflow::Fail`, like the operations over integers.
+    fn call(
+        &self,
+        name: Symbol,
+        args: &[Value],
+        options: &EvalOptions,
+    ) -> Result<Value, EvalError> {
+        let integers = args
+            .iter()
+            .map(|arg| {
+                arg.to_i64().ok_or(EvalError::NonIntegerArgument {
+                    name,
+                    value: arg.to_f64(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match (self.function)(&integers) {
+            Ok(Value::Float(_)) if options.integer_overflow == IntegerOverflow::Fail => {
+                Err(EvalError::IntegerOverflow {
+                    operation: Token::Identifier(name),
+                })
+            }
+            Ok(value) => Ok(value),
+            Err(reason) => Err(EvalError::InvalidArguments { name, reason }),
+        }
+    }
+}
+
+const INTEGER_BUILTINS: &[IntegerBuiltin] = &[
+    IntegerBuiltin {
+        name: "gcd",
+        arity: Arity::AtLeast(2),
+        function: |args| Ok(gcd(args)),
+    },
+    IntegerBuiltin {
+        name: "lcm",
+        arity: Arity::AtLeast(2),
+        function: |args| Ok(lcm(args)),
+    },
+    IntegerBuiltin {
+        name: "ncr",
+        arity: Arity::Exact(2),
+        function: ncr,
+    },
+    IntegerBuiltin {
+        name: "npr",
+        arity: Arity::Exact(2),
+        function: npr,
+    },
+];
+
+/// Whether the value is between `-1` and `1`, the domain of `asin` and `acos`. NaN is let through, so it gives NaN
+/// like any other operation over it.
+fn unit_interval(value: f64) -> bool {
+    value.abs() <= 1.0 || value.is_nan()
+}
+
+/// Applies the rounding `function` to the first argument at the decimal places given by the optional second one,
+/// which must be an integer. Negative places round to tens, hundreds and so on, so `round(1234, -2)` is `1200`.
+fn round_digits(args: &[f64], function: fn(f64) -> f64) -> Option<f64> {
+    let (value, digits) = (args[0], args.get(1).copied().unwrap_or(0.0));
+
+    // Non-finite places have no integer part, so they fail too
+    if digits.fract() != 0.0 || !digits.is_finite() {
+        return None;
+    }
+
+    // Powers of ten are exact for negative places, but not their inverses, so the value is divided by them instead
+    let factor = 10f64.powf(digits.abs());
+    let rounded = match digits >= 0.0 {
+        true => function(value * factor) / factor,
+        false => function(value / factor) * factor,
+    };
+
+    // Scaling only overflows for places beyond the precision of floats, where finite values have no digits left to
+    // round, or which are too big for any value
+    match rounded.is_finite() || !value.is_finite() {
+        true => Some(rounded),
+        false if digits >= 0.0 => Some(value),
+        false => Some(0.0f64.copysign(value)),
+    }
+}
+
+/// Absolute value of an integer or a decimal. It is a float for `i64::MIN`, whose absolute value doesn't fit in an
+/// `i64`.
+fn exact_abs(args: &[Value]) -> Option<Value> {
+    match &args[0] {
+        Value::Integer(value) => Some(
+            value
+                .checked_abs()
+                .map(Value::Integer)
+                .unwrap_or(Value::Float(value.unsigned_abs() as f64)),
+        ),
+        Value::Float(_) => None,
+        #[cfg(feature = "big-decimal")]
+        Value::Decimal(decimal) => Some(Value::Decimal(decimal.abs())),
+    }
+}
+
+/// Sign of an integer or a decimal as the integer `-1`, `0` or `1`.
+fn exact_sign(args: &[Value]) -> Option<Value> {
+    match args[0] {
+        Value::Float(_) => None,
+        _ => args[0]
+            .compare(&Value::Integer(0))
+            .map(|ordering| Value::Integer(ordering as i64)),
+    }
+}
+
+/// Rounding of a number to an integer, done by `floor`, `ceil`, `trunc` and `round`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Rounding {
+    Floor,
+    Ceil,
+    Trunc,
+    Round,
+}
+
+/// Rounds an integer or a decimal to an integer exactly, being integers already rounded. Rounding to a number of
+/// decimal places is left to floats.
+#[cfg_attr(not(feature = "big-decimal"), allow(unused_variables))]
+fn exact_rounding(args: &[Value], rounding: Rounding) -> Option<Value> {
+    match args {
+        [Value::Integer(value)] => Some(Value::Integer(*value)),
+        #[cfg(feature = "big-decimal")]
+        [Value::Decimal(decimal)] => Some(Value::Decimal(match rounding {
+            Rounding::Floor => decimal.floor(),
+            Rounding::Ceil => decimal.ceil(),
+            Rounding::Trunc => decimal.trunc(),
+            Rounding::Round => decimal.round(),
+        })),
+        _ => None,
+    }
+}
+
+/// Sign of the value as `-1`, `0` or `1`. Both `0.0` and `-0.0` are `0`, and NaN gives NaN like any other operation
+/// over it.
+fn sign(value: f64) -> f64 {
+    match value.partial_cmp(&0.0) {
+        Some(Ordering::Greater) => 1.0,
+        Some(Ordering::Less) => -1.0,
+        Some(Ordering::Equal) => 0.0,
+        None => f64::NAN,
+    }
+}
+
+/// Whether none of the values is NaN, which can't be ordered, so functions comparing their arguments, like `min`, are
+/// not defined for it.
+fn no_nan(args: &[f64]) -> bool {
+    !args.iter().any(|arg| arg.is_nan())
+}
+
+/// Checks the bounds `min` and `max` of `clamp`, which can't be out of order. NaN bounds are left to the function.
+fn ordered_bounds(args: &[Value]) -> Result<(), String> {
+    let (min, max) = (&args[1], &args[2]);
+
+    match min.compare(max) == Some(Ordering::Greater) {
+        true => Err(format!(
+            "expects min not greater than max, found min = {min} and max = {max}"
+        )),
+        false => Ok(()),
+    }
+}
+
+/// Argument which is `ordering` to every other one, like the smallest one for `Ordering::Less`, compared exactly and
+/// given unchanged. The first one is taken among equal arguments, and it is `None` if any of them is NaN, which is
+/// left to the function over floats.
+fn select(args: &[Value], ordering: Ordering) -> Option<Value> {
+    let mut selected = &args[0];
+
+    for arg in &args[1..] {
+        if arg.compare(selected)? == ordering {
+            selected = arg;
+        }
+    }
+
+    Some(selected.clone())
+}
+
+/// Value of `clamp` between its bounds, compared exactly and given unchanged, or the bound it is beyond. It is `None`
+/// if any of them is NaN, which is left to the function over floats.
+fn exact_clamp(args: &[Value]) -> Option<Value> {
+    let (value, min, max) = (&args[0], &args[1], &args[2]);
+
+    let clamped = match (value.compare(min)?, value.compare(max)?) {
+        (Ordering::Less, _) => min,
+        (_, Ordering::Greater) => max,
+        _ => value,
+    };
+
+    Some(clamped.clone())
+}
+
+/// Whether the value is not negative, the domain of logarithms and square roots. NaN is let through, like for
+/// `unit_interval`.
+fn non_negative(value: f64) -> bool {
+    value >= 0.0 || value.is_nan()
+}
+
+/// Root of the given integer `degree`, which can't be `0`. Odd roots keep the sign of negative values, so
+/// `root(-8, 3)` is `-2`, while even roots are only defined for non-negative values. Negative degrees give the
+/// inverse of the root, going to infinity at `0`.
+fn nth_root(value: f64, degree: f64) -> Option<f64> {
+    // Non-finite degrees have no integer part, so they fail too
+    if degree.fract() != 0.0 || !degree.is_finite() || degree == 0.0 {
+        return None;
+    }
+
+    let odd = degree % 2.0 != 0.0;
+    if !odd && !non_negative(value) {
+        return None;
+    }
+
+    // Square and cube roots are correctly rounded, unlike powers to their inverse degree
+    let root = match degree.abs() {
+        2.0 => value.abs().sqrt(),
+        3.0 => value.abs().cbrt(),
+        _ => value.abs().powf(degree.abs().recip()),
+    };
+
+    let root = if degree < 0.0 { root.recip() } else { root };
+
+    Some(root.copysign(if odd { value } else { 1.0 }))
+}
+
+/// Value of a non-negative integer, which is a float if it doesn't fit in an `i64`.
+fn natural(value: u128) -> Value {
+    i64::try_from(value)
+        .map(Value::Integer)
+        .unwrap_or(Value::Float(value as f64))
+}
+
+/// Greatest common divisor of two integers, by the Euclidean algorithm.
+fn gcd_pair(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+/// Greatest common divisor of the values, ignoring their signs. It is `0` only if all the values are `0`, which every
+/// integer divides.
+fn gcd(args: &[i64]) -> Value {
+    natural(u128::from(
+        args.iter()
+            .fold(0, |gcd, arg| gcd_pair(gcd, arg.unsigned_abs())),
+    ))
+}
+
+/// Least common multiple of the values, ignoring their signs. It is `0` if any of the values is `0`.
+fn lcm(args: &[i64]) -> Value {
+    // Only `0` is a multiple of `0`, which also has no common divisor to divide by
+    if args.contains(&0) {
+        return Value::Integer(0);
+    }
+
+    // The multiple is kept as the factors it is built from, so dividing each value by its common divisor with the
+    // multiple before multiplying stays exact even when the multiple doesn't fit in any integer
+    let mut factors: Vec<u64> = Vec::with_capacity(args.len());
+
+    for arg in args {
+        let factor = factors.iter().fold(arg.unsigned_abs(), |rest, factor| {
+            rest / gcd_pair(*factor, rest)
+        });
+
+        factors.push(factor);
+    }
+
+    factors
+        .iter()
+        .try_fold(1u128, |multiple, factor| {
+            multiple.checked_mul(u128::from(*factor))
+        })
+        .map(natural)
+        .unwrap_or_else(|| Value::Float(factors.iter().map(|factor| *factor as f64).product()))
+}
+
+/// Checks the number of items `n` and the number of them chosen `r` of a combination or permutation.
+fn choice(args: &[i64]) -> Result<(u64, u64), String> {
+    let (n, r) = (args[0], args[1]);
+
+    if n < 0 {
+        return Err(format!("expects a non-negative n, found {n}"));
+    }
+
+    if !(0..=n).contains(&r) {
+        return Err(format!(
+            "expects r between 0 and n, found r = {r} and n = {n}"
+        ));
+    }
+
+    Ok((n as u64, r as u64))
+}
+
+/// Product of the ratios between each factor and its divisor, applied in order so every partial product is an
+/// integer. It is exact while it fits in a `u128`, and computed over floats otherwise, stopping once it is infinite.
+/// Every ratio but the last is at least `2`, so the product overflows after a few thousand of them even for huge
+/// arguments.
+fn ratios_product(ratios: impl Iterator<Item = (u64, u64)> + Clone) -> Value {
+    let exact = ratios
+        .clone()
+        .try_fold(1u128, |product, (factor, divisor)| {
+            Some(product.checked_mul(u128::from(factor))? / u128::from(divisor))
+        });
+
+    exact.map(natural).unwrap_or_else(|| {
+        let mut product = 1.0f64;
+
+        for (factor, divisor) in ratios {
+            if product.is_infinite() {
+                break;
+            }
+
+            product = product * factor as f64 / divisor as f64;
+        }
+
+        Value::Float(product)
+    })
+}
+
+/// Number of combinations of `r` items out of `n`, by the multiplicative formula instead of factorials, which could
+/// overflow even for small results like `ncr(1000, 3)`.
+fn ncr(args: &[i64]) -> Result<Value, String> {
+    let (n, r) = choice(args)?;
+
+    // Choosing `r` items is choosing the `n - r` left out, and the fewer the factors the sooner it ends
+    let r = r.min(n - r);
+
+    Ok(ratios_product((1..=r).map(|i| (n - r + i, i))))
+}
+
+/// Number of permutations of `r` items out of `n`, as the product of the `r` biggest integers up to `n`.
+fn npr(args: &[i64]) -> Result<Value, String> {
+    let (n, r) = choice(args)?;
+
+    Ok(ratios_product(
+        (n - r + 1..=n).rev().map(|factor| (factor, 1)),
+    ))
+}
+
+/// Absolute value computed by the built-in `abs`, for the bars around an expression, which are syntax, so they
+/// can't be replaced by functions of the same name.
+pub fn absolute_value(value: Value, options: &EvalOptions) -> Result<Value, EvalError> {
+    let abs = BUILTINS
+        .iter()
+        .find(|builtin| builtin.name == "abs")
+        .expect("abs should be a built-in function");
+
+    abs.call(Symbol::intern("abs"), &[value], options)
+}
+
+/// Implementation of a function over its already evaluated arguments, whose number is already checked against the
+/// arity of the function. It is thread safe, so parsers holding a registry can be sent to other threads.
+pub type NativeFunction = Arc<dyn Fn(&[Value]) -> Result<Value, EvalError> + Send + Sync>;
+
+/// Code run by a function of a registry.
+#[derive(Clone)]
+enum Implementation {
+    Builtin(&'static Builtin),
+    IntegerBuiltin(&'static IntegerBuiltin),
+    Native(NativeFunction),
+}
+
+/// Function in a registry, whose implementation is missing if it was only declared for checking calls.
+#[derive(Clone)]
+struct RegisteredFunction {
+    arity: Arity,
+    implementation: Option<Implementation>,
+}
+
+/// Names, arities and implementations of the functions expressions can call, so the `Parser` can check calls
+/// before they are evaluated, and evaluation can run them.
+#[derive(Clone, Default)]
+pub struct FunctionRegistry {
+    functions: HashMap<Symbol, RegisteredFunction>,
+}
+
+impl FunctionRegistry {
+    /// Creates a registry without functions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with the built-in functions, like `sqrt` and `min`.
+    pub fn builtins() -> Self {
+        let mut registry = Self::new();
+
+        let builtins = BUILTINS.iter().map(|builtin| {
+            (
+                builtin.name,
+                builtin.arity,
+                Implementation::Builtin(builtin),
+            )
+        });
+        let integer_builtins = INTEGER_BUILTINS.iter().map(|builtin| {
+            (
+                builtin.name,
+                builtin.arity,
+                Implementation::IntegerBuiltin(builtin),
+            )
+        });
+
+        for (name, arity, implementation) in builtins.chain(integer_builtins) {
+            let function = RegisteredFunction {
+                arity,
+                implementation: Some(implementation),
+            };
+
+            registry.functions.insert(Symbol::intern(name), function);
+        }
+
+        registry
+    }
+
+    /// Declares the function `name` accepting `arity` arguments, replacing its previous registration if any. It
+    /// has no implementation, so it is only useful for checking calls while parsing, like the ones to functions
+    /// defined later by the user.
+    pub fn register(&mut self, name: &str, arity: Arity) {
+        let function = RegisteredFunction {
+            arity,
+            implementation: None,
+        };
+
+        self.functions.insert(Symbol::intern(name), function);
+    }
+
+    /// Registers the function `name` accepting `arity` arguments and implemented by `function`, replacing its
+    /// previous registration if any, even a built-in one.
+    pub fn register_fn(
+        &mut self,
+        name: &str,
+        arity: Arity,
+        function: impl Fn(&[Value]) -> Result<Value, EvalError> + Send + Sync + 'static,
+    ) {
+        let function = RegisteredFunction {
+            arity,
+            implementation: Some(Implementation::Native(Arc::new(function))),
+        };
+
+        self.functions.insert(Symbol::intern(name), function);
+    }
+
+    /// Arity of the function `name`, if registered.
+    pub fn arity(&self, name: Symbol) -> Option<Arity> {
+        self.functions.get(&name).map(|function| function.arity)
+    }
+
+    /// Calls the function `name` over the already evaluated `args`, as told by the `options` for the built-in
+    /// ones. It fails if the function has no implementation or doesn't accept the number of arguments.
+    pub fn call(
+        &self,
+        name: Symbol,
+        args: &[Value],
+        options: &EvalOptions,
+    ) -> Result<Value, EvalError> {
+        let function = self
+            .functions
+            .get(&name)
+            .ok_or(EvalError::UnknownFunction(name))?;
+
+        let implementation = function
+            .implementation
+            .as_ref()
+            .ok_or(EvalError::UnknownFunction(name))?;
+
+        if !function.arity.accepts(args.len()) {
+            return Err(EvalError::WrongArity {
+                name,
+                expected: function.arity,
+                found: args.len(),
+            });
+        }
+
+        match implementation {
+            Implementation::Builtin(builtin) => builtin.call(name, args, options),
+            Implementation::IntegerBuiltin(builtin) => builtin.call(name, args, options),
+            Implementation::Native(function) => function(args),
+        }
+    }
+}
+
+// Implementations can't be printed, so only the arities are
+impl fmt::Debug for FunctionRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.functions
+                    .iter()
+                    .map(|(name, function)| (name, function.arity)),
+            )
+            .finish()
+    }
+}
+
+/// Function defined by the user, like `f(x) = x * x + 1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserFunction {
+    pub params: Vec<Symbol>,
+    pub body: Expression,
+}
+
+impl UserFunction {
+    pub fn new(params: Vec<Symbol>, body: Expression) -> Self {
+        Self { params, body }
+    }
+
+    /// Calls the function, named `name`, over the already evaluated `args`.
+    ///
+    /// Arguments are bound to the parameters in a child scope of `env`, so the body can use global variables too,
+    /// but parameters take precedence over them.
+    pub fn call(
+        &self,
+        name: Symbol,
+        args: &[Value],
+        env: &Environment,
+    ) -> Result<Value, EvalError> {
+        if args.len() != self.params.len() {
+            return Err(EvalError::WrongArity {
+                name,
+                expected: Arity::Exact(self.params.len()),
+                found: args.len(),
+            });
+        }
+
+        let mut scope = env.child();
+
+        for (param, arg) in self.params.iter().zip(args) {
+            scope.define(param.as_str(), arg.clone());
+        }
+
+        self.body.eval_with(&scope)
+    }
+}
+
+#[cfg(test)]
+mod ast_functions_tests {
+    use std::f64::consts::{E, FRAC_PI_2, FRAC_PI_4};
+
+    use crate::{
+        ast::{
+            expressions::EvalError,
+            options::{AngleMode, EvalOptions, IntegerOverflow},
+            value::Value,
+        },
+        tokenizer::{symbol::Symbol, tokens::Token},
+    };
+
+    use super::{Arity, FunctionRegistry, BUILTINS, INTEGER_BUILTINS};
+
+    /// Built-in function names and their arguments paired with the expected result, so every built-in function has
+    /// a few cases checked by `assert_builtin_cases`.
+    const BUILTIN_CASES: &[(&str, &[f64], f64)] = &[
+        ("sqrt", &[16.0], 4.0),
+        ("sqrt", &[0.0], 0.0),
+        ("cbrt", &[27.0], 3.0),
+        ("cbrt", &[-8.0], -2.0),
+        ("root", &[16.0, 4.0], 2.0),
+        ("root", &[-8.0, 3.0], -2.0),
+        ("root", &[-32.0, 5.0], -2.0),
+        ("root", &[4.0, -2.0], 0.5),
+        ("root", &[f64::NAN, 2.0], f64::NAN),
+        ("abs", &[-2.5], 2.5),
+        ("abs", &[0.0], 0.0),
+        ("sign", &[-7.5], -1.0),
+        ("sign", &[0.001], 1.0),
+        ("sign", &[0.0], 0.0),
+        ("sign", &[-0.0], 0.0),
+        ("sign", &[f64::NEG_INFINITY], -1.0),
+        ("sign", &[f64::NAN], f64::NAN),
+        ("copysign", &[3.0, -1.0], -3.0),
+        ("copysign", &[-3.0, 2.0], 3.0),
+        ("copysign", &[2.0, -0.0], -2.0),
+        ("min", &[3.0, 1.0, 2.0], 1.0),
+        ("max", &[3.0, 1.0, 2.0], 3.0),
+        ("clamp", &[5.0, 0.0, 1.0], 1.0),
+        ("floor", &[-2.5], -3.0),
+        ("ceil", &[2.1], 3.0),
+        ("trunc", &[-2.7], -2.0),
+        ("round", &[2.5], 3.0),
+        ("sin", &[0.0], 0.0),
+        ("cos", &[0.0], 1.0),
+        ("tan", &[0.0], 0.0),
+        ("asin", &[0.0], 0.0),
+        ("acos", &[1.0], 0.0),
+        ("atan", &[0.0], 0.0),
+        ("atan2", &[0.0, 1.0], 0.0),
+        ("ln", &[1.0], 0.0),
+        ("log10", &[100.0], 2.0),
+        ("log2", &[8.0], 3.0),
+        ("log", &[27.0, 3.0], 3.0),
+        ("exp", &[0.0], 1.0),
+        ("gcd", &[12.0, 18.0, 24.0], 6.0),
+        ("gcd", &[-4.0, 6.0], 2.0),
+        ("gcd", &[0.0, 0.0], 0.0),
+        ("gcd", &[0.0, -5.0], 5.0),
+        ("lcm", &[4.0, 6.0], 12.0),
+        ("lcm", &[-4.0, 6.0, 10.0], 60.0),
+        ("lcm", &[0.0, 5.0], 0.0),
+        ("lcm", &[0.0, 0.0], 0.0),
+        ("lcm", &[5.0, 0.0, 0.0], 0.0),
+        ("ncr", &[5.0, 2.0], 10.0),
+        ("ncr", &[5.0, 0.0], 1.0),
+        ("ncr", &[0.0, 0.0], 1.0),
+        ("npr", &[5.0, 2.0], 20.0),
+        ("npr", &[5.0, 5.0], 120.0),
+        ("npr", &[5.0, 0.0], 1.0),
+    ];
+
+    /// Calls the built-in function `name` over `args`, evaluating with the default options.
+    fn call_builtin(name: &str, args: &[f64]) -> Result<f64, EvalError> {
+        let args = args.iter().copied().map(Value::Float).collect::<Vec<_>>();
+
+        FunctionRegistry::builtins()
+            .call(Symbol::intern(name), &args, &EvalOptions::default())
+            .map(|value| value.to_f64())
+    }
+
+    /// Transcendental functions, whose results are rounded differently across platforms, so they are only checked
+    /// within `1e-12`.
+    const TRANSCENDENTAL: &[&str] = &[
+        "cbrt", "root", "sin", "cos", "tan", "asin", "acos", "atan", "atan2", "ln", "log10",
+        "log2", "log", "exp",
+    ];
+
+    /// Checks that each built-in function name and its arguments give exactly the expected result, or within `1e-12`
+    /// for transcendental functions. NaN is expected to give NaN.
+    fn assert_builtin_cases(cases: &[(&str, &[f64], f64)]) {
+        for (name, args, expected_result) in cases {
+            // Act
+            let result = call_builtin(name, args);
+
+            // Assert
+            let result =
+                result.unwrap_or_else(|err| panic!("{name}({args:?}) should not fail: {err}"));
+            let approximate =
+                TRANSCENDENTAL.contains(name) && (result - expected_result).abs() < 1e-12;
+            assert!(
+                result == *expected_result
+                    || approximate
+                    || (result.is_nan() && expected_result.is_nan()),
+                "should compute {name}({args:?}) as {expected_result}, found {result}"
+            )
+        }
+    }
+
+    #[test]
+    fn test_call_builtins() {
+        // Arrange
+        // Each function name and its arguments are paired with the expected result
+        let cases = [
+            ("sqrt", vec![Value::Integer(16)], Value::Float(4.0)),
+            ("abs", vec![Value::Float(-2.5)], Value::Float(2.5)),
+            ("min", vec![3.into(), 1.into(), 2.into()], Value::Integer(1)),
+            ("max", vec![3.into(), 1.into(), 2.into()], Value::Integer(3)),
+            (
+                "max",
+                vec![Value::Integer(-1), Value::Float(-1.5)],
+                Value::Integer(-1),
+            ),
+            ("floor", vec![Value::Float(-2.5)], Value::Float(-3.0)),
+            ("ceil", vec![Value::Float(2.1)], Value::Float(3.0)),
+        ];
+        let registry = FunctionRegistry::builtins();
+
+        for (name, args, expected_result) in cases {
+            // Act
+            let result = registry.call(Symbol::intern(name), &args, &EvalOptions::default());
+
+            // Assert
+            assert_eq!(
+                result,
+                Ok(expected_result),
+                "should call the built-in function with the given arguments"
+            )
+        }
+    }
+
+    #[test]
+    fn test_builtin_cases() {
+        // Arrange, Act & Assert
+        assert_builtin_cases(BUILTIN_CASES);
+
+        let names = BUILTINS.iter().map(|builtin| builtin.name);
+        let integer_names = INTEGER_BUILTINS.iter().map(|builtin| builtin.name);
+
+        for builtin in names.chain(integer_names) {
+            assert!(
+                BUILTIN_CASES.iter().any(|(name, ..)| *name == builtin),
+                "should check some cases of the built-in function '{builtin}'"
+            )
+        }
+
+        assert!(
+            call_builtin("sign", &[-0.0]).unwrap().is_sign_positive(),
+            "should give the sign of both zeros as 0, not as -0"
+        )
+    }
+
+    #[test]
+    fn test_call_fails() {
+        // Arrange
+        let mut registry = FunctionRegistry::builtins();
+        registry.register("declared", Arity::Exact(1));
+
+        let sqrt = Symbol::intern("sqrt");
+        let max = Symbol::intern("max");
+        let unknown = Symbol::intern("foo");
+        let declared = Symbol::intern("declared");
+        let call = |name, args: &[f64]| {
+            let args = args.iter().copied().map(Value::Float).collect::<Vec<_>>();
+            registry.call(name, &args, &EvalOptions::default())
+        };
+
+        // Act & Assert
+        assert_eq!(
+            call(unknown, &[1.0]).unwrap_err(),
+            EvalError::UnknownFunction(unknown),
+            "should return error if function does not exist"
+        );
+
+        assert_eq!(
+            call(declared, &[1.0]).unwrap_err(),
+            EvalError::UnknownFunction(declared),
+            "should return error if function is only declared without implementation"
+        );
+
+        assert_eq!(
+            call(sqrt, &[1.0, 2.0]).unwrap_err(),
+            EvalError::WrongArity {
+                name: sqrt,
+                expected: Arity::Exact(1),
+                found: 2
+            },
+            "should return error if function does not accept the number of arguments"
+        );
+
+        assert_eq!(
+            call(max, &[]).unwrap_err().to_string(),
+            "[EVAL ERROR]: function 'max' expects at least 2 arguments, found 0",
+            "should describe the accepted number of arguments"
+        );
+
+        assert_eq!(
+            call(Symbol::intern("round"), &[1.0, 2.0, 3.0])
+                .unwrap_err()
+                .to_string(),
+            "[EVAL ERROR]: function 'round' expects between 1 and 2 arguments, found 3",
+            "should describe the range of arguments accepted by functions with optional arguments"
+        )
+    }
+
+    #[test]
+    fn test_registry() {
+        // Arrange
+        let mut registry = FunctionRegistry::builtins();
+
+        // Act
+        registry.register("sqrt", Arity::AtLeast(1));
+        registry.register("lerp", Arity::Exact(3));
+
+        // Assert
+        // Each function name is paired with its expected arity
+        let cases = [
+            ("min", Some(Arity::AtLeast(2))),
+            ("abs", Some(Arity::Exact(1))),
+            ("sqrt", Some(Arity::AtLeast(1))),
+            ("lerp", Some(Arity::Exact(3))),
+            ("foo", None),
+        ];
+
+        for (name, expected_arity) in cases {
+            assert_eq!(
+                registry.arity(Symbol::intern(name)),
+                expected_arity,
+                "should keep the last arity registered for each function"
+            )
+        }
+
+        assert_eq!(
+            FunctionRegistry::new().arity(Symbol::intern("sqrt")),
+            None,
+            "should not register built-in functions in an empty registry"
+        )
+    }
+
+    #[test]
+    fn test_register_fn() {
+        // Arrange
+        let mut registry = FunctionRegistry::builtins();
+        let double = Symbol::intern("double");
+        let sqrt = Symbol::intern("sqrt");
+
+        // Act
+        registry.register_fn("double", Arity::Exact(1), |args| {
+            Ok(Value::Float(args[0].to_f64() * 2.0))
+        });
+        registry.register_fn("sqrt", Arity::Exact(1), |_| Ok(Value::Integer(0)));
+
+        // Assert
+        assert_eq!(
+            registry.call(double, &[Value::Integer(21)], &EvalOptions::default()),
+            Ok(Value::Float(42.0)),
+            "should call the registered closure"
+        );
+
+        assert_eq!(
+            registry.call(sqrt, &[Value::Integer(16)], &EvalOptions::default()),
+            Ok(Value::Integer(0)),
+            "should override the built-in function"
+        );
+
+        assert_eq!(
+            registry
+                .call(double, &[], &EvalOptions::default())
+                .unwrap_err()
+                .to_string(),
+            "[EVAL ERROR]: function 'double' expects 1 argument, found 0",
+            "should check the arity of registered closures"
+        )
+    }
+
+    #[test]
+    fn test_call_trigonometric_functions() {
+        // Arrange
+        let registry = FunctionRegistry::builtins();
+        let degrees = EvalOptions {
+            angle_mode: AngleMode::Degrees,
+            ..EvalOptions::default()
+        };
+
+        // Each function name and its arguments are paired with the expected result in radians and in degrees
+        let cases = [
+            ("sin", vec![FRAC_PI_2], 1.0, FRAC_PI_2.to_radians().sin()),
+            ("sin", vec![90.0], 90f64.sin(), 1.0),
+            ("cos", vec![0.0], 1.0, 1.0),
+            ("cos", vec![60.0], 60f64.cos(), 0.5),
+            ("tan", vec![45.0], 45f64.tan(), 1.0),
+            ("asin", vec![1.0], FRAC_PI_2, 90.0),
+            ("acos", vec![0.5], 0.5f64.acos(), 60.0),
+            ("atan", vec![1.0], FRAC_PI_4, 45.0),
+            ("atan2", vec![1.0, -1.0], 3.0 * FRAC_PI_4, 135.0),
+        ];
+
+        for (name, args, expected_radians, expected_degrees) in cases {
+            let args = args.into_iter().map(Value::Float).collect::<Vec<_>>();
+
+            // Act
+            let radians = registry.call(Symbol::intern(name), &args, &EvalOptions::default());
+            let degrees = registry.call(Symbol::intern(name), &args, &degrees);
+
+            // Assert
+            assert!(
+                (radians.unwrap().to_f64() - expected_radians).abs() < 1e-12,
+                "should work with angles in radians by default"
+            );
+            assert!(
+                (degrees.unwrap().to_f64() - expected_degrees).abs() < 1e-12,
+                "should convert angles from and to degrees in degrees mode"
+            )
+        }
+    }
+
+    #[test]
+    fn test_call_fails_out_of_domain() {
+        // Arrange
+        let registry = FunctionRegistry::builtins();
+        let cases = [("asin", 2.0), ("asin", -1.5), ("acos", 1.0001)];
+
+        for (name, arg) in cases {
+            let name = Symbol::intern(name);
+
+            // Act
+            let result = registry.call(name, &[Value::Float(arg)], &EvalOptions::default());
+
+            // Assert
+            assert_eq!(
+                result,
+                Err(EvalError::DomainError {
+                    name,
+                    args: vec![arg]
+                }),
+                "should fail for arguments out of the domain of the function instead of giving NaN"
+            )
+        }
+
+        assert!(
+            registry
+                .call(
+                    Symbol::intern("asin"),
+                    &[Value::Float(f64::NAN)],
+                    &EvalOptions::default()
+                )
+                .unwrap()
+                .is_nan(),
+            "should give NaN for NaN arguments"
+        );
+
+        assert_eq!(
+            registry
+                .call(
+                    Symbol::intern("asin"),
+                    &[Value::Integer(2)],
+                    &EvalOptions::default()
+                )
+                .unwrap_err()
+                .to_string(),
+            "[EVAL ERROR]: function 'asin' is not defined for 2",
+            "should describe the arguments out of the domain"
+        )
+    }
+
+    #[test]
+    fn test_call_logarithms() {
+        // Arrange, Act & Assert
+        // Each function name and its arguments are paired with the expected result
+        assert_builtin_cases(&[
+            ("ln", &[E], 1.0),
+            ("ln", &[1.0], 0.0),
+            ("log10", &[1000.0], 3.0),
+            ("log2", &[0.125], -3.0),
+            ("log", &[8.0, 2.0], 3.0),
+            ("log", &[0.25, 0.5], 2.0),
+            ("exp", &[1.0], E),
+            ("exp", &[0.0], 1.0),
+            ("ln", &[f64::INFINITY], f64::INFINITY),
+            ("exp", &[f64::NEG_INFINITY], 0.0),
+        ]);
+    }
+
+    #[test]
+    fn test_call_roots() {
+        // Arrange
+        let values = [-1000.0, -27.0, -2.0, -0.125, 0.0, 0.5, 2.0, 10.0, 12345.678];
+        let degrees = [-3.0, -2.0, 1.0, 2.0, 3.0, 4.0, 5.0, 7.0];
+
+        for value in values {
+            for degree in degrees {
+                // Act
+                let result = call_builtin("root", &[value, degree]);
+
+                // Assert
+                let even_of_negative = degree % 2.0 == 0.0 && value < 0.0;
+                let at_pole = value == 0.0 && degree < 0.0;
+
+                match result {
+                    Ok(root) if !even_of_negative && !at_pole => assert!(
+                        (root.powf(degree) - value).abs() <= value.abs() * 1e-12,
+                        "should give a root of {value} whose power to {degree} is back the value, found {root}"
+                    ),
+                    result => assert!(
+                        matches!(result, Err(EvalError::DomainError { .. }))
+                            && (even_of_negative || at_pole),
+                        "should only fail for even roots of negative values and at poles, found {result:?} for \
+                         root({value}, {degree})"
+                    ),
+                }
+            }
+        }
+
+        for value in values {
+            // Act
+            let (sqrt, cbrt) = (
+                call_builtin("sqrt", &[value]),
+                call_builtin("cbrt", &[value]),
+            );
+
+            // Assert
+            assert_eq!(
+                sqrt.is_ok(),
+                value >= 0.0,
+                "should take square roots of non-negative values only"
+            );
+
+            let cbrt = cbrt.unwrap();
+            assert!(
+                (cbrt.powi(3) - value).abs() <= value.abs() * 1e-12,
+                "should give a cube root of {value} whose cube is back the value, found {cbrt}"
+            )
+        }
+    }
+
+    #[test]
+    fn test_call_roots_fails() {
+        // Arrange
+        // Each function name and its arguments are out of the domain of the function
+        let cases: [(&str, &[f64]); 7] = [
+            ("sqrt", &[-1.0]),
+            ("sqrt", &[f64::NEG_INFINITY]),
+            ("root", &[-16.0, 4.0]),
+            ("root", &[8.0, 0.0]),
+            ("root", &[8.0, 2.5]),
+            ("root", &[8.0, f64::INFINITY]),
+            ("root", &[8.0, f64::NAN]),
+        ];
+
+        for (name, args) in cases {
+            // Act
+            let result = call_builtin(name, args);
+
+            // Assert
+            assert!(
+                matches!(result, Err(EvalError::DomainError { .. })),
+                "should fail to compute {name}({args:?}), found {result:?}"
+            )
+        }
+
+        assert_eq!(
+            call_builtin("root", &[-16.0, 4.0]).unwrap_err().to_string(),
+            "[EVAL ERROR]: function 'root' is not defined for -16, 4",
+            "should describe the arguments out of the domain"
+        )
+    }
+
+    #[test]
+    fn test_call_integer_functions() {
+        // Arrange
+        let registry = FunctionRegistry::builtins();
+        let fail_on_overflow = EvalOptions {
+            integer_overflow: IntegerOverflow::Fail,
+            ..EvalOptions::default()
+        };
+
+        let big = 2i64.pow(40);
+        let overflowing_lcm = [Value::Integer(big), Value::Integer(3i64.pow(30))];
+
+        // Each function name and its arguments are paired with the expected result
+        let cases = [
+            (
+                "gcd",
+                vec![Value::Integer(12), Value::Float(18.0)],
+                Value::Integer(6),
+            ),
+            (
+                "lcm",
+                vec![Value::Integer(4), Value::Integer(6)],
+                Value::Integer(12),
+            ),
+            (
+                "lcm",
+                vec![Value::Integer(big), Value::Integer(big * 3)],
+                Value::Integer(big * 3),
+            ),
+            (
+                "lcm",
+                vec![Value::Integer(i64::MAX), Value::Integer(i64::MAX)],
+                Value::Integer(i64::MAX),
+            ),
+            (
+                "gcd",
+                vec![Value::Integer(i64::MIN), Value::Integer(0)],
+                Value::Float(2f64.powi(63)),
+            ),
+            (
+                "lcm",
+                overflowing_lcm.to_vec(),
+                Value::Float(big as f64 * 3f64.powi(30)),
+            ),
+        ];
+
+        for (name, args, expected_result) in cases {
+            // Act
+            let result = registry.call(Symbol::intern(name), &args, &EvalOptions::default());
+
+            // Assert
+            assert_eq!(
+                result,
+                Ok(expected_result),
+                "should compute {name}({args:?}) exactly while it fits in an integer"
+            )
+        }
+
+        assert_eq!(
+            registry.call(Symbol::intern("lcm"), &overflowing_lcm, &fail_on_overflow),
+            Err(EvalError::IntegerOverflow {
+                operation: Token::Identifier(Symbol::intern("lcm"))
+            }),
+            "should fail for results not fitting in an integer if integer overflows fail"
+        )
+    }
+
+    #[test]
+    fn test_call_integer_functions_fails() {
+        // Arrange
+        // Each function name and its arguments are paired with the argument which is not an integer
+        let cases: [(&str, &[f64], f64); 4] = [
+            ("gcd", &[2.5, 5.0], 2.5),
+            ("gcd", &[4.0, f64::NAN], f64::NAN),
+            ("lcm", &[3.0, 1e19], 1e19),
+            ("lcm", &[f64::INFINITY, 2.0], f64::INFINITY),
+        ];
+
+        for (name, args, expected_value) in cases {
+            // Act
+            let err = call_builtin(name, args).unwrap_err();
+
+            // Assert
+            assert!(
+                matches!(
+                    err,
+                    EvalError::NonIntegerArgument { value, .. }
+                        if value == expected_value || value.is_nan() && expected_value.is_nan()
+                ),
+                "should fail for {name}({args:?}) naming its argument which is not an integer, found {err:?}"
+            )
+        }
+
+        assert_eq!(
+            call_builtin("gcd", &[2.5, 5.0]).unwrap_err().to_string(),
+            "[EVAL ERROR]: function 'gcd' only works on integers fitting in 64 bits, found 2.5",
+            "should describe the argument which is not an integer"
+        )
+    }
+
+    #[test]
+    fn test_call_combinatorics() {
+        // Arrange
+        let registry = FunctionRegistry::builtins();
+        let call = |name, n: i64, r: i64| {
+            registry.call(
+                Symbol::intern(name),
+                &[Value::Integer(n), Value::Integer(r)],
+                &EvalOptions::default(),
+            )
+        };
+
+        // Each function name and its arguments are paired with the expected result
+        let cases = [
+            ("ncr", 1000, 3, Value::Integer(166_167_000)),
+            ("ncr", 60, 30, Value::Integer(118_264_581_564_861_424)),
+            (
+                "ncr",
+                1_000_000_000,
+                2,
+                Value::Integer(499_999_999_500_000_000),
+            ),
+            ("npr", 20, 20, Value::Integer(2_432_902_008_176_640_000)),
+            ("npr", 1000, 3, Value::Integer(997_002_000)),
+            ("ncr", 100, 50, Value::Float(1.008_913_445_455_642e29)),
+            ("ncr", i64::MAX, i64::MAX / 2, Value::Float(f64::INFINITY)),
+            ("npr", i64::MAX, i64::MAX, Value::Float(f64::INFINITY)),
+        ];
+
+        for (name, n, r, expected_result) in cases {
+            // Act
+            let result = call(name, n, r).unwrap();
+
+            // Assert
+            assert!(
+                match (&result, &expected_result) {
+                    (Value::Float(result), Value::Float(expected)) => {
+                        result == expected || (result - expected).abs() <= expected * 1e-12
+                    }
+                    _ => result == expected_result,
+                },
+                "should compute {name}({n}, {r}) as {expected_result}, found {result}"
+            )
+        }
+
+        for n in 0..=30 {
+            for r in 0..=n {
+                // Act
+                let (combinations, complement) = (call("ncr", n, r), call("ncr", n, n - r));
+
+                // Assert
+                assert_eq!(
+                    combinations, complement,
+                    "should choose as many combinations of {r} items out of {n} as of the {} left out",
+                    n - r
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_call_combinatorics_fails() {
+        // Arrange
+        // Each function name and its arguments are paired with the expected error
+        let cases = [
+            (
+                "ncr",
+                vec![-1.0, 0.0],
+                "function 'ncr' expects a non-negative n, found -1",
+            ),
+            (
+                "ncr",
+                vec![3.0, 5.0],
+                "function 'ncr' expects r between 0 and n, found r = 5 and n = 3",
+            ),
+            (
+                "npr",
+                vec![5.0, -1.0],
+                "function 'npr' expects r between 0 and n, found r = -1 and n = 5",
+            ),
+            (
+                "npr",
+                vec![2.5, 1.0],
+                "function 'npr' only works on integers fitting in 64 bits, found 2.5",
+            ),
+        ];
+
+        for (name, args, expected_error) in cases {
+            // Act
+            let err = call_builtin(name, &args).unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                format!("[EVAL ERROR]: {expected_error}"),
+                "should describe why the arguments are not valid"
+            )
+        }
+    }
+
+    #[test]
+    fn test_call_logarithms_fails_out_of_domain() {
+        // Arrange
+        let registry = FunctionRegistry::builtins();
+        let allow_non_finite = EvalOptions {
+            allow_non_finite: true,
+            ..EvalOptions::default()
+        };
+
+        let negatives = [
+            ("ln", vec![-1.0]),
+            ("log10", vec![-2.0]),
+            ("log2", vec![-0.5]),
+            ("log", vec![-8.0, 2.0]),
+            ("log", vec![8.0, -2.0]),
+            ("log", vec![8.0, 0.0]),
+            ("log", vec![1.0, 1.0]),
+        ];
+
+        // Each function name and its arguments are paired with the infinity the function goes to
+        let poles = [
+            ("ln", vec![0.0], f64::NEG_INFINITY),
+            ("log10", vec![0.0], f64::NEG_INFINITY),
+            ("log2", vec![-0.0], f64::NEG_INFINITY),
+            ("log", vec![8.0, 1.0], f64::INFINITY),
+        ];
+
+        for (name, args) in negatives {
+            let name = Symbol::intern(name);
+            let values = args.iter().copied().map(Value::Float).collect::<Vec<_>>();
+
+            // Act
+            let strict = registry.call(name, &values, &EvalOptions::default());
+            let allowed = registry.call(name, &values, &allow_non_finite);
+
+            // Assert
+            assert_eq!(
+                (strict, allowed),
+                (
+                    Err(EvalError::DomainError { name, args: args.clone() }),
+                    Err(EvalError::DomainError { name, args })
+                ),
+                "should fail out of the domain of the function, even if non-finite results are allowed"
+            )
+        }
+
+        for (name, args, expected_infinity) in poles {
+            let name = Symbol::intern(name);
+            let values = args.iter().copied().map(Value::Float).collect::<Vec<_>>();
+
+            // Act
+            let strict = registry.call(name, &values, &EvalOptions::default());
+            let allowed = registry.call(name, &values, &allow_non_finite);
+
+            // Assert
+            assert_eq!(
+                strict,
+                Err(EvalError::DomainError { name, args }),
+                "should fail at the poles of the function by default"
+            );
+            assert_eq!(
+                allowed,
+                Ok(Value::Float(expected_infinity)),
+                "should give infinity at the poles of the function if non-finite results are allowed"
+            )
+        }
+    }
+
+    #[test]
+    fn test_call_rounding_functions() {
+        // Arrange, Act & Assert
+        // Each function name and its arguments are paired with the expected result
+        assert_builtin_cases(&[
+            ("round", &[1.23456, 2.0], 1.23),
+            ("round", &[1234.0, -2.0], 1200.0),
+            ("round", &[1250.0, -2.0], 1300.0),
+            ("round", &[2.5], 3.0),
+            ("round", &[-2.5], -3.0),
+            ("round", &[0.125, 2.0], 0.13),
+            ("floor", &[-2.5], -3.0),
+            ("floor", &[1.23456, 3.0], 1.234),
+            ("ceil", &[2.1], 3.0),
+            ("ceil", &[1234.0, -2.0], 1300.0),
+            ("trunc", &[-2.7], -2.0),
+            ("trunc", &[-1234.5, -1.0], -1230.0),
+            ("round", &[1e300, 20.0], 1e300),
+            ("round", &[1234.0, -400.0], 0.0),
+        ]);
+
+        assert_eq!(
+            call_builtin("round", &[3.5, 0.5]).unwrap_err().to_string(),
+            "[EVAL ERROR]: function 'round' is not defined for 3.5, 0.5",
+            "should only round to an integer number of decimal places"
+        )
+    }
+
+    #[test]
+    fn test_call_exact_functions() {
+        // Arrange
+        let registry = FunctionRegistry::builtins();
+        let fail_on_overflow = EvalOptions {
+            integer_overflow: IntegerOverflow::Fail,
+            ..EvalOptions::default()
+        };
+
+        // Above 2^53, where not every integer is a float
+        let big = 2i64.pow(53) + 1;
+
+        // Each function name and its arguments are paired with the expected result
+        let cases = [
+            ("abs", vec![Value::Integer(-big)], Value::Integer(big)),
+            (
+                "abs",
+                vec![Value::Integer(i64::MAX)],
+                Value::Integer(i64::MAX),
+            ),
+            ("floor", vec![Value::Integer(big)], Value::Integer(big)),
+            ("ceil", vec![Value::Integer(-big)], Value::Integer(-big)),
+            ("trunc", vec![Value::Integer(big)], Value::Integer(big)),
+            ("round", vec![Value::Integer(big)], Value::Integer(big)),
+            ("sign", vec![Value::Integer(-big)], Value::Integer(-1)),
+            ("sign", vec![Value::Integer(0)], Value::Integer(0)),
+            (
+                "abs",
+                vec![Value::Integer(i64::MIN)],
+                Value::Float(2f64.powi(63)),
+            ),
+        ];
+
+        for (name, args, expected_result) in cases {
+            // Act
+            let result = registry.call(Symbol::intern(name), &args, &EvalOptions::default());
+
+            // Assert
+            assert_eq!(
+                result,
+                Ok(expected_result),
+                "should compute {name}({args:?}) exactly while it fits in an integer"
+            )
+        }
+
+        assert_eq!(
+            registry.call(
+                Symbol::intern("abs"),
+                &[Value::Integer(i64::MIN)],
+                &fail_on_overflow
+            ),
+            Err(EvalError::IntegerOverflow {
+                operation: Token::Identifier(Symbol::intern("abs"))
+            }),
+            "should fail for results not fitting in an integer if integer overflows fail"
+        );
+
+        assert_eq!(
+            registry.call(
+                Symbol::intern("round"),
+                &[Value::Integer(1234), Value::Integer(-2)],
+                &EvalOptions::default()
+            ),
+            Ok(Value::Float(1200.0)),
+            "should round to decimal places over floats"
+        )
+    }
+
+    #[test]
+    fn test_call_comparison_functions() {
+        // Arrange, Act & Assert
+        // Each function name and its arguments are paired with the expected result
+        assert_builtin_cases(&[
+            ("min", &[2.0, -1.0], -1.0),
+            ("max", &[2.0, 7.5, -1.0, 3.0], 7.5),
+            ("min", &[f64::NEG_INFINITY, 0.0], f64::NEG_INFINITY),
+            ("clamp", &[1024.0, 0.0, 1000.0], 1000.0),
+            ("clamp", &[-5.0, 0.0, 1000.0], 0.0),
+            ("clamp", &[2.5, 0.0, 3.0], 2.5),
+            ("clamp", &[2.5, 1.0, 1.0], 1.0),
+        ]);
+    }
+
+    #[test]
+    fn test_call_comparison_functions_exactly() {
+        // Arrange
+        let registry = FunctionRegistry::builtins();
+
+        // Above 2^53, where not every integer is a float
+        let big = 2i64.pow(53) + 1;
+
+        // Each function name and its arguments are paired with the argument selected, given unchanged
+        let cases = [
+            ("max", vec![big.into(), 1.into()], Value::Integer(big)),
+            (
+                "min",
+                vec![big.into(), (big - 1).into(), Value::Float(1e300)],
+                Value::Integer(big - 1),
+            ),
+            ("max", vec![Value::Float(2.0), 2.into()], Value::Float(2.0)),
+            (
+                "clamp",
+                vec![big.into(), 0.into(), (big - 1).into()],
+                Value::Integer(big - 1),
+            ),
+            (
+                "clamp",
+                vec![big.into(), Value::Float(0.5), (big + 1).into()],
+                Value::Integer(big),
+            ),
+        ];
+
+        for (name, args, expected_result) in cases {
+            // Act
+            let result = registry.call(Symbol::intern(name), &args, &EvalOptions::default());
+
+            // Assert
+            assert_eq!(
+                result,
+                Ok(expected_result),
+                "should compare the arguments of {name}({args:?}) exactly"
+            )
+        }
+
+        assert_eq!(
+            registry
+                .call(
+                    Symbol::intern("clamp"),
+                    &[0.into(), big.into(), (big - 1).into()],
+                    &EvalOptions::default()
+                )
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "[EVAL ERROR]: function 'clamp' expects min not greater than max, found min = {big} and max = {}",
+                big - 1
+            ),
+            "should compare the bounds exactly"
+        )
+    }
+
+    #[test]
+    fn test_call_comparison_functions_fails() {
+        // Arrange
+        let registry = FunctionRegistry::builtins();
+
+        // NaN arguments, which can't be compared, are out of the domain
+        let cases = [
+            ("clamp", vec![f64::NAN, 0.0, 1.0]),
+            ("clamp", vec![0.5, f64::NAN, 1.0]),
+            ("min", vec![1.0, f64::NAN]),
+            ("max", vec![f64::NAN, 1.0, 2.0]),
+        ];
+
+        for (name, args) in cases {
+            let name = Symbol::intern(name);
+            let values = args.iter().copied().map(Value::Float).collect::<Vec<_>>();
+
+            // Act
+            let result = registry.call(name, &values, &EvalOptions::default());
+
+            // Assert
+            assert!(
+                matches!(result, Err(EvalError::DomainError { name: found, .. }) if found == name),
+                "should reject {name}({args:?})"
+            )
+        }
+
+        assert_eq!(
+            registry
+                .call(
+                    Symbol::intern("clamp"),
+                    &[5.into(), 3.into(), 1.into()],
+                    &EvalOptions::default()
+                )
+                .unwrap_err()
+                .to_string(),
+            "[EVAL ERROR]: function 'clamp' expects min not greater than max, found min = 3 and max = 1",
+            "should name the bounds out of order"
+        );
+
+        assert_eq!(
+            registry
+                .call(Symbol::intern("min"), &[1.into()], &EvalOptions::default())
+                .unwrap_err()
+                .to_string(),
+            "[EVAL ERROR]: function 'min' expects at least 2 arguments, found 1",
+            "should need two arguments at least"
+        )
+    }
+}
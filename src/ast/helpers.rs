@@ -1,27 +1,26 @@
 #![allow(dead_code)]
 
-use std::mem;
+use std::{iter::Peekable, mem};
 
 use crate::tokenizer::tokens::Token;
 
-/// Takes a look at the next element of the iterator without consume it.
-pub fn peek<I: Iterator<Item = Token> + Clone>(tokens_iter: &I) -> Option<Token> {
-    tokens_iter.clone().next()
-}
+use super::parser::ParserToken;
 
 /// Tries to match the given token against some token of the given tokens list **comparing its variant only**.
 ///
 /// If some token matches, consumes the token from the iterator.
-pub fn match_token<I: Iterator<Item = Token> + Clone>(
+#[deprecated(note = "match tokens by their `TokenKind` with `Parser::advance_if` instead")]
+pub fn match_token<I: Iterator<Item: ParserToken>>(
     tokens_to_match: &[mem::Discriminant<Token>],
-    tokens_iter: &mut I,
+    tokens_iter: &mut Peekable<I>,
 ) -> Option<Token> {
-    let current_token = peek(tokens_iter);
+    let current_token = tokens_iter.peek().map(|token| token.token());
 
-    if let Some(current) = current_token {
+    // End of input never matches, so it is left in the iterator for reporting what was expected instead
+    if let Some(current) = current_token.filter(|token| !matches!(token, Token::Eof(_))) {
         for token in tokens_to_match {
-            if mem::discriminant(&current) == *token {
-                return tokens_iter.next();
+            if mem::discriminant(current) == *token {
+                return tokens_iter.next().map(ParserToken::into_token);
             }
         }
     }
@@ -32,16 +31,18 @@ pub fn match_token<I: Iterator<Item = Token> + Clone>(
 /// Tries to match the given token against some token of the given tokens list **comparing its variant and internal value**.
 ///
 /// If token matches, consumes it from the iterator.
-pub fn match_concrete_token<I: Iterator<Item = Token> + Clone>(
+#[deprecated(note = "match tokens by their `TokenKind` with `Parser::advance_if` instead")]
+pub fn match_concrete_token<I: Iterator<Item: ParserToken>>(
     tokens_to_match: &[Token],
-    tokens_iter: &mut I,
+    tokens_iter: &mut Peekable<I>,
 ) -> Option<Token> {
-    let current_token = peek(tokens_iter);
+    let current_token = tokens_iter.peek().map(|token| token.token());
 
-    if let Some(current) = current_token {
+    // End of input never matches, so it is left in the iterator for reporting what was expected instead
+    if let Some(current) = current_token.filter(|token| !matches!(token, Token::Eof(_))) {
         for token in tokens_to_match {
-            if current == *token {
-                return tokens_iter.next();
+            if current == token {
+                return tokens_iter.next().map(ParserToken::into_token);
             }
         }
     }
@@ -50,46 +51,24 @@ pub fn match_concrete_token<I: Iterator<Item = Token> + Clone>(
 }
 
 #[cfg(test)]
+#[allow(deprecated)]
 mod ast_helpers_tests {
     use std::{mem, vec};
 
-    use crate::tokenizer::tokens::{Operator, Token};
+    use crate::tokenizer::tokens::{Operator, Position, Token};
 
-    use super::{match_concrete_token, match_token, peek};
-
-    #[test]
-    fn test_peek() {
-        // Arrange
-        let tokens_source: Vec<Token> = vec![Token::Number(10.0)];
-        let tokens_iterator = tokens_source.clone().into_iter();
-
-        // Act
-        let peeked = peek(&tokens_iterator.clone()).unwrap();
-
-        // Assert
-        assert_eq!(
-            peeked,
-            tokens_source.clone()[0],
-            "should take a look at the current element of the iterator"
-        );
-
-        assert_eq!(
-            tokens_iterator.count(),
-            tokens_source.len(),
-            "should not consume token from the iterator after peek"
-        );
-    }
+    use super::{match_concrete_token, match_token};
 
     #[test]
     fn test_match_token_success() {
         // Arrange
-        let matching_token = Token::Number(10.0);
-        let base_number_token = Token::Number(0.0);
-        let mut tokens_source = vec![matching_token.clone()].into_iter();
+        let matching_token = Token::float(10.0);
+        let base_number_token = Token::float(0.0);
+        let mut tokens_source = vec![matching_token].into_iter().peekable();
 
         // Act
         let matched = match_token(
-            // Notice we are trying to match `Token::Number(10.0)` against `Token::Number(0.0)`.
+            // Notice we are trying to match `Token::float(10.0)` against `Token::float(0.0)`.
             // It is intentional because `match_token` just compares the enum variant, so we dont care about the internal value of the token.
             &[mem::discriminant(&base_number_token)],
             &mut tokens_source,
@@ -117,13 +96,13 @@ mod ast_helpers_tests {
     #[test]
     fn test_match_token_fails() {
         // Arrange
-        let number_token = Token::Number(10.0);
+        let number_token = Token::float(10.0);
         let operator_token = Token::Operator(Operator::Star);
-        let mut tokens_source = vec![number_token].into_iter();
+        let mut tokens_source = vec![number_token].into_iter().peekable();
 
         // Act
         let matched = match_token(
-            // Notice in this case we are trying to match `Token::Number(10.0)` against `Token::Operator(Operator::Star)`.
+            // Notice in this case we are trying to match `Token::float(10.0)` against `Token::Operator(Operator::Star)`.
             // Since token's variants are not the same, it shouldn't match
             &[mem::discriminant(&operator_token)],
             &mut tokens_source,
@@ -142,14 +121,38 @@ mod ast_helpers_tests {
         )
     }
 
+    #[test]
+    fn test_match_token_never_matches_eof() {
+        // Arrange
+        let eof_token = Token::Eof(Position::new(1, 4));
+        let mut tokens_source = vec![eof_token].into_iter().peekable();
+
+        // Act
+        let matched = match_token(&[mem::discriminant(&eof_token)], &mut tokens_source);
+        let concrete_matched =
+            match_concrete_token(std::slice::from_ref(&eof_token), &mut tokens_source);
+
+        // Assert
+        assert!(
+            matched.is_none() && concrete_matched.is_none(),
+            "should not match end of input even if it is asked for"
+        );
+
+        assert_eq!(
+            tokens_source.count(),
+            1,
+            "should keep end of input in the iterator"
+        )
+    }
+
     #[test]
     fn test_match_concrete_token_success() {
         // Arrange
-        let number_token = Token::Number(10.0);
-        let mut tokens_source = vec![number_token.clone()].into_iter();
+        let number_token = Token::float(10.0);
+        let mut tokens_source = vec![number_token].into_iter().peekable();
 
         // Act
-        let matched = match_concrete_token(&[number_token.clone()], &mut tokens_source);
+        let matched = match_concrete_token(std::slice::from_ref(&number_token), &mut tokens_source);
 
         // Assert
         assert!(
@@ -169,9 +172,9 @@ mod ast_helpers_tests {
     #[test]
     fn test_match_concrete_token_fails() {
         // Arrange
-        let number_token = Token::Number(10.0);
-        let token_to_match = Token::Number(20.0);
-        let mut tokens_source = vec![number_token.clone()].into_iter();
+        let number_token = Token::float(10.0);
+        let token_to_match = Token::float(20.0);
+        let mut tokens_source = vec![number_token].into_iter().peekable();
 
         // Act
         let result = match_concrete_token(&[token_to_match], &mut tokens_source);
@@ -1,3 +1,10 @@
+#[cfg(feature = "big-decimal")]
+pub mod decimal;
+pub mod environment;
 mod expressions;
+pub mod functions;
 mod helpers;
+pub mod operators;
+pub mod options;
 pub mod parser;
+pub mod value;
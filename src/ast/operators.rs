@@ -0,0 +1,76 @@
+#![allow(dead_code)]
+
+use std::fmt;
+
+use super::expressions::EvalError;
+
+/// How consecutive infix operators of the same level are grouped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Associativity {
+    /// `1 - 2 - 3` is `(1 - 2) - 3`.
+    Left,
+    /// `2 ^ 3 ^ 2` is `2 ^ (3 ^ 2)`.
+    Right,
+    /// Operators can't be chained, so `1 < 2 < 3` is an error.
+    None,
+}
+
+/// Level of the built-in operators a custom operator binds its operands as tight as.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Precedence {
+    /// Like `<`, `==` and the other comparisons.
+    Comparison,
+    /// Like `+` and `-`.
+    Term,
+    /// Like `*`, `/`, `//` and `%`.
+    Factor,
+    /// Like `^`.
+    Power,
+}
+
+/// Infix operator registered by the user, like `@` for the average of its operands.
+///
+/// Its symbol must be registered in the tokenizer as well, so it is tokenized as `Token::CustomOperator`.
+#[derive(Clone, Copy)]
+pub struct CustomOperator {
+    pub symbol: char,
+    pub precedence: Precedence,
+    pub associativity: Associativity,
+    /// Computes the value of the operator from its left and right operands.
+    pub eval: fn(f64, f64) -> Result<f64, EvalError>,
+}
+
+impl CustomOperator {
+    pub fn new(
+        symbol: char,
+        precedence: Precedence,
+        associativity: Associativity,
+        eval: fn(f64, f64) -> Result<f64, EvalError>,
+    ) -> Self {
+        Self {
+            symbol,
+            precedence,
+            associativity,
+            eval,
+        }
+    }
+}
+
+impl fmt::Debug for CustomOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomOperator")
+            .field("symbol", &self.symbol)
+            .field("precedence", &self.precedence)
+            .field("associativity", &self.associativity)
+            .finish_non_exhaustive()
+    }
+}
+
+// Functions can't be reliably compared, so operators are equal if they are parsed the same way
+impl PartialEq for CustomOperator {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+            && self.precedence == other.precedence
+            && self.associativity == other.associativity
+    }
+}
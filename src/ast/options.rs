@@ -0,0 +1,43 @@
+#![allow(dead_code)]
+
+/// What an operation over integers does when its result doesn't fit in an `i64`, like `2 ^ 63`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegerOverflow {
+    /// Computes the operation over floats instead, so `2 ^ 63` is `9223372036854775808` as a float.
+    #[default]
+    Promote,
+    /// Fails with `EvalError::IntegerOverflow`.
+    Fail,
+}
+
+/// How numbers are represented while evaluating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    /// Integers while operations over them are exact, and floats otherwise.
+    #[default]
+    Standard,
+    /// Decimals of arbitrary precision, so `12345678901234567890 + 1` doesn't round. Operations which can't be
+    /// computed exactly, like functions, fall back to floats.
+    #[cfg(feature = "big-decimal")]
+    Big,
+}
+
+/// Unit of the angles taken by trigonometric functions, like `sin`, and given by their inverses, like `asin`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AngleMode {
+    #[default]
+    Radians,
+    /// Angles are in degrees, so `sin(90)` is `1` and `asin(1)` is `90`.
+    Degrees,
+}
+
+/// Settings changing how expressions are evaluated. `Default` holds the settings used by `Environment::new`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EvalOptions {
+    /// Whether operations overflowing to infinity or giving NaN from finite operands, like `1e308 * 10`, return
+    /// their result instead of failing with `EvalError::NonFiniteResult`.
+    pub allow_non_finite: bool,
+    pub integer_overflow: IntegerOverflow,
+    pub precision: Precision,
+    pub angle_mode: AngleMode,
+}
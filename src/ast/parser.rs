@@ -1,304 +1,3637 @@
+#![allow(dead_code)]
+
 use core::fmt;
-use std::{error::Error, fmt::Debug, mem};
+use std::{
+    error::Error,
+    fmt::Debug,
+    iter::{Copied, Peekable},
+    mem, slice, vec,
+};
 
 use crate::{
-    ast::{expressions::UnaryExpr, helpers::match_token},
-    tokenizer::tokens::{Operator, Token},
+    ast::expressions::UnaryExpr,
+    tokenizer::{
+        parser::{Tokenizer, TokenizerError},
+        symbol::Symbol,
+        tokens::{Operator, Position, Span, Spanned, Token, TokenKind},
+    },
+};
+
+use super::{
+    expressions::{BinaryExpr, Expression, LiteralExpr, NodeSpan, Program},
+    functions::{Arity, FunctionRegistry},
+    operators::{Associativity, CustomOperator, Precedence},
 };
 
-use super::{
-    expressions::{BinaryExpr, Expression},
-    helpers::{match_concrete_token, peek},
-};
+/// What the parser could have accepted where it failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+    Number,
+    Identifier,
+    /// Exactly the given token, like `)`.
+    Token(Token),
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Number => write!(f, "a number"),
+            Expected::Identifier => write!(f, "an identifier"),
+            Expected::Token(token) => write!(f, "'{token}'"),
+        }
+    }
+}
+
+/// Tokens an expression can start with.
+const EXPRESSION_STARTS: &[Expected] = &[
+    Expected::Number,
+    Expected::Identifier,
+    Expected::Token(Token::LeftParen),
+    Expected::Token(Token::Pipe),
+];
+
+/// Class of a parsing failure, with the details of what was expected if any.
+///
+/// New kinds may be added as the grammar grows, so matching on them must have a fallback arm.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ASTParseErrorKind {
+    /// Found token is none of the expected ones.
+    UnexpectedToken(Vec<Expected>),
+    /// Found token can't start an expression, like the `*` in `2 + * 3`, so an operand is missing.
+    ExpectedExpression,
+    /// End of input was reached where any of the expected tokens was required, so the source may just be
+    /// unfinished, like `1 +`.
+    UnexpectedEof(Vec<Expected>),
+    /// Found token is not the one required by the `context`, like the `:` between the branches of a conditional.
+    ExpectedToken {
+        expected: TokenKind,
+        context: &'static str,
+    },
+    /// A `)` without its `(`.
+    UnmatchedRightParen,
+    /// A `(` without its `)`, which is missing when input ends. Position of the `(` is only known with spanned
+    /// tokens.
+    UnclosedLeftParen { opened_at: Option<Position> },
+    /// A grouping whose `)` was expected instead of the found token, like the `3` in `(1 + 2 3)`.
+    ExpectedClosingParen { opened_at: Option<Position> },
+    /// Token left after a complete expression.
+    TrailingToken,
+    /// Expression is nested deeper than the `max_depth` of the parser.
+    TooDeep { max_depth: usize },
+    /// Expressions built by the parser have more nodes than its `max_nodes`.
+    LimitExceeded { max_nodes: usize },
+    /// A `=` after an expression which is neither a variable nor a function head, like `2x = 4`.
+    InvalidAssignmentTarget,
+    /// A parameter of a function definition which is not a variable name, like the `1` in `f(1) = 2`.
+    InvalidParameter,
+    /// A parameter repeated in the same function definition, like `f(x, x) = x`.
+    DuplicateParameter(Symbol),
+    /// A `=` after the value of an assignment, like `a = b = 2`.
+    ChainedAssignment,
+    /// A `;` without a statement before it, like the second one in `1;;2`.
+    EmptyStatement,
+    /// A comparison right after another one, like `1 < 2 < 3`.
+    ChainedComparison,
+    /// An absolute value right inside another one, like `| |x| - 1 |`, which must be grouped as `|(|x|) - 1|`.
+    NestedAbsoluteValue,
+    /// A call with a number of arguments its function doesn't accept, like `sqrt(1, 2)`.
+    WrongArity {
+        name: Symbol,
+        expected: Arity,
+        found: usize,
+    },
+    /// A call to a function missing from the registry, only rejected with `UnknownFunctions::Reject`.
+    UnknownFunction(Symbol),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ASTParseError {
+    /// Boxed, since every method of the parser returns the error and a bigger one makes their frames bigger.
+    kind: Box<ASTParseErrorKind>,
+    found: Option<Token>,
+    position: Option<Position>,
+}
+
+impl ASTParseError {
+    fn new<T: ParserToken>(kind: ASTParseErrorKind, found: Option<T>) -> Self {
+        Self {
+            kind: Box::new(kind),
+            found: found.map(ParserToken::into_token),
+            position: found.and_then(|token| token.position()),
+        }
+    }
+
+    /// Error for a token which is none of the `expected` ones, or for the end of input if it was reached.
+    fn unexpected<T: ParserToken>(expected: &[Expected], found: Option<T>) -> Self {
+        let kind = match found.as_ref().map(|token| token.token()) {
+            None | Some(Token::Eof(_)) => ASTParseErrorKind::UnexpectedEof(expected.to_vec()),
+            Some(_) => ASTParseErrorKind::UnexpectedToken(expected.to_vec()),
+        };
+
+        Self::new(kind, found)
+    }
+
+    /// Error for a token which can't start an expression, or for the end of input if it was reached.
+    fn expected_expression<T: ParserToken>(found: Option<T>) -> Self {
+        match found.as_ref().map(|token| token.token()) {
+            None | Some(Token::Eof(_)) => Self::unexpected(EXPRESSION_STARTS, found),
+            Some(_) => Self::new(ASTParseErrorKind::ExpectedExpression, found),
+        }
+    }
+
+    /// Kind of the error, with what was expected if any.
+    pub fn kind(&self) -> &ASTParseErrorKind {
+        &self.kind
+    }
+
+    /// Token which caused the error, being `None` or `Token::Eof` if the end of input was reached.
+    pub fn found(&self) -> Option<&Token> {
+        self.found.as_ref()
+    }
+
+    /// Position in the source of the token which caused the error, only known if the parser was fed with
+    /// spanned tokens or the end of input was reached.
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    /// Whether the error was caused by reaching the end of input in the middle of an expression, like in `1 +`,
+    /// `max(1,` or `(1 + 2`, so more input could complete it.
+    pub fn is_incomplete(&self) -> bool {
+        match *self.kind {
+            ASTParseErrorKind::UnexpectedEof(_) | ASTParseErrorKind::UnclosedLeftParen { .. } => {
+                true
+            }
+            ASTParseErrorKind::ExpectedToken { .. } => {
+                matches!(self.found, None | Some(Token::Eof(_)))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ASTParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Tokens built by hand may not end with `Token::Eof`, so running out of them is the end too
+        let found = match self.found {
+            None | Some(Token::Eof(_)) => "end of input".to_string(),
+            Some(token) => format!("'{token}'"),
+        };
+
+        write!(f, "[AST PARSE ERROR]: ")?;
+
+        match &*self.kind {
+            ASTParseErrorKind::UnexpectedToken(expected)
+            | ASTParseErrorKind::UnexpectedEof(expected) => {
+                write!(f, "expected {}, found {found}", describe_expected(expected))?
+            }
+            ASTParseErrorKind::ExpectedExpression => write!(
+                f,
+                "expected {}, found {found}",
+                describe_expected(EXPRESSION_STARTS)
+            )?,
+            ASTParseErrorKind::ExpectedToken { expected, context } => {
+                write!(f, "expected {expected} {context}, found {found}")?
+            }
+            ASTParseErrorKind::UnmatchedRightParen => write!(f, "missing '(' for ')'")?,
+            ASTParseErrorKind::UnclosedLeftParen { opened_at } => match opened_at {
+                Some(opened_at) => write!(f, "missing ')' for '(' at {opened_at}, found {found}")?,
+                None => write!(f, "missing ')' for '(', found {found}")?,
+            },
+            ASTParseErrorKind::ExpectedClosingParen { .. } => {
+                write!(f, "expected ')', found {found}")?
+            }
+            ASTParseErrorKind::TrailingToken => {
+                write!(f, "unexpected token {found} after expression")?
+            }
+            ASTParseErrorKind::TooDeep { max_depth } => {
+                write!(f, "expression is nested deeper than {max_depth} levels")?
+            }
+            ASTParseErrorKind::LimitExceeded { max_nodes } => {
+                write!(f, "expression has more than {max_nodes} nodes")?
+            }
+            ASTParseErrorKind::InvalidAssignmentTarget => {
+                write!(f, "only a variable or a function can be assigned with '='")?
+            }
+            ASTParseErrorKind::InvalidParameter => {
+                write!(f, "parameters of a function must be variable names")?
+            }
+            ASTParseErrorKind::DuplicateParameter(name) => {
+                write!(f, "parameter '{name}' is repeated in function definition")?
+            }
+            ASTParseErrorKind::ChainedAssignment => {
+                write!(f, "chained assignments like 'a = b = 2' are not supported")?
+            }
+            ASTParseErrorKind::EmptyStatement => write!(f, "empty statement before {found}")?,
+            ASTParseErrorKind::ChainedComparison => write!(
+                f,
+                "comparisons can't be chained like '1 < 2 < 3', group them with parentheses instead"
+            )?,
+            ASTParseErrorKind::NestedAbsoluteValue => write!(
+                f,
+                "absolute values can't be nested like '| |x| - 1 |', group the inner one with parentheses instead"
+            )?,
+            ASTParseErrorKind::WrongArity {
+                name,
+                expected,
+                found,
+            } => write!(f, "function '{name}' expects {expected}, found {found}")?,
+            ASTParseErrorKind::UnknownFunction(name) => write!(f, "unknown function '{name}'")?,
+        }
+
+        match self.position {
+            Some(position) => write!(f, " at {position}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for ASTParseError {}
+
+/// Lists the alternatives which were expected, joining the last one with `or`, like `a number, an identifier or
+/// '('`.
+fn describe_expected(expected: &[Expected]) -> String {
+    let expected = expected
+        .iter()
+        .map(Expected::to_string)
+        .collect::<Vec<String>>();
+
+    match expected.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} or {last}", rest.join(", ")),
+        None => "nothing".to_string(),
+    }
+}
+
+/// Error building an expression straight from its source, either while tokenizing or while parsing it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    Tokenizer(TokenizerError),
+    Parser(ASTParseError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Tokenizer(err) => write!(f, "{err}"),
+            ParseError::Parser(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::Tokenizer(err) => Some(err),
+            ParseError::Parser(err) => Some(err),
+        }
+    }
+}
+
+impl From<TokenizerError> for ParseError {
+    fn from(err: TokenizerError) -> Self {
+        ParseError::Tokenizer(err)
+    }
+}
+
+impl From<ASTParseError> for ParseError {
+    fn from(err: ASTParseError) -> Self {
+        ParseError::Parser(err)
+    }
+}
+
+/// Result of parsing a source which may still be being written, like the lines read by the REPL.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseOutcome {
+    Complete(Program),
+    /// Source ends in the middle of an expression, like `1 +`, so it needs more tokens to be complete.
+    NeedMoreInput,
+}
+
+/// Token the parser can be fed with, which may know where it is in the source.
+///
+/// Both bare tokens (from `Tokenizer::tokenize`) and spanned tokens (from `Tokenizer::tokenize_spanned`) are
+/// accepted, but only the latter let errors point to the token which caused them.
+pub trait ParserToken: Copy + Debug {
+    fn token(&self) -> &Token;
+
+    /// Takes the token out, dropping where it is in the source.
+    fn into_token(self) -> Token;
+
+    /// Position in the source where the token starts, if known.
+    fn position(&self) -> Option<Position>;
+
+    /// Range of the source the token was built from, if known.
+    fn span(&self) -> Option<Span>;
+}
+
+impl ParserToken for Token {
+    fn token(&self) -> &Token {
+        self
+    }
+
+    fn into_token(self) -> Token {
+        self
+    }
+
+    // Bare tokens don't know where they are, except the end of input, which carries its position
+    fn position(&self) -> Option<Position> {
+        match self {
+            Token::Eof(position) => Some(*position),
+            _ => None,
+        }
+    }
+
+    fn span(&self) -> Option<Span> {
+        None
+    }
+}
+
+impl ParserToken for Spanned<Token> {
+    fn token(&self) -> &Token {
+        &self.value
+    }
+
+    fn into_token(self) -> Token {
+        self.value
+    }
+
+    fn position(&self) -> Option<Position> {
+        Some(self.position)
+    }
+
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+type ExpressionResult = Result<Expression, ASTParseError>;
+
+// Binding powers of each level of operators, from the loosest to the tightest. Operators bind their operands
+// tighter the higher their binding power is, so the levels are spaced to fit the left and right binding powers
+// of the infix operators.
+const OR_BP: u8 = 1;
+const AND_BP: u8 = 3;
+const NOT_BP: u8 = 5;
+const BIT_OR_BP: u8 = 7;
+const BIT_AND_BP: u8 = 9;
+const COMPARISON_BP: u8 = 11;
+const SHIFT_BP: u8 = 13;
+const TERM_BP: u8 = 15;
+const FACTOR_BP: u8 = 17;
+/// Signs with `SignPrecedence::BelowPower`.
+const SIGN_BP: u8 = 19;
+const POWER_BP: u8 = 21;
+/// Signs with `SignPrecedence::AbovePower`.
+const TIGHT_SIGN_BP: u8 = 23;
+const POSTFIX_BP: u8 = 25;
+
+/// Levels of nesting allowed by default, enough for any expression written by hand.
+const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// Binding power of a prefix operator, or `None` if it can't start an expression. Binding power of signs depends
+/// on their `sign_precedence`.
+fn prefix_binding_power(operator: Operator, sign_precedence: SignPrecedence) -> Option<u8> {
+    match operator {
+        Operator::Minus | Operator::Plus => match sign_precedence {
+            SignPrecedence::BelowPower => Some(SIGN_BP),
+            SignPrecedence::AbovePower => Some(TIGHT_SIGN_BP),
+        },
+        Operator::Not => Some(NOT_BP),
+        _ => None,
+    }
+}
+
+/// Binding power and associativity of an infix operator, or `None` if it can't be between two operands.
+fn infix_operator(operator: Operator) -> Option<(u8, Associativity)> {
+    match operator {
+        Operator::Or => Some((OR_BP, Associativity::Left)),
+        Operator::And => Some((AND_BP, Associativity::Left)),
+        // Like in C, bitwise operators bind looser than comparisons, while shifts bind tighter
+        Operator::BitOr => Some((BIT_OR_BP, Associativity::Left)),
+        Operator::Ampersand => Some((BIT_AND_BP, Associativity::Left)),
+        Operator::LessLess | Operator::GreaterGreater => Some((SHIFT_BP, Associativity::Left)),
+        Operator::Less
+        | Operator::LessEqual
+        | Operator::Greater
+        | Operator::GreaterEqual
+        | Operator::EqualEqual
+        | Operator::BangEqual => Some((COMPARISON_BP, Associativity::None)),
+        Operator::Plus | Operator::Minus => Some((TERM_BP, Associativity::Left)),
+        Operator::Star | Operator::Slash | Operator::DoubleSlash | Operator::Percent => {
+            Some((FACTOR_BP, Associativity::Left))
+        }
+        Operator::Caret => Some((POWER_BP, Associativity::Right)),
+        _ => None,
+    }
+}
+
+/// Left and right binding powers of an infix operator, or `None` if it can't be between two operands.
+///
+/// Left associative operators bind their right side tighter, so the next operator of the same level takes the
+/// expression built so far. Right associative operators are the other way around. Non associative operators
+/// are grouped as left associative ones, and chaining them is checked apart.
+fn infix_binding_power(operator: Operator) -> Option<(u8, u8)> {
+    infix_operator(operator).map(|(bp, associativity)| binding_powers(bp, associativity))
+}
+
+/// Left and right binding powers of an infix operator of the given level and associativity.
+fn binding_powers(bp: u8, associativity: Associativity) -> (u8, u8) {
+    match associativity {
+        Associativity::Right => (bp + 1, bp),
+        Associativity::Left | Associativity::None => (bp, bp + 1),
+    }
+}
+
+/// Binding power of the level of built-in operators given by the precedence of a custom operator.
+fn precedence_binding_power(precedence: Precedence) -> u8 {
+    match precedence {
+        Precedence::Comparison => COMPARISON_BP,
+        Precedence::Term => TERM_BP,
+        Precedence::Factor => FACTOR_BP,
+        Precedence::Power => POWER_BP,
+    }
+}
+
+/// Operator between two operands, either a built-in one or a custom one registered in the parser.
+#[derive(Debug, Clone, Copy)]
+enum Infix {
+    Builtin(Operator),
+    Custom(CustomOperator),
+}
+
+impl Infix {
+    /// Binding power and associativity of the operator.
+    fn binding(&self) -> (u8, Associativity) {
+        match self {
+            Infix::Builtin(operator) => infix_operator(*operator).unwrap(),
+            Infix::Custom(operator) => (
+                precedence_binding_power(operator.precedence),
+                operator.associativity,
+            ),
+        }
+    }
+}
+
+/// What is assigned by a statement with `=`: a variable, or a function with its parameters.
+enum AssignTarget {
+    Variable(Symbol, NodeSpan),
+    Function(Symbol, Vec<Symbol>, NodeSpan),
+}
+
+/// Binding power of a postfix operator, or `None` if it can't follow an operand.
+fn postfix_binding_power(operator: Operator) -> Option<u8> {
+    match operator {
+        Operator::PercentSuffix | Operator::Degrees | Operator::Radians | Operator::Bang => {
+            Some(POSTFIX_BP)
+        }
+        _ => None,
+    }
+}
+
+/// How tight the signs `-` and `+` bind compared to `^`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SignPrecedence {
+    /// Like in math, signs apply to the result of the power: `-2^2` is `-(2^2)`, which is `-4`.
+    #[default]
+    BelowPower,
+    /// Like in spreadsheets, signs apply to the base of the power: `-2^2` is `(-2)^2`, which is `4`.
+    AbovePower,
+}
+
+/// How calls to functions missing from the registry of the parser are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum UnknownFunctions {
+    /// Calls are built as they are, so they fail when evaluated unless the function exists by then.
+    #[default]
+    Defer,
+    /// Calls fail to parse with an `UnknownFunction` error.
+    Reject,
+}
+
+/// Stores the state of the tokens stream and exposes methods for perform the AST building
+#[derive(Debug)]
+pub struct Parser<I: Iterator<Item: ParserToken>> {
+    pub tokens: Peekable<I>,
+    /// Levels of nesting of the expression being built.
+    depth: usize,
+    max_depth: usize,
+    /// Nodes of the expressions built so far.
+    nodes: usize,
+    max_nodes: usize,
+    /// Whether the expression being built is right inside bars, where a `|` can only close them.
+    in_absolute_value: bool,
+    custom_operators: Vec<CustomOperator>,
+    sign_precedence: SignPrecedence,
+    /// Functions whose calls are checked while parsing, if any.
+    functions: Option<FunctionRegistry>,
+    unknown_functions: UnknownFunctions,
+    /// Source the tokens were built from, if known, where the lexemes of number literals are taken from.
+    source: Option<Box<str>>,
+}
+
+impl<I: Iterator<Item: ParserToken>> Parser<I> {
+    /// Creates a parser over the given tokens stream, usually the ones yielded by a `Tokenizer`:
+    /// `Parser::new(Tokenizer::new(source).tokenize()?.into_iter())`, or `Parser::from_slice` to borrow them.
+    /// Tokens from `tokenize_spanned` are preferred when errors are shown to users, so they can tell where each
+    /// error is.
+    pub fn new(tokens: I) -> Self {
+        Self {
+            tokens: tokens.peekable(),
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            nodes: 0,
+            max_nodes: usize::MAX,
+            in_absolute_value: false,
+            custom_operators: Vec::new(),
+            sign_precedence: SignPrecedence::default(),
+            functions: None,
+            unknown_functions: UnknownFunctions::default(),
+            source: None,
+        }
+    }
+
+    /// Sets the levels of nesting allowed for expressions, like groupings, operands and stacked unary operators.
+    /// Parsing fails with a `TooDeep` error past them, instead of exhausting the stack.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the nodes allowed for all the expressions built by the parser, like literals, variables and
+    /// operations. Parsing fails with a `LimitExceeded` error past them, instead of building a huge tree. Nodes
+    /// are unlimited by default.
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    /// Parses the given custom operator between two operands, binding them as tight as the built-in operators of
+    /// its precedence. Its symbol must be registered in the tokenizer too, so it is tokenized as
+    /// `Token::CustomOperator`. Registering the same symbol again replaces its operator.
+    pub fn custom_operator(mut self, operator: CustomOperator) -> Self {
+        self.custom_operators
+            .retain(|custom| custom.symbol != operator.symbol);
+        self.custom_operators.push(operator);
+        self
+    }
+
+    /// Sets how tight the signs `-` and `+` bind compared to `^`. By default, they bind looser like in math, so
+    /// `-2^2` is `-4`, while `SignPrecedence::AbovePower` makes it `4` like in spreadsheets.
+    pub fn sign_precedence(mut self, sign_precedence: SignPrecedence) -> Self {
+        self.sign_precedence = sign_precedence;
+        self
+    }
+
+    /// Checks the number of arguments of calls against the given functions, failing with a `WrongArity` error
+    /// when a function doesn't accept them, instead of waiting for the call to be evaluated. Without functions,
+    /// calls are never checked while parsing.
+    pub fn functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = Some(functions);
+        self
+    }
+
+    /// Sets how calls to functions missing from the ones given to `functions` are handled, deferring them to
+    /// evaluation by default.
+    pub fn unknown_functions(mut self, unknown_functions: UnknownFunctions) -> Self {
+        self.unknown_functions = unknown_functions;
+        self
+    }
+
+    /// Sets the source the spanned tokens were built from, so number literals keep the text they were written
+    /// with, like `1_000` or `0xFF`. Without it, they are written as their values.
+    pub fn source(mut self, source: &str) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+}
+
+// Slice methods
+impl<'a, T: ParserToken> Parser<Copied<slice::Iter<'a, T>>> {
+    /// Creates a parser borrowing the given tokens, either bare or spanned, instead of taking them. Tokens are
+    /// left untouched, so the same ones can be parsed again: `Parser::from_slice(&tokens).program()`.
+    pub fn from_slice(tokens: &'a [T]) -> Self {
+        Self::new(tokens.iter().copied())
+    }
+}
+
+// Source methods
+impl Parser<vec::IntoIter<Spanned<Token>>> {
+    /// Tokenizes and parses the source as a single expression, like `program` does.
+    ///
+    /// Tokens are spanned, so parsing errors tell where they are.
+    pub fn from_source(source: &str) -> Result<Expression, ParseError> {
+        Ok(Self::spanned(source)?.program()?)
+    }
+
+    /// Tokenizes and parses the source as statements separated by `;`, like `statements` does.
+    pub fn statements_from_source(source: &str) -> Result<Program, ParseError> {
+        Ok(Self::spanned(source)?.statements()?)
+    }
+
+    /// Tokenizes and parses the source as statements separated by `;`, like `parse_or_incomplete` does.
+    pub fn parse_or_incomplete_from_source(source: &str) -> Result<ParseOutcome, ParseError> {
+        Ok(Self::spanned(source)?.parse_or_incomplete()?)
+    }
+
+    /// Creates a parser over the spanned tokens of the source.
+    fn spanned(source: &str) -> Result<Self, TokenizerError> {
+        let tokens = Tokenizer::new(source).tokenize_spanned()?;
+        Ok(Self::new(tokens.into_iter()).source(source))
+    }
+}
+
+// Expression methods
+impl<I: Iterator<Item: ParserToken>> Parser<I> {
+    /// Builds the root's program expression.
+    ///   
+    /// The whole tokens stream must be a single expression, so any token left after it is an error.
+    ///
+    /// Production rule: `Program -> Conditional EOF`
+    pub fn program(&mut self) -> ExpressionResult {
+        let expr = self.conditional()?;
+
+        self.check_nodes()?;
+
+        if self.is_at_end() {
+            return Ok(expr);
+        }
+
+        Err(self.trailing_token())
+    }
+
+    /// Builds a program made of statements separated by `;`, which may end with a single `;`. Empty statements,
+    /// like the one between `1;;2`, fail with an `EmptyStatement` error at the `;` after them.
+    ///
+    /// Newlines are whitespace, so a statement can span several lines: `1 +\n2` is a single statement.
+    ///
+    /// Production rule: `Statements -> Statement* EOF`
+    pub fn statements(&mut self) -> Result<Program, ASTParseError> {
+        let mut statements = Vec::new();
+
+        while !self.is_at_end() {
+            if let Some(semicolon) = self.advance_if(&[TokenKind::Semicolon]) {
+                return Err(ASTParseError::new(
+                    ASTParseErrorKind::EmptyStatement,
+                    Some(semicolon),
+                ));
+            }
+
+            statements.push(self.statement()?);
+        }
+
+        Ok(Program::new(statements))
+    }
+
+    /// Same as `statements`, but telling apart sources which are just unfinished, like `1 +` or `max(1,`, from
+    /// the wrong ones. The former are `ParseOutcome::NeedMoreInput`, so the tokens of the next line can be
+    /// appended to them before parsing again.
+    pub fn parse_or_incomplete(&mut self) -> Result<ParseOutcome, ASTParseError> {
+        match self.statements() {
+            Ok(program) => Ok(ParseOutcome::Complete(program)),
+            Err(err) if err.is_incomplete() => Ok(ParseOutcome::NeedMoreInput),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Builds a statement, which is either an assignment, a function definition or an expression, and must be
+    /// followed by a `;` or the end of input.
+    ///
+    /// Production rule: `Statement -> (IDENTIFIER "=" Conditional | Call "=" Conditional | Conditional) (";" | EOF)`,
+    /// where the arguments of the `Call` must be different identifiers
+    fn statement(&mut self) -> ExpressionResult {
+        let mut expr = self.conditional()?;
+
+        // Assigned variable is parsed as any other term, since it is only known to be assigned once `=` is found.
+        // Likewise, the head of a function definition is parsed as a call, like `f(x)` in `f(x) = x + 1`
+        if let Some(equals) = self.advance_if(&[TokenKind::Equals]) {
+            let target = match &mut expr {
+                Expression::Variable(name, name_span) => AssignTarget::Variable(*name, *name_span),
+                Expression::Call { name, args, span } => {
+                    AssignTarget::Function(*name, Self::parameters(mem::take(args), equals)?, *span)
+                }
+                _ => {
+                    return Err(ASTParseError::new(
+                        ASTParseErrorKind::InvalidAssignmentTarget,
+                        Some(equals),
+                    ))
+                }
+            };
+
+            let value = self.conditional()?;
+
+            if let Some(equals) = self.advance_if(&[TokenKind::Equals]) {
+                return Err(ASTParseError::new(
+                    ASTParseErrorKind::ChainedAssignment,
+                    Some(equals),
+                ));
+            }
+
+            self.take_nodes(1);
+
+            expr = match target {
+                AssignTarget::Variable(name, name_span) => Expression::Assign {
+                    name,
+                    span: name_span.to(value.node_span()),
+                    value: Box::new(value),
+                },
+                AssignTarget::Function(name, params, head_span) => {
+                    // Following statements can call the function, so their calls are checked against it
+                    if let Some(functions) = &mut self.functions {
+                        functions.register(name.as_str(), Arity::Exact(params.len()));
+                    }
+
+                    Expression::FunctionDef {
+                        name,
+                        params,
+                        span: head_span.to(value.node_span()),
+                        body: Box::new(value),
+                    }
+                }
+            };
+        }
+
+        self.check_nodes()?;
+
+        if self.is_at_end() || self.advance_if(&[TokenKind::Semicolon]).is_some() {
+            return Ok(expr);
+        }
+
+        Err(self.trailing_token())
+    }
+
+    /// Names of the parameters of a function definition, given as the `args` of its head. Each of them must be a
+    /// different variable, otherwise the error points at the `equals` of the definition.
+    fn parameters(args: Vec<Expression>, equals: I::Item) -> Result<Vec<Symbol>, ASTParseError> {
+        let mut params = Vec::with_capacity(args.len());
+
+        for arg in args {
+            let kind = match arg {
+                Expression::Variable(name, _) if !params.contains(&name) => {
+                    params.push(name);
+                    continue;
+                }
+                Expression::Variable(name, _) => ASTParseErrorKind::DuplicateParameter(name),
+                _ => ASTParseErrorKind::InvalidParameter,
+            };
+
+            return Err(ASTParseError::new(kind, Some(equals)));
+        }
+
+        Ok(params)
+    }
+
+    /// Error for a token left after a complete expression.
+    fn trailing_token(&mut self) -> ASTParseError {
+        let next = self.peek();
+
+        let kind = match next.as_ref().map(|token| token.token()) {
+            // Every `)` closing a grouping is consumed by it, so a remaining one was never opened
+            Some(Token::RightParen) => ASTParseErrorKind::UnmatchedRightParen,
+            _ => ASTParseErrorKind::TrailingToken,
+        };
+
+        ASTParseError::new(kind, next)
+    }
+
+    /// Builds a conditional, like `x > 0 ? x : -x`, whose branches are conditionals too, so they are right
+    /// associative: `a ? b : c ? d : e` is `a ? b : (c ? d : e)`.
+    ///
+    /// Production rule: `Conditional -> Or ("?" Conditional ":" Conditional)?`
+    fn conditional(&mut self) -> ExpressionResult {
+        let condition = self.or()?;
+
+        if self.advance_if(&[TokenKind::Question]).is_none() {
+            return Ok(condition);
+        }
+
+        let then = self.conditional()?;
+
+        self.expect(TokenKind::Colon, "between the branches of a conditional")?;
+
+        let otherwise = self.conditional()?;
+
+        self.take_nodes(1);
+
+        Ok(Expression::Conditional {
+            condition: Box::new(condition),
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+        })
+    }
+
+    /// Builds an expression of any operators, since `or` is the loosest of them. Each level of precedence is
+    /// parsed by `parse_expression` instead of a method of its own.
+    fn or(&mut self) -> ExpressionResult {
+        self.parse_expression(OR_BP)
+    }
+
+    /// Builds an expression made of the operators binding at least as tight as `min_bp`, by precedence climbing.
+    ///
+    /// Each operator found takes the expression built so far as its left side. Its right side only takes the
+    /// operators binding tighter than it, so they are nested deeper in the tree: `1 + 2 * 3` is `1 + (2 * 3)`.
+    /// Binding powers are given by `prefix_binding_power`, `infix_binding_power` and `postfix_binding_power`.
+    fn parse_expression(&mut self, min_bp: u8) -> ExpressionResult {
+        self.nested(1, |parser| parser.parse_operators(min_bp))
+    }
+
+    /// Same as `parse_expression`, but without taking a level of nesting.
+    fn parse_operators(&mut self, min_bp: u8) -> ExpressionResult {
+        let mut expr = self.prefix()?;
+
+        // Binding power of the last non associative operator taking `expr` as its left side
+        let mut non_associative_bp = None;
+
+        loop {
+            let (operator, custom) = match self.peek_token() {
+                Some(Token::Operator(operator)) => (Some(*operator), None),
+                Some(Token::CustomOperator(symbol)) => (None, Some(*symbol)),
+                _ => (None, None),
+            };
+            let custom = custom.and_then(|symbol| self.find_custom_operator(symbol));
+
+            if let Some(bp) = operator.and_then(postfix_binding_power) {
+                if bp < min_bp {
+                    break;
+                }
+
+                let operator = self.tokens.next().unwrap();
+                self.take_nodes(1);
+
+                let span = NodeSpan::new(operator.span());
+                expr = Expression::Postfix(UnaryExpr::spanned(operator.into_token(), span, expr));
+                continue;
+            }
+
+            // An operand right after another one is an implicit multiplication, which doesn't consume any token
+            let (infix, explicit) = match (
+                operator.filter(|op| infix_binding_power(*op).is_some()),
+                custom,
+            ) {
+                (Some(operator), _) => (Infix::Builtin(operator), true),
+                (None, Some(custom)) => (Infix::Custom(custom), true),
+                (None, None) if self.starts_implicit_multiplication() => {
+                    (Infix::Builtin(Operator::Star), false)
+                }
+                (None, None) => break,
+            };
+
+            let (bp, associativity) = infix.binding();
+            let (left_bp, right_bp) = binding_powers(bp, associativity);
+
+            if left_bp < min_bp {
+                break;
+            }
+
+            if associativity == Associativity::None {
+                if non_associative_bp == Some(bp) {
+                    let next = self.peek();
+
+                    return Err(ASTParseError::new(
+                        ASTParseErrorKind::ChainedComparison,
+                        next,
+                    ));
+                }
+
+                non_associative_bp = Some(bp);
+            }
+
+            if explicit {
+                self.tokens.next();
+            }
+
+            let right = self.parse_expression(right_bp)?;
+
+            self.take_nodes(1);
+
+            expr = match infix {
+                Infix::Builtin(operator) => {
+                    Expression::Binary(BinaryExpr::new(expr, Token::Operator(operator), right))
+                }
+                Infix::Custom(operator) => Expression::CustomBinary {
+                    operator,
+                    left: Box::new(expr),
+                    right: Box::new(right),
+                },
+            };
+        }
+
+        Ok(expr)
+    }
+
+    /// Builds the prefix operators, if any, applied to their operand.
+    ///
+    /// Operand only takes the operators binding tighter than prefix ones, so any grouping can be negated as a whole,
+    /// like `-(1 + 2)`. Negation binds looser than exponentiation by default, so `-2 ^ 2` is `-(2 ^ 2)`, unless the
+    /// parser has `SignPrecedence::AbovePower`. Prefix operators can be stacked, so `--5` is the negation of `-5`.
+    /// Stacked operators are collected in a loop rather than recursively, so a long run of them can't exhaust the
+    /// stack while parsing.
+    ///
+    /// Only operators of the same level are collected together, since the operand of the last one is parsed at
+    /// its level. So in `not -x > 0`, the operand of `-` is just `x`, while `not` takes `-x > 0`.
+    fn prefix(&mut self) -> ExpressionResult {
+        let mut operators = Vec::new();
+        let mut level = None;
+
+        while let Some(&Token::Operator(operator)) = self.peek_token() {
+            let Some(bp) = prefix_binding_power(operator, self.sign_precedence) else {
+                break;
+            };
+
+            if *level.get_or_insert(bp) != bp {
+                break;
+            }
+
+            let span = self.peek_span();
+            self.tokens.next();
+            operators.push((operator, span));
+        }
+
+        // Anything else must start a primary or a prefix operator of another level, so a missing operand (even at
+        // the end of input) is reported by them
+        let operand = match operators.last() {
+            // Every operator is a level of nesting of the operand
+            Some((operator, _)) => {
+                let bp = prefix_binding_power(*operator, self.sign_precedence).unwrap();
+                self.nested(operators.len(), |parser| parser.parse_operators(bp))?
+            }
+            None => return self.primary(),
+        };
+
+        self.take_nodes(operators.len());
+
+        // Innermost operator is the last one found, so operators are applied from right to left
+        Ok(operators
+            .into_iter()
+            .rev()
+            .fold(operand, |expr, (operator, span)| {
+                Expression::Unary(UnaryExpr::spanned(
+                    Token::Operator(operator),
+                    NodeSpan::new(span),
+                    expr,
+                ))
+            }))
+    }
+
+    /// Builds an expression with `levels` more levels of nesting, failing if they are deeper than allowed.
+    fn nested(
+        &mut self,
+        levels: usize,
+        parse: impl FnOnce(&mut Self) -> ExpressionResult,
+    ) -> ExpressionResult {
+        // Every operand is nested, so the nodes built before it are checked here too
+        if self.depth + levels > self.max_depth || self.nodes > self.max_nodes {
+            return Err(self.limit_exceeded(levels));
+        }
+
+        // Depth is restored even if parsing fails, so a parser recovering from errors starts over from the same depth
+        self.depth += levels;
+        let result = parse(self);
+        self.depth -= levels;
+
+        result
+    }
+
+    /// Counts `count` more nodes in the expressions built. Nodes are only checked against `max_nodes` by
+    /// `check_nodes`, since an error path in every method building a node makes their frames bigger.
+    fn take_nodes(&mut self, count: usize) {
+        self.nodes = self.nodes.saturating_add(count);
+    }
+
+    /// Fails if the expressions built have more nodes than allowed.
+    fn check_nodes(&mut self) -> Result<(), ASTParseError> {
+        if self.nodes <= self.max_nodes {
+            return Ok(());
+        }
+
+        Err(self.limit_exceeded(0))
+    }
+
+    /// Error for nesting `levels` more levels, or for the nodes built, exceeding the limits of the parser.
+    fn limit_exceeded(&mut self, levels: usize) -> ASTParseError {
+        let kind = match self.depth + levels > self.max_depth {
+            true => ASTParseErrorKind::TooDeep {
+                max_depth: self.max_depth,
+            },
+            false => ASTParseErrorKind::LimitExceeded {
+                max_nodes: self.max_nodes,
+            },
+        };
+
+        let next = self.peek();
+        ASTParseError::new(kind, next)
+    }
+
+    /// Builds an expression either right inside bars or not, restoring the previous state afterwards.
+    fn within_absolute_value(
+        &mut self,
+        in_absolute_value: bool,
+        parse: impl FnOnce(&mut Self) -> ExpressionResult,
+    ) -> ExpressionResult {
+        let outer = std::mem::replace(&mut self.in_absolute_value, in_absolute_value);
+        let result = parse(self);
+        self.in_absolute_value = outer;
+
+        result
+    }
+
+    /// Finds an implicit multiplication, which happens when an operand is right after another one without any
+    /// operator between them, like `2(3 + 4)` or `2pi`.
+    ///
+    /// Notice signs and numbers never start an implicit multiplication, so `2 -3` is still a subtraction.
+    fn starts_implicit_multiplication(&mut self) -> bool {
+        matches!(
+            self.peek_token(),
+            Some(Token::LeftParen | Token::Identifier(_) | Token::Constant(_))
+        )
+    }
+
+    /// Builds a primary, which is either a literal, a function call, a variable, a grouping or an absolute value.
+    ///
+    /// Production rule: `Primary -> Literal | Call | IDENTIFIER | "(" Conditional ")" | AbsoluteValue`
+    fn primary(&mut self) -> ExpressionResult {
+        if self.check(TokenKind::Pipe) {
+            return self.absolute_value();
+        }
+
+        if let Some(name_token) = self.advance_if(&[TokenKind::Identifier]) {
+            let &Token::Identifier(name) = name_token.token() else {
+                unreachable!("identifier kind is only given to identifier tokens")
+            };
+
+            let opened_at = self.peek_position();
+
+            // An identifier is only a function name if it is right before a `(`
+            if self.advance_if(&[TokenKind::LeftParen]).is_some() {
+                return self.call(name, name_token, opened_at);
+            }
+
+            self.take_nodes(1);
+
+            return Ok(Expression::Variable(name, NodeSpan::new(name_token.span())));
+        }
+
+        let opened_at = self.peek_position();
+
+        if self.advance_if(&[TokenKind::LeftParen]).is_none() {
+            // Literal is the last alternative, so its error lists everything a primary can start with
+            return self
+                .literal()
+                .map_err(|_| ASTParseError::expected_expression(self.peek()));
+        }
+
+        // Bars are closed by the same token opening them, so only parentheses can nest them
+        let expr = self.within_absolute_value(false, Self::conditional)?;
+
+        if self.advance_if(&[TokenKind::RightParen]).is_none() {
+            return Err(self.unclosed_paren(
+                opened_at,
+                ASTParseErrorKind::ExpectedClosingParen { opened_at },
+            ));
+        }
+
+        self.take_nodes(1);
+
+        Ok(Expression::Grouping(Box::new(expr)))
+    }
+
+    /// Builds an absolute value, like `|3 - 5|`, which is not a call, so functions named `abs` can't replace it.
+    ///
+    /// Since a `|` right inside bars always closes them, nested absolute values must be grouped, like `|(|x|) - 1|`.
+    ///
+    /// Production rule: `AbsoluteValue -> "|" Conditional "|"`
+    fn absolute_value(&mut self) -> ExpressionResult {
+        if self.in_absolute_value {
+            return Err(ASTParseError::new(
+                ASTParseErrorKind::NestedAbsoluteValue,
+                self.peek(),
+            ));
+        }
+
+        let opening_span = NodeSpan::new(self.peek_span());
+        self.tokens.next();
+
+        let expr = self.within_absolute_value(true, Self::conditional)?;
+        let closing_span = NodeSpan::new(self.peek_span());
+
+        self.expect(TokenKind::Pipe, "closing the absolute value")?;
+
+        self.take_nodes(1);
+
+        Ok(Expression::AbsoluteValue {
+            expr: Box::new(expr),
+            span: opening_span.to(closing_span),
+        })
+    }
+
+    /// Builds a function call, whose name (from `name_token`) and `(` were already consumed. The `(` is at
+    /// `opened_at`, if known.
+    ///
+    /// Production rule: `Call -> IDENTIFIER "(" (Conditional ("," Conditional)*)? ")"`
+    fn call(
+        &mut self,
+        name: Symbol,
+        name_token: I::Item,
+        opened_at: Option<Position>,
+    ) -> ExpressionResult {
+        let mut args = Vec::new();
+        let mut closing_span = NodeSpan::new(self.peek_span());
+
+        if self.advance_if(&[TokenKind::RightParen]).is_none() {
+            loop {
+                args.push(self.within_absolute_value(false, Self::conditional)?);
+
+                if self.advance_if(&[TokenKind::Comma]).is_some() {
+                    continue;
+                }
+
+                closing_span = NodeSpan::new(self.peek_span());
+
+                if self.advance_if(&[TokenKind::RightParen]).is_some() {
+                    break;
+                }
+
+                return Err(self.unclosed_paren(
+                    opened_at,
+                    ASTParseErrorKind::UnexpectedToken(vec![
+                        Expected::Token(Token::Comma),
+                        Expected::Token(Token::RightParen),
+                    ]),
+                ));
+            }
+        }
+
+        let span = NodeSpan::new(name_token.span()).to(closing_span);
+
+        self.check_arity(name, args.len(), name_token)?;
+        self.take_nodes(1);
+
+        Ok(Expression::Call { name, args, span })
+    }
+
+    /// Checks the function `name` accepts `found` arguments, if the parser was given functions. Errors point at
+    /// the `name_token` of the call.
+    ///
+    /// The head of a function definition, like `f(x)` in `f(x) = x + 1`, is not a call, so it is never checked.
+    fn check_arity(
+        &mut self,
+        name: Symbol,
+        found: usize,
+        name_token: I::Item,
+    ) -> Result<(), ASTParseError> {
+        if self.check(TokenKind::Equals) {
+            return Ok(());
+        }
+
+        let Some(functions) = &self.functions else {
+            return Ok(());
+        };
+
+        let kind = match functions.arity(name) {
+            Some(expected) if !expected.accepts(found) => ASTParseErrorKind::WrongArity {
+                name,
+                expected,
+                found,
+            },
+            None if self.unknown_functions == UnknownFunctions::Reject => {
+                ASTParseErrorKind::UnknownFunction(name)
+            }
+            _ => return Ok(()),
+        };
+
+        Err(ASTParseError::new(kind, Some(name_token)))
+    }
+
+    /// Error for a `(` at `opened_at` whose `)` was expected instead of the next token.
+    ///
+    /// Reaching the end of input means the `(` is never closed, so the error points at it. Otherwise, the error
+    /// is the `otherwise` one about the token found instead, like in `(1 + 2 3)`.
+    fn unclosed_paren(
+        &mut self,
+        opened_at: Option<Position>,
+        otherwise: ASTParseErrorKind,
+    ) -> ASTParseError {
+        let next = self.peek();
+
+        if self.is_at_end() {
+            return ASTParseError::new(ASTParseErrorKind::UnclosedLeftParen { opened_at }, next);
+        }
+
+        ASTParseError::new(otherwise, next)
+    }
+
+    /// Builds a literal.
+    ///
+    /// Literal is a `terminal` symbol, so does not belongs to any production rule
+    fn literal(&mut self) -> ExpressionResult {
+        if let Some(literal) = self.advance_if(&[TokenKind::Number, TokenKind::Constant]) {
+            self.take_nodes(1);
+
+            let span = literal.span();
+            let token = literal.into_token();
+
+            // Lexeme is only known if the token has a span into a known source
+            let lexeme = span
+                .zip(self.source.as_deref())
+                .and_then(|(span, source)| source.get(span.start..span.end));
+
+            let literal = match lexeme {
+                Some(lexeme) => LiteralExpr::written(token, lexeme),
+                None => LiteralExpr::new(token),
+            };
+
+            return Ok(Expression::Literal(literal, NodeSpan::new(span)));
+        }
+
+        Err(ASTParseError::unexpected(&[Expected::Number], self.peek()))
+    }
+
+    /// Checks if the next token is of the given kind, without consuming it.
+    fn check(&mut self, kind: TokenKind) -> bool {
+        self.tokens
+            .peek()
+            .is_some_and(|token| token.token().kind() == kind)
+    }
+
+    /// Consumes the next token if it is of any of the given kinds.
+    ///
+    /// End of input never matches, so it is left in the stream for reporting what was expected instead.
+    fn advance_if(&mut self, kinds: &[TokenKind]) -> Option<I::Item> {
+        let kind = kinds
+            .iter()
+            .copied()
+            .filter(|kind| *kind != TokenKind::Eof)
+            .find(|kind| self.check(*kind))?;
+
+        self.tokens.next_if(|token| token.token().kind() == kind)
+    }
+
+    /// Consumes the next token, which must be of the given kind. Otherwise, fails with an error telling what was
+    /// expected along with the `context` requiring it, like `expected ':' between the branches of a conditional`.
+    fn expect(&mut self, kind: TokenKind, context: &'static str) -> Result<I::Item, ASTParseError> {
+        self.advance_if(&[kind]).ok_or_else(|| {
+            ASTParseError::new(
+                ASTParseErrorKind::ExpectedToken {
+                    expected: kind,
+                    context,
+                },
+                self.peek(),
+            )
+        })
+    }
+
+    /// Takes a look at the next token without consume it.
+    fn peek(&mut self) -> Option<I::Item> {
+        self.tokens.peek().copied()
+    }
+
+    /// Custom operator registered with the given symbol, if any.
+    fn find_custom_operator(&self, symbol: char) -> Option<CustomOperator> {
+        self.custom_operators
+            .iter()
+            .find(|custom| custom.symbol == symbol)
+            .copied()
+    }
+
+    /// Takes a look at the next token without consuming nor cloning it, leaving out where it is in the source.
+    fn peek_token(&mut self) -> Option<&Token> {
+        self.tokens.peek().map(|token| token.token())
+    }
+
+    /// Range of the source of the next token, if known.
+    fn peek_span(&mut self) -> Option<Span> {
+        self.tokens.peek().and_then(|token| token.span())
+    }
+
+    /// Position of the next token, if known.
+    fn peek_position(&mut self) -> Option<Position> {
+        self.tokens.peek().and_then(|token| token.position())
+    }
+
+    /// Checks if the tokens stream is over, either by reaching `Token::Eof` or running out of tokens.
+    fn is_at_end(&mut self) -> bool {
+        matches!(self.peek_token(), None | Some(Token::Eof(_)))
+    }
+}
+
+// Recovery methods
+impl<I: Iterator<Item: ParserToken>> Parser<I> {
+    /// Same as `statements`, but reporting all the errors found instead of stopping at the first one.
+    ///
+    /// When a statement fails, its remaining tokens are skipped up to the next `;`, so the following statements
+    /// are still built. Empty statements, like the one between `1;;2`, are reported without skipping anything.
+    pub fn parse_all_with_recovery(&mut self) -> (Vec<Expression>, Vec<ASTParseError>) {
+        let mut exprs = Vec::new();
+        let mut errors = Vec::new();
+
+        loop {
+            if self.is_at_end() {
+                return (exprs, errors);
+            }
+
+            if let Some(semicolon) = self.advance_if(&[TokenKind::Semicolon]) {
+                errors.push(ASTParseError::new(
+                    ASTParseErrorKind::EmptyStatement,
+                    Some(semicolon),
+                ));
+                continue;
+            }
+
+            match self.statement() {
+                Ok(expr) => exprs.push(expr),
+                Err(err) => {
+                    errors.push(err);
+                    self.synchronize();
+                }
+            }
+        }
+    }
+
+    /// Skips the tokens of the failed statement, including the `;` ending it, but never the end of input.
+    fn synchronize(&mut self) {
+        while !self.is_at_end() {
+            if let Some(Token::Semicolon) = self.tokens.next().map(ParserToken::into_token) {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod ast_parser_tests {
+    use std::{sync::mpsc, thread};
+
+    use crate::{
+        ast::expressions::{BinaryExpr, Expression, UnaryExpr},
+        tokenizer::{
+            builder::TokenizerBuilder,
+            parser::Tokenizer,
+            symbol::Symbol,
+            tokens::{Constant, Operator, Position, Span, Token, TokenKind},
+        },
+    };
+
+    use super::{
+        super::{
+            functions::{Arity, FunctionRegistry},
+            operators::{Associativity, CustomOperator, Precedence},
+            value::Value,
+        },
+        ASTParseErrorKind, Expected, ParseError, ParseOutcome, Parser, SignPrecedence,
+        UnknownFunctions,
+    };
+
+    #[test]
+    fn test_literal_constant() {
+        // Arrange
+        let literal_token = Token::Constant(Constant::Pi);
+        let mut parser = Parser::new([literal_token].into_iter());
+
+        // Act
+        let literal_expr = parser.literal();
+
+        // Assert
+        assert_eq!(
+            literal_expr.unwrap(),
+            Expression::literal(literal_token),
+            "should build literal expression for constant token"
+        )
+    }
+
+    #[test]
+    fn test_literal_success() {
+        // Arrange
+        let literal_token = Token::float(10.0);
+        let tokens_source = [literal_token].into_iter();
+
+        let mut parser = Parser::new(tokens_source);
+        let expected_expr = Expression::literal(literal_token);
+
+        // Act
+        let literal_expr = parser.literal();
+
+        // Assert
+        assert_eq!(
+            literal_expr.unwrap(),
+            expected_expr,
+            "should build literal expression for given token"
+        );
+
+        assert_eq!(
+            parser.tokens.count(),
+            0,
+            "should consume token from tokens source once expression was built"
+        )
+    }
+
+    #[test]
+    fn test_literal_fails() {
+        // Arrange
+        let non_literal_token = Token::Operator(Operator::Star);
+        let tokens_source = [non_literal_token].into_iter();
+        let mut parser = Parser::new(tokens_source);
+
+        // Act
+        let result = parser.literal();
+
+        // Assert
+        assert!(
+            result.is_err(),
+            "should return error if cannot parse literal expression"
+        )
+    }
+
+    #[test]
+    fn test_literal_fails_at_end_of_input() {
+        // Arrange
+        let tokens = Tokenizer::new("2 +").tokenize().unwrap();
+        let mut parser = Parser::new(tokens.into_iter());
+
+        // Act
+        let err = parser.program().unwrap_err();
+
+        // Assert
+        assert_eq!(
+            err.to_string(),
+            "[AST PARSE ERROR]: expected a number, an identifier, '(' or '|', found end of input at 1:4",
+            "should report the position where the source ended"
+        )
+    }
+
+    #[test]
+    fn test_from_source() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("1 + 2 * 3", "(1 + (2 * 3))"),
+            ("|x - 1|", "|(x - 1)|"),
+            ("max(1, 2)^2", "(max(1, 2) ^ 2)"),
+        ];
+
+        for (source, expected_expr) in cases {
+            // Act
+            let expr = Parser::from_source(source);
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should tokenize and parse the source in one call"
+            )
+        }
+    }
+
+    #[test]
+    fn test_from_source_fails() {
+        // Arrange
+        // Each source is paired with its expected error
+        let cases = [
+            (
+                "1 + ~",
+                "[TOKENIZER ERROR] at 1:5 (offset 4): unexpected character '~'",
+            ),
+            (
+                "1 + * 2",
+                "[AST PARSE ERROR]: expected a number, an identifier, '(' or '|', found '*' at 1:5",
+            ),
+        ];
+
+        for (source, expected_error) in cases {
+            // Act
+            let err = Parser::from_source(source).unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                expected_error,
+                "should report errors of both tokenizer and parser"
+            )
+        }
+
+        assert!(
+            matches!(Parser::from_source("~"), Err(ParseError::Tokenizer(_))),
+            "should wrap tokenizer errors"
+        );
+        assert!(
+            matches!(Parser::from_source("2 +"), Err(ParseError::Parser(_))),
+            "should wrap parser errors"
+        );
+    }
+
+    #[test]
+    fn test_statements_from_source() {
+        // Act
+        let program = Parser::statements_from_source("x = 2; x * 3");
+
+        // Assert
+        assert_eq!(
+            program.unwrap().to_string(),
+            "x = 2; (x * 3)",
+            "should tokenize and parse every statement in one call"
+        );
+        assert!(
+            matches!(
+                Parser::statements_from_source("x = 2; 1 +"),
+                Err(ParseError::Parser(_))
+            ),
+            "should fail like statements does"
+        );
+    }
+
+    #[test]
+    fn test_errors_carry_found_token() {
+        // Arrange
+        // Each source is paired with the token its error should be triggered by
+        let cases = [
+            ("2 + *", Some(Token::Operator(Operator::Star))),
+            ("(1 + 2 ,", Some(Token::Comma)),
+            ("max(1 = 2)", Some(Token::Equals)),
+            ("a ? b ;", Some(Token::Semicolon)),
+            ("1 + 2)", Some(Token::RightParen)),
+            ("1 < 2 < 3", Some(Token::Operator(Operator::Less))),
+            ("2 +", None),
+        ];
+
+        for (source, expected_found) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.into_iter());
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.found()
+                    .filter(|token| !matches!(token, Token::Eof(_)))
+                    .copied(),
+                expected_found,
+                "should carry the token which triggered the error"
+            )
+        }
+    }
+
+    #[test]
+    fn test_literal_fails_describing_found_token() {
+        // Arrange
+        let tokens = Tokenizer::new("2 * * 3").tokenize().unwrap();
+        let mut parser = Parser::new(tokens.into_iter());
+
+        // Act
+        let err = parser.program().unwrap_err();
+
+        // Assert
+        assert_eq!(
+            err.to_string(),
+            "[AST PARSE ERROR]: expected a number, an identifier, '(' or '|', found '*'",
+            "should report the token found instead of a number"
+        )
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        // Arrange
+        // Each source is paired with the kind of its error
+        let cases = [
+            (
+                "max(1 2)",
+                ASTParseErrorKind::UnexpectedToken(vec![
+                    Expected::Token(Token::Comma),
+                    Expected::Token(Token::RightParen),
+                ]),
+            ),
+            ("1 + * 2", ASTParseErrorKind::ExpectedExpression),
+            (
+                "1 +",
+                ASTParseErrorKind::UnexpectedEof(vec![
+                    Expected::Number,
+                    Expected::Identifier,
+                    Expected::Token(Token::LeftParen),
+                    Expected::Token(Token::Pipe),
+                ]),
+            ),
+            (
+                "1 ? 2;",
+                ASTParseErrorKind::ExpectedToken {
+                    expected: TokenKind::Colon,
+                    context: "between the branches of a conditional",
+                },
+            ),
+            ("1 + 2)", ASTParseErrorKind::UnmatchedRightParen),
+            (
+                "(1 + 2",
+                ASTParseErrorKind::UnclosedLeftParen {
+                    opened_at: Some(Position::new(1, 1)),
+                },
+            ),
+            (
+                "(1 2)",
+                ASTParseErrorKind::ExpectedClosingParen {
+                    opened_at: Some(Position::new(1, 1)),
+                },
+            ),
+            ("1 2", ASTParseErrorKind::TrailingToken),
+            (
+                "((((((((((1))))))))))",
+                ASTParseErrorKind::TooDeep { max_depth: 8 },
+            ),
+            ("2 = 1", ASTParseErrorKind::InvalidAssignmentTarget),
+            ("a = b = 1", ASTParseErrorKind::ChainedAssignment),
+            ("1 < 2 < 3", ASTParseErrorKind::ChainedComparison),
+            ("| |x| |", ASTParseErrorKind::NestedAbsoluteValue),
+            (
+                "sqrt()",
+                ASTParseErrorKind::WrongArity {
+                    name: Symbol::intern("sqrt"),
+                    expected: Arity::Exact(1),
+                    found: 0,
+                },
+            ),
+            (
+                "foo(1)",
+                ASTParseErrorKind::UnknownFunction(Symbol::intern("foo")),
+            ),
+            ("1;;2", ASTParseErrorKind::EmptyStatement),
+        ];
+
+        for (source, expected_kind) in cases {
+            let tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied())
+                .max_depth(8)
+                .functions(FunctionRegistry::builtins())
+                .unknown_functions(UnknownFunctions::Reject);
+
+            // Act
+            let err = parser.statements().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.kind(),
+                &expected_kind,
+                "should classify the error of `{source}` without looking at its message"
+            )
+        }
+    }
+
+    #[test]
+    fn test_errors_with_spanned_tokens() {
+        // Arrange
+        // Each source is paired with the expected kind, found token and position of its error
+        let cases = [
+            (
+                "2 * * 3",
+                ASTParseErrorKind::ExpectedExpression,
+                Token::Operator(Operator::Star),
+                Position::new(1, 5),
+            ),
+            (
+                "(1 + 2 3)",
+                ASTParseErrorKind::ExpectedClosingParen {
+                    opened_at: Some(Position::new(1, 1)),
+                },
+                Token::integer(3),
+                Position::new(1, 8),
+            ),
+            (
+                "2 3",
+                ASTParseErrorKind::TrailingToken,
+                Token::integer(3),
+                Position::new(1, 3),
+            ),
+            (
+                "1 +\n2)",
+                ASTParseErrorKind::UnmatchedRightParen,
+                Token::RightParen,
+                Position::new(2, 2),
+            ),
+        ];
+
+        for (source, expected_kind, expected_found, expected_position) in cases {
+            let tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                (err.kind(), err.found().copied(), err.position()),
+                (
+                    &expected_kind,
+                    Some(expected_found),
+                    Some(expected_position)
+                ),
+                "should report what was expected, the token found instead and where it is"
+            )
+        }
+    }
+
+    #[test]
+    fn test_errors_display_position() {
+        // Arrange
+        let source = "2 * * 3";
+        let spanned_tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+
+        // Act
+        let spanned_err = Parser::new(spanned_tokens.iter().copied())
+            .program()
+            .unwrap_err();
+        let err = Parser::new(tokens.iter().copied()).program().unwrap_err();
+
+        // Assert
+        assert_eq!(
+            spanned_err.to_string(),
+            "[AST PARSE ERROR]: expected a number, an identifier, '(' or '|', found '*' at 1:5",
+            "should show where the error is when tokens are spanned"
+        );
+
+        assert_eq!(
+            (err.position(), err.to_string()),
+            (
+                None,
+                "[AST PARSE ERROR]: expected a number, an identifier, '(' or '|', found '*'"
+                    .to_string()
+            ),
+            "should not know where the error is when tokens are bare"
+        )
+    }
+
+    #[test]
+    fn test_statements() {
+        // Arrange
+        // Each source is paired with its expected statements
+        let cases = [
+            ("1+1; 2*3", vec!["(1 + 1)", "(2 * 3)"]),
+            ("1; 2;", vec!["1", "2"]),
+            ("a = 2; a * 3; a", vec!["a = 2", "(a * 3)", "a"]),
+            ("1 +\n2; 3", vec!["(1 + 2)", "3"]),
+            ("", vec![]),
+        ];
+
+        for (source, expected_statements) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let program = parser.statements();
+
+            // Assert
+            assert_eq!(
+                program
+                    .unwrap()
+                    .statements()
+                    .iter()
+                    .map(Expression::to_string)
+                    .collect::<Vec<String>>(),
+                expected_statements,
+                "should build every statement separated by `;`"
+            )
+        }
+    }
+
+    #[test]
+    fn test_statements_empty_fails() {
+        // Arrange
+        // Each source is paired with the position of the `;` after its empty statement
+        let cases = [
+            ("1;;2", Position::new(1, 3)),
+            ("1; ; 2", Position::new(1, 4)),
+            (";1", Position::new(1, 1)),
+            (" ; ", Position::new(1, 2)),
+            ("1; 2;\n;", Position::new(2, 1)),
+        ];
+
+        for (source, expected_position) in cases {
+            let tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.statements().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                (err.kind(), err.found().copied(), err.position()),
+                (
+                    &ASTParseErrorKind::EmptyStatement,
+                    Some(Token::Semicolon),
+                    Some(expected_position)
+                ),
+                "should report empty statements at the `;` after them"
+            )
+        }
+
+        assert_eq!(
+            Parser::statements_from_source("1;;2")
+                .unwrap_err()
+                .to_string(),
+            "[AST PARSE ERROR]: empty statement before ';' at 1:3",
+            "should describe empty statements"
+        )
+    }
+
+    #[test]
+    fn test_statements_assignment() {
+        // Arrange
+        let tokens = Tokenizer::new("x = 3 * 4; y = -x; x").tokenize().unwrap();
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        // Act
+        let program = parser.statements();
+
+        // Assert
+        assert_eq!(
+            program.unwrap().to_string(),
+            "x = (3 * 4); y = (-x); x",
+            "should build assignments of the variables before `=`"
+        )
+    }
+
+    #[test]
+    fn test_statements_assignment_fails() {
+        // Arrange
+        // Each source is paired with its expected error kind and position
+        let cases = [
+            (
+                "a = b = 2",
+                ASTParseErrorKind::ChainedAssignment,
+                Position::new(1, 7),
+            ),
+            (
+                "2x = 4",
+                ASTParseErrorKind::InvalidAssignmentTarget,
+                Position::new(1, 4),
+            ),
+            (
+                "x + 1 = 4",
+                ASTParseErrorKind::InvalidAssignmentTarget,
+                Position::new(1, 7),
+            ),
+        ];
+
+        for (source, expected_kind, expected_position) in cases {
+            let tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.statements().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                (err.kind(), err.found().copied(), err.position()),
+                (&expected_kind, Some(Token::Equals), Some(expected_position)),
+                "should only assign single variables"
+            )
+        }
+
+        assert_eq!(
+            Parser::new(Tokenizer::new("a = b = 2").tokenize().unwrap().into_iter())
+                .statements()
+                .unwrap_err()
+                .to_string(),
+            "[AST PARSE ERROR]: chained assignments like 'a = b = 2' are not supported",
+            "should explain chained assignments are not supported"
+        )
+    }
+
+    #[test]
+    fn test_statements_function_definition() {
+        // Arrange
+        let tokens = Tokenizer::new("f(x) = x * x + 1; g(a, b) = max(a, b) - f(a); f(3)")
+            .tokenize()
+            .unwrap();
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        // Act
+        let program = parser.statements();
+
+        // Assert
+        assert_eq!(
+            program.unwrap().to_string(),
+            "f(x) = ((x * x) + 1); g(a, b) = (max(a, b) - f(a)); f(3)",
+            "should build definitions of the functions whose heads are before `=`"
+        )
+    }
+
+    #[test]
+    fn test_statements_function_definition_fails() {
+        // Arrange
+        // Each source is paired with its expected error kind
+        let cases = [
+            ("f(1) = 2", ASTParseErrorKind::InvalidParameter),
+            ("f(x + 1) = x", ASTParseErrorKind::InvalidParameter),
+            (
+                "f(x, y, x) = x",
+                ASTParseErrorKind::DuplicateParameter(Symbol::intern("x")),
+            ),
+            ("2 * f(x) = x", ASTParseErrorKind::InvalidAssignmentTarget),
+        ];
+
+        for (source, expected_kind) in cases {
+            let tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.statements().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                (err.kind(), err.found().copied()),
+                (&expected_kind, Some(Token::Equals)),
+                "should only define functions whose parameters are different variables"
+            )
+        }
+
+        assert_eq!(
+            Parser::new(
+                Tokenizer::new("f(x, x) = x")
+                    .tokenize()
+                    .unwrap()
+                    .into_iter()
+            )
+            .statements()
+            .unwrap_err()
+            .to_string(),
+            "[AST PARSE ERROR]: parameter 'x' is repeated in function definition",
+            "should name the repeated parameter"
+        )
+    }
+
+    #[test]
+    fn test_statements_function_definition_arity() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("f(x) = x; f(1)", Ok("f(x) = x; f(1)".to_string())),
+            (
+                "f(x) = x; f(1, 2)",
+                Err(ASTParseErrorKind::WrongArity {
+                    name: Symbol::intern("f"),
+                    expected: Arity::Exact(1),
+                    found: 2,
+                }),
+            ),
+            (
+                "f(1)",
+                Err(ASTParseErrorKind::UnknownFunction(Symbol::intern("f"))),
+            ),
+        ];
+
+        for (source, expected_result) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied())
+                .functions(FunctionRegistry::builtins())
+                .unknown_functions(UnknownFunctions::Reject);
+
+            // Act
+            let result = parser.statements();
+
+            // Assert
+            assert_eq!(
+                result
+                    .map(|program| program.to_string())
+                    .map_err(|err| err.kind().clone()),
+                expected_result,
+                "should check calls against the functions defined by previous statements"
+            )
+        }
+    }
+
+    #[test]
+    fn test_statements_fails() {
+        // Arrange
+        // Each source is paired with its expected error
+        let cases = [
+            (
+                "1; 2 3",
+                "[AST PARSE ERROR]: unexpected token '3' after expression",
+            ),
+            (
+                "1 +; 2",
+                "[AST PARSE ERROR]: expected a number, an identifier, '(' or '|', found ';'",
+            ),
+            ("(1; 2)", "[AST PARSE ERROR]: expected ')', found ';'"),
+        ];
+
+        for (source, expected_error) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.statements().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                expected_error,
+                "should fail at the first malformed statement"
+            )
+        }
+    }
+
+    #[test]
+    fn test_statements_fails_in_middle_statement() {
+        // Arrange
+        let tokens = Tokenizer::new("a = 2; a +* 3; a")
+            .tokenize_spanned()
+            .unwrap();
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        // Act
+        let err = parser.statements().unwrap_err();
+
+        // Assert
+        assert_eq!(
+            (err.found().copied(), err.position()),
+            (
+                Some(Token::Operator(Operator::Star)),
+                Some(Position::new(1, 11))
+            ),
+            "should report where the failing statement is in the whole source"
+        )
+    }
+
+    #[test]
+    fn test_parse_or_incomplete() {
+        // Arrange
+        // Each source is paired with whether it needs more input
+        let cases = [
+            ("1 +", true),
+            ("x = 2; 3 *", true),
+            ("max(1,", true),
+            ("(1 + 2", true),
+            ("|x", true),
+            ("a ? b", true),
+            ("1 + 2", false),
+            ("1;", false),
+        ];
+
+        for (source, expected_incomplete) in cases {
+            let tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let outcome = parser.parse_or_incomplete();
+
+            // Assert
+            assert_eq!(
+                outcome.unwrap() == ParseOutcome::NeedMoreInput,
+                expected_incomplete,
+                "should ask for more input only when input ends in the middle of an expression"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_or_incomplete_fails() {
+        // Arrange
+        let tokens = Tokenizer::new("1 + *").tokenize().unwrap();
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        // Act
+        let err = parser.parse_or_incomplete().unwrap_err();
+
+        // Assert
+        assert_eq!(
+            err.kind(),
+            &ASTParseErrorKind::ExpectedExpression,
+            "should fail on errors which more input can't fix"
+        );
+        assert!(
+            !err.is_incomplete(),
+            "should not consider errors before the end of input incomplete"
+        );
+    }
+
+    #[test]
+    fn test_parse_or_incomplete_with_more_tokens() {
+        // Arrange
+        let mut tokens = Tokenizer::new("1 +").tokenize().unwrap();
+        let outcome = Parser::from_slice(&tokens).parse_or_incomplete();
+
+        // Act
+        // Tokens of the next line replace the end of input of the previous one
+        tokens.pop();
+        tokens.extend(Tokenizer::new("2").tokenize().unwrap());
+        let completed = Parser::from_slice(&tokens).parse_or_incomplete();
+
+        // Assert
+        assert_eq!(
+            outcome.unwrap(),
+            ParseOutcome::NeedMoreInput,
+            "should ask for more input before the operand"
+        );
+
+        let Ok(ParseOutcome::Complete(program)) = completed else {
+            panic!("should complete the expression with the appended tokens")
+        };
+
+        assert_eq!(
+            program.eval(),
+            Ok(Some(Value::Integer(3))),
+            "should evaluate the completed expression"
+        );
+    }
+
+    #[test]
+    fn test_unexpected_eof_kind() {
+        // Arrange
+        let tokens = Tokenizer::new("2 *").tokenize().unwrap();
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        // Act
+        let err = parser.program().unwrap_err();
+
+        // Assert
+        assert!(
+            matches!(err.kind(), ASTParseErrorKind::UnexpectedEof(_)),
+            "should tell the end of input apart from other unexpected tokens"
+        );
+        assert_eq!(
+            err.to_string(),
+            "[AST PARSE ERROR]: expected a number, an identifier, '(' or '|', found end of input at 1:4",
+            "should describe what was expected before the end of input"
+        );
+    }
+
+    #[test]
+    fn test_parse_all_with_recovery() {
+        // Arrange
+        let tokens = Tokenizer::new("1 + * 2;\n3;;\n(4 + 5; 6 7")
+            .tokenize_spanned()
+            .unwrap();
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        // Act
+        let (exprs, errors) = parser.parse_all_with_recovery();
+
+        // Assert
+        assert_eq!(
+            exprs
+                .iter()
+                .map(Expression::to_string)
+                .collect::<Vec<String>>(),
+            vec!["3"],
+            "should build the statements without errors"
+        );
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|err| (err.found().copied(), err.position()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    Some(Token::Operator(Operator::Star)),
+                    Some(Position::new(1, 5))
+                ),
+                (Some(Token::Semicolon), Some(Position::new(2, 3))),
+                (Some(Token::Semicolon), Some(Position::new(3, 7))),
+                (Some(Token::integer(7)), Some(Position::new(3, 11))),
+            ],
+            "should report the error of every failing statement where it is"
+        )
+    }
+
+    #[test]
+    fn test_parse_all_with_recovery_without_errors() {
+        // Arrange
+        let tokens = Tokenizer::new("1 + 2; 3 * 4;").tokenize().unwrap();
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        // Act
+        let (exprs, errors) = parser.parse_all_with_recovery();
+
+        // Assert
+        assert_eq!(
+            (
+                exprs
+                    .iter()
+                    .map(Expression::to_string)
+                    .collect::<Vec<String>>(),
+                errors
+            ),
+            (vec!["(1 + 2)".to_string(), "(3 * 4)".to_string()], vec![]),
+            "should build every statement when there are no errors"
+        )
+    }
+
+    #[test]
+    fn test_program_from_slice() {
+        // Arrange
+        let tokens = Tokenizer::new("2 * (3 + x) - 1").tokenize().unwrap();
+        let spanned_tokens = Tokenizer::new("2 * (3 + x) - 1")
+            .tokenize_spanned()
+            .unwrap();
+
+        // Act
+        let first = Parser::from_slice(&tokens).program();
+        let second = Parser::from_slice(&tokens).program();
+        let spanned = Parser::from_slice(&spanned_tokens).program();
+
+        // Assert
+        assert_eq!(
+            first.unwrap(),
+            second.unwrap(),
+            "should parse the same tokens twice to the same expression"
+        );
+        assert_eq!(
+            spanned.unwrap(),
+            Parser::new(tokens.into_iter()).program().unwrap(),
+            "should parse borrowed tokens like owned ones"
+        );
+        assert_eq!(
+            Parser::from_slice(&spanned_tokens)
+                .program()
+                .unwrap()
+                .span(),
+            Some(Span::new(0, 15)),
+            "should keep the spans of borrowed spanned tokens"
+        );
+    }
+
+    #[test]
+    fn test_program_from_non_clone_iterator() {
+        // Arrange
+        // Tokens are received from a channel, whose iterator can't be cloned
+        let (sender, receiver) = mpsc::channel();
+
+        for token in Tokenizer::new("2 * (3 + 4)").tokenize().unwrap() {
+            sender.send(token).unwrap();
+        }
+
+        drop(sender);
+
+        let mut parser = Parser::new(receiver.into_iter());
+
+        // Act
+        let expr = parser.program();
+
+        // Assert
+        assert_eq!(
+            expr.unwrap().to_string(),
+            "(2 * (3 + 4))",
+            "should parse tokens from iterators which can't be cloned"
+        )
+    }
+
+    #[test]
+    fn test_program_large_tokens_stream() {
+        // Arrange
+        // 100k tokens, alternating numbers and operators
+        const TERMS: usize = 50_000;
+        let source = format!("{}1", "1 + 2 * ".repeat(TERMS / 2));
+        let tokens = Tokenizer::new(&source).tokenize().unwrap();
+
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        // Act
+        // Expression is as deep as the number of terms, and dropping it is recursive, so it needs a bigger stack
+        // than the one of test threads. Notice parsing it doesn't nest, since operators of the same level are
+        // built in a loop
+        let result = thread::scope(|scope| {
+            thread::Builder::new()
+                .stack_size(256 * 1024 * 1024)
+                .spawn_scoped(scope, || parser.program().map(|expr| expr.eval().unwrap()))
+                .unwrap()
+                .join()
+                .unwrap()
+        });
+
+        // Assert
+        assert_eq!(
+            result.unwrap(),
+            (TERMS + 1) as f64,
+            "should parse and evaluate every token of a big stream"
+        )
+    }
+
+    #[test]
+    fn test_program_long_operator_chain() {
+        // Arrange
+        const TERMS: usize = 10_000;
+        let source = vec!["1"; TERMS].join(" - ");
+        let tokens = Tokenizer::new(&source).tokenize().unwrap();
+
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        // Act
+        // Chain is as deep as its number of terms, and dropping it is recursive, so it needs a bigger stack than
+        // the one of test threads
+        let result = thread::scope(|scope| {
+            thread::Builder::new()
+                .stack_size(64 * 1024 * 1024)
+                .spawn_scoped(scope, || parser.program().map(|expr| expr.eval().unwrap()))
+                .unwrap()
+                .join()
+                .unwrap()
+        });
+
+        // Assert
+        // Only grouping the chain from the left gives `1 - (TERMS - 1)`
+        assert_eq!(
+            result.unwrap(),
+            1.0 - (TERMS - 1) as f64,
+            "should build a long chain of operators of the same level from the left"
+        )
+    }
+
+    #[test]
+    fn test_program_fails_by_trailing_tokens() {
+        // Arrange
+        // Each source is paired with its expected error
+        let cases = [
+            (
+                "2 3",
+                "[AST PARSE ERROR]: unexpected token '3' after expression",
+            ),
+            (
+                "1 + 2 4 * 5",
+                "[AST PARSE ERROR]: unexpected token '4' after expression",
+            ),
+            ("1+2 )", "[AST PARSE ERROR]: missing '(' for ')'"),
+            // A spaced `%` is the modulo operator, so it is missing its right operand
+            (
+                "15 %",
+                "[AST PARSE ERROR]: expected a number, an identifier, '(' or '|', found end of input at 1:5",
+            ),
+        ];
+
+        for (source, expected_error) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                expected_error,
+                "should not ignore tokens after a complete expression"
+            )
+        }
+    }
+
+    #[test]
+    fn test_program_consumes_whole_stream() {
+        // Arrange
+        let tokens = Tokenizer::new("1 + 2 * 3").tokenize().unwrap();
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        // Act
+        let expr = parser.program();
+
+        // Assert
+        assert_eq!(
+            expr.unwrap().to_string(),
+            "(1 + (2 * 3))",
+            "should build the expression when no token is left after it"
+        )
+    }
+
+    #[test]
+    fn test_primary_nested_grouping() {
+        // Arrange
+        let literal_token = Token::integer(2);
+        let tokens_source = [
+            Token::LeftParen,
+            Token::LeftParen,
+            literal_token,
+            Token::RightParen,
+            Token::RightParen,
+        ]
+        .into_iter();
+
+        let mut parser = Parser::new(tokens_source);
+
+        // Act
+        let primary_expr = parser.primary();
+
+        // Assert
+        assert_eq!(
+            primary_expr.unwrap(),
+            Expression::Grouping(Box::new(Expression::Grouping(Box::new(
+                Expression::literal(literal_token)
+            )))),
+            "should keep a grouping for each pair of parentheses"
+        );
+
+        assert_eq!(
+            parser.tokens.count(),
+            0,
+            "should consume every paren of the grouping"
+        )
+    }
+
+    #[test]
+    fn test_grouping_changes_precedence() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("(1 + 2) * 3", "((1 + 2) * 3)"),
+            ("1 + 2 * 3", "(1 + (2 * 3))"),
+            ("2 * (3 - (4 - 1))", "(2 * (3 - (4 - 1)))"),
+            ("-(1 + 2)", "(-(1 + 2))"),
+            ("(50%) * 2", "((50%) * 2)"),
+        ];
+
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should relate the tokens inside parens before the ones outside"
+            )
+        }
+    }
+
+    #[test]
+    fn test_grouping_fails_by_unbalanced_parens() {
+        // Arrange
+        // Each source is paired with its expected error
+        let cases = [
+            (
+                "(1 + 2",
+                "[AST PARSE ERROR]: missing ')' for '(', found end of input at 1:7",
+            ),
+            ("(1 + 2 3)", "[AST PARSE ERROR]: expected ')', found '3'"),
+            ("1 + 2)", "[AST PARSE ERROR]: missing '(' for ')'"),
+            (
+                "()",
+                "[AST PARSE ERROR]: expected a number, an identifier, '(' or '|', found ')'",
+            ),
+        ];
+
+        for (source, expected_error) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                expected_error,
+                "should report which paren is missing"
+            )
+        }
+    }
+
+    #[test]
+    fn test_unbalanced_parens_with_positions() {
+        // Arrange
+        // Each source is paired with its expected error, pointing at the unmatched paren
+        let cases = [
+            (
+                "(1 + (2 * 3)",
+                "[AST PARSE ERROR]: missing ')' for '(' at 1:1, found end of input at 1:13",
+            ),
+            (
+                "(1 + (2 * 3",
+                "[AST PARSE ERROR]: missing ')' for '(' at 1:6, found end of input at 1:12",
+            ),
+            (
+                "((1) + (2 *\n(3)",
+                "[AST PARSE ERROR]: missing ')' for '(' at 1:8, found end of input at 2:4",
+            ),
+            (
+                "2 * max(1, (2)",
+                "[AST PARSE ERROR]: missing ')' for '(' at 1:8, found end of input at 1:15",
+            ),
+            ("1 + 2)", "[AST PARSE ERROR]: missing '(' for ')' at 1:6"),
+            ("(1 + 2))", "[AST PARSE ERROR]: missing '(' for ')' at 1:8"),
+            (
+                "(1 + 2 3)",
+                "[AST PARSE ERROR]: expected ')', found '3' at 1:8",
+            ),
+        ];
+
+        for (source, expected_error) in cases {
+            let tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                expected_error,
+                "should point at the paren which is not matched"
+            )
+        }
+    }
+
+    #[test]
+    fn test_power_associativity_and_precedence() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("2 ^ 3 ^ 2", "(2 ^ (3 ^ 2))"),
+            ("2 * 3 ^ 2", "(2 * (3 ^ 2))"),
+            ("2 ^ 3 * 2", "((2 ^ 3) * 2)"),
+            ("-2 ^ 2", "(-(2 ^ 2))"),
+            ("(-2) ^ 2", "((-2) ^ 2)"),
+            ("2 ^ -3", "(2 ^ (-3))"),
+            ("2 ^ -3 ^ 2", "(2 ^ (-(3 ^ 2)))"),
+            ("-2 * 3", "((-2) * 3)"),
+            ("(2 ^ 3) ^ 2", "((2 ^ 3) ^ 2)"),
+        ];
+
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should parse powers as right associative, tighter than factors and negation"
+            )
+        }
+    }
+
+    #[test]
+    fn test_power_with_signs_above_power() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("-2 ^ 2", "((-2) ^ 2)"),
+            ("(-2) ^ 2", "((-2) ^ 2)"),
+            ("2 ^ -3", "(2 ^ (-3))"),
+            ("2 ^ -3 ^ 2", "(2 ^ ((-3) ^ 2))"),
+            ("-2 * 3", "((-2) * 3)"),
+            ("not -2 ^ 2", "(not ((-2) ^ 2))"),
+        ];
+
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser =
+                Parser::new(tokens.iter().copied()).sign_precedence(SignPrecedence::AbovePower);
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should parse signs tighter than powers like spreadsheets do"
+            )
+        }
+    }
+
+    #[test]
+    fn test_unary_with_operator_success() {
+        // Arrange
+        let operator_token = Token::Operator(Operator::Minus);
+        let literal_token = Token::float(25.5);
+
+        let tokens_source = [operator_token, literal_token].into_iter();
+        let mut parser = Parser::new(tokens_source);
+
+        let expected_expr = Expression::Unary(UnaryExpr::new(
+            operator_token,
+            Expression::literal(literal_token),
+        ));
+
+        // Act
+        let unary_expr = parser.program();
+
+        // Assert
+        assert_eq!(
+            expected_expr,
+            unary_expr.unwrap(),
+            "should build unary expression for a valid stream of tokens"
+        );
+    }
+
+    #[test]
+    fn test_unary_for_literal_success() {
+        // Arrange
+        let literal_token = Token::float(29.9);
+        let tokens_source = [literal_token].into_iter();
+
+        let mut parser = Parser::new(tokens_source);
+        let expected_expr = Expression::literal(literal_token);
+
+        // Act
+        let literal_from_unary = parser.program();
+
+        // Assert
+        assert_eq!(
+            expected_expr,
+            literal_from_unary.unwrap(),
+            "should build a literal expression for given tokens stream if operator does not exist"
+        )
+    }
+
+    #[test]
+    fn test_postfix_chained_percents() {
+        // Arrange
+        let percent_token = Token::Operator(Operator::PercentSuffix);
+        let literal_token = Token::integer(50);
+        let tokens_source = [literal_token, percent_token, percent_token].into_iter();
+
+        let mut parser = Parser::new(tokens_source);
+        let expected_expr = Expression::Postfix(UnaryExpr::new(
+            percent_token,
+            Expression::Postfix(UnaryExpr::new(
+                percent_token,
+                Expression::literal(literal_token),
+            )),
+        ));
+
+        // Act
+        let postfix_expr = parser.program();
+
+        // Assert
+        assert_eq!(
+            postfix_expr.unwrap(),
+            expected_expr,
+            "should nest postfix expressions for chained percent suffixes"
+        )
+    }
+
+    #[test]
+    fn test_postfix_unit_suffix() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("30deg * 2", "((30deg) * 2)"),
+            ("2 ^ 90deg", "(2 ^ (90deg))"),
+            ("-1.5rad", "(-(1.5rad))"),
+        ];
+
+        for (source, expected_expr) in cases {
+            // Act
+            let expr = Parser::from_source(source);
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should bind unit suffixes as tight as other postfix operators"
+            )
+        }
+    }
+
+    #[test]
+    fn test_comparison() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("1 + 1 < 3", "((1 + 1) < 3)"),
+            ("2 ^ 10 == 4 * 256", "((2 ^ 10) == (4 * 256))"),
+            ("(1 < 2) != 0", "((1 < 2) != 0)"),
+            ("x >= -1", "(x >= (-1))"),
+        ];
+
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should build comparisons binding looser than any other operator"
+            )
+        }
+    }
+
+    #[test]
+    fn test_absolute_value() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("|3 - 5| * 2", "(|(3 - 5)| * 2)"),
+            ("|-x|^2", "(|(-x)| ^ 2)"),
+            ("|(|x|) - 1|", "|((|x|) - 1)|"),
+            ("|x| - |y|", "(|x| - |y|)"),
+            ("|max(|x|, 1)|", "|max(|x|, 1)|"),
+        ];
+
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should build absolute values apart from calls"
+            )
+        }
+    }
+
+    #[test]
+    fn test_absolute_value_fails() {
+        // Arrange
+        // Each source is paired with its expected error
+        let cases = [
+            ("|3 - 5", "[AST PARSE ERROR]: expected '|' closing the absolute value, found end of input at 1:7"),
+            ("|(3 - 5|", "[AST PARSE ERROR]: expected ')', found '|'"),
+            (
+                "| |x| - 1 |",
+                "[AST PARSE ERROR]: absolute values can't be nested like '| |x| - 1 |', group the inner one with parentheses instead",
+            ),
+            ("||x||", "[AST PARSE ERROR]: absolute values can't be nested like '| |x| - 1 |', group the inner one with parentheses instead"),
+            ("3 - 5|", "[AST PARSE ERROR]: unexpected token '|' after expression"),
+        ];
+
+        for (source, expected_error) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                expected_error,
+                "should require bars in pairs, nesting them only through parentheses"
+            )
+        }
+    }
+
+    #[test]
+    fn test_custom_operator() {
+        // Arrange
+        let average = |left, right| Ok((left + right) / 2.0);
+
+        // Each source is paired with the precedence and associativity of `@`, and its expected expression
+        let cases = [
+            (
+                "1 @ 3 * 2",
+                Precedence::Factor,
+                Associativity::Left,
+                "((1 @ 3) * 2)",
+            ),
+            (
+                "1 @ 3 * 2",
+                Precedence::Term,
+                Associativity::Left,
+                "(1 @ (3 * 2))",
+            ),
+            (
+                "1 @ 3 ^ 2",
+                Precedence::Power,
+                Associativity::Right,
+                "(1 @ (3 ^ 2))",
+            ),
+            (
+                "1 ^ 3 @ 2",
+                Precedence::Power,
+                Associativity::Right,
+                "(1 ^ (3 @ 2))",
+            ),
+            (
+                "1 @ 2 @ 3",
+                Precedence::Term,
+                Associativity::Left,
+                "((1 @ 2) @ 3)",
+            ),
+            (
+                "1 @ 2 @ 3",
+                Precedence::Term,
+                Associativity::Right,
+                "(1 @ (2 @ 3))",
+            ),
+            (
+                "1 + 2 @ 3",
+                Precedence::Comparison,
+                Associativity::None,
+                "((1 + 2) @ 3)",
+            ),
+        ];
+
+        for (source, precedence, associativity, expected_expr) in cases {
+            let tokens = TokenizerBuilder::new()
+                .custom_operator('@')
+                .build(source)
+                .tokenize()
+                .unwrap();
+            let mut parser = Parser::new(tokens.iter().copied())
+                .custom_operator(CustomOperator::new('@', precedence, associativity, average));
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should group custom operators by their precedence and associativity"
+            )
+        }
+    }
+
+    #[test]
+    fn test_custom_operator_fails() {
+        // Arrange
+        let average = |left, right| Ok((left + right) / 2.0);
+
+        // Each source is paired with its expected error
+        let cases = [
+            ("1 @ 2 @ 3", "[AST PARSE ERROR]: comparisons can't be chained like '1 < 2 < 3', group them with parentheses instead"),
+            ("1 @", "[AST PARSE ERROR]: expected a number, an identifier, '(' or '|', found end of input at 1:4"),
+            ("1 # 2", "[AST PARSE ERROR]: unexpected token '#' after expression"),
+        ];
+
+        for (source, expected_error) in cases {
+            let tokens = TokenizerBuilder::new()
+                .comments(false)
+                .custom_operator('@')
+                .custom_operator('#')
+                .build(source)
+                .tokenize()
+                .unwrap();
+            let mut parser = Parser::new(tokens.iter().copied()).custom_operator(
+                CustomOperator::new('@', Precedence::Comparison, Associativity::None, average),
+            );
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                expected_error,
+                "should report malformed custom operators and the ones not registered in the parser"
+            )
+        }
+    }
+
+    #[test]
+    fn test_expected_token_errors() {
+        // Arrange
+        // Each source is paired with the expected kind, found token and position of its error
+        let cases = [
+            (
+                "a ? b 1",
+                ASTParseErrorKind::ExpectedToken {
+                    expected: TokenKind::Colon,
+                    context: "between the branches of a conditional",
+                },
+                Token::integer(1),
+                Position::new(1, 7),
+            ),
+            (
+                "|x; 1|",
+                ASTParseErrorKind::ExpectedToken {
+                    expected: TokenKind::Pipe,
+                    context: "closing the absolute value",
+                },
+                Token::Semicolon,
+                Position::new(1, 3),
+            ),
+        ];
+
+        for (source, expected_kind, expected_found, expected_position) in cases {
+            let tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                (err.kind(), err.found().copied(), err.position()),
+                (
+                    &expected_kind,
+                    Some(expected_found),
+                    Some(expected_position)
+                ),
+                "should report the token required by the context, along with the one found instead"
+            )
+        }
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("1 or 0 and 0", "(1 or (0 and 0))"),
+            ("a and b or c and d", "((a and b) or (c and d))"),
+            ("x > 0 and x < 10", "((x > 0) and (x < 10))"),
+            ("not x > 0", "(not (x > 0))"),
+            ("not -x + 1 > 0", "(not (((-x) + 1) > 0))"),
+            ("not not x and y", "((not (not x)) and y)"),
+            ("-not x", "(-(not x))"),
+            ("a or b ? 1 : 2", "((a or b) ? 1 : 2)"),
+        ];
+
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should bind or looser than and, and both looser than not and comparisons"
+            )
+        }
+    }
+
+    #[test]
+    fn test_bitwise_operators() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("1 << 2 + 3", "(1 << (2 + 3))"),
+            ("1 << 2 >> 3", "((1 << 2) >> 3)"),
+            ("1 << 2 < 3 >> 4", "((1 << 2) < (3 >> 4))"),
+            ("x & 1 == 1", "(x & (1 == 1))"),
+            ("a bor b & c", "(a bor (b & c))"),
+            ("a & b bor c & d", "((a & b) bor (c & d))"),
+            ("a and b bor c", "(a and (b bor c))"),
+            ("not x & 1", "(not (x & 1))"),
+        ];
+
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should bind bitwise operators looser than comparisons and shifts tighter, like C does"
+            )
+        }
+    }
+
+    #[test]
+    fn test_conditional() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("x > 0 ? x : -x", "((x > 0) ? x : (-x))"),
+            ("a ? b : c ? d : e", "(a ? b : (c ? d : e))"),
+            ("a ? b ? c : d : e", "(a ? (b ? c : d) : e)"),
+            ("(a ? 1 : 2) * 3", "((a ? 1 : 2) * 3)"),
+            ("max(a ? 1 : 2, 3)", "max((a ? 1 : 2), 3)"),
+        ];
+
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should build right associative conditionals looser than comparisons"
+            )
+        }
+    }
+
+    #[test]
+    fn test_conditional_fails() {
+        // Arrange
+        // Each source is paired with its expected error
+        let cases = [
+            (
+                "a ? b",
+                "[AST PARSE ERROR]: expected ':' between the branches of a conditional, found end of input at 1:6",
+            ),
+            ("a ? b 1", "[AST PARSE ERROR]: expected ':' between the branches of a conditional, found '1'"),
+            (
+                "a : b",
+                "[AST PARSE ERROR]: unexpected token ':' after expression",
+            ),
+        ];
+
+        for (source, expected_error) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                expected_error,
+                "should report malformed conditionals"
+            )
+        }
+    }
+
+    #[test]
+    fn test_comparison_fails_by_chaining() {
+        // Arrange
+        let sources = ["1 < 2 < 3", "1 == 1 != 0", "x <= 1 + 2 > y"];
+
+        for source in sources {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.kind(),
+                &ASTParseErrorKind::ChainedComparison,
+                "should not allow chained comparisons"
+            )
+        }
+    }
+
+    #[test]
+    fn test_postfix_factorial() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("5!", "(5!)"),
+            ("(2+3)!", "((2 + 3)!)"),
+            ("3!!", "((3!)!)"),
+            ("-3!", "(-(3!))"),
+            ("2 * 3! ^ 2", "(2 * ((3!) ^ 2))"),
+        ];
 
-#[derive(Debug, Clone)]
-pub struct ASTParseError {
-    message: &'static str,
-}
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
 
-impl ASTParseError {
-    fn new(message: &'static str) -> Self {
-        Self { message }
-    }
-}
+            // Act
+            let expr = parser.program();
 
-impl fmt::Display for ASTParseError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[AST PARSE ERROR]: {}", self.message)
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should build factorials binding tighter than any other operator"
+            )
+        }
     }
-}
 
-impl Error for ASTParseError {}
-
-type ExpressionResult = Result<Expression, ASTParseError>;
+    #[test]
+    fn test_factor_with_percent() {
+        // Arrange
+        let tokens_source = [
+            Token::integer(200),
+            Token::Operator(Operator::Star),
+            Token::integer(15),
+            Token::Operator(Operator::PercentSuffix),
+        ]
+        .into_iter();
 
-/// Stores the state of the tokens stream and exposes methods for perform the AST building
-#[derive(Debug)]
-pub struct Parser<I: Iterator<Item = Token> + Clone + Debug> {
-    pub tokens: I,
-}
+        let mut parser = Parser::new(tokens_source);
 
-impl<I: Iterator<Item = Token> + Clone + Debug> Parser<I> {
-    pub fn new(tokens: I) -> Self {
-        Self { tokens: tokens }
-    }
-}
+        // Act
+        let factor_expr = parser.program();
 
-// Expression methods
-impl<I: Iterator<Item = Token> + Clone + Debug> Parser<I> {
-    /// Builds the root's program expression.
-    ///   
-    /// Production rule: `Program -> (Term)*`
-    pub fn program(&mut self) -> ExpressionResult {
-        self.term()
+        // Assert
+        assert_eq!(
+            factor_expr.unwrap().to_string(),
+            "(200 * (15%))",
+            "should bind percent suffix tighter than multiplication"
+        )
     }
 
-    /// Builds a term.
-    ///
-    /// Production rule: `Term -> Factor (("+" | "-") Factor)*`
-    fn term(&mut self) -> ExpressionResult {
-        const TERM_OPERATORS: &[Token] = &[
-            Token::Operator(Operator::Plus),
-            Token::Operator(Operator::Minus),
+    #[test]
+    fn test_unary_plus() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("+5", "(+5)"),
+            ("-+5", "(-(+5))"),
+            ("2 - +3", "(2 - (+3))"),
+            ("3 * +2", "(3 * (+2))"),
         ];
 
-        let mut binary_expr: Option<Expression> = None;
-        let left = self.factor();
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
 
-        if left.is_err() {
-            return Err(left.unwrap_err());
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should accept plus as unary operator"
+            )
         }
+    }
 
-        while let Some(operator) = match_concrete_token(TERM_OPERATORS, &mut self.tokens) {
-            let right = self.factor();
+    #[test]
+    fn test_unary_nested_operators() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("--5", "(-(-5))"),
+            ("- -5", "(-(-5))"),
+            ("---5", "(-(-(-5)))"),
+            ("2 - --3", "(2 - (-(-3)))"),
+            ("-+-5", "(-(+(-5)))"),
+        ];
 
-            if right.is_err() {
-                return Err(right.unwrap_err());
-            }
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
 
-            match binary_expr {
-                Some(prev_expr) => {
-                    binary_expr = Some(Expression::Binary(BinaryExpr::new(
-                        prev_expr,
-                        operator,
-                        right.unwrap(),
-                    )))
-                }
-                None => {
-                    binary_expr = Some(Expression::Binary(BinaryExpr::new(
-                        left.clone().unwrap(),
-                        operator,
-                        right.unwrap(),
-                    )))
-                }
-            };
-        }
+            // Act
+            let expr = parser.program();
 
-        match binary_expr {
-            Some(binary_expr) => Ok(binary_expr),
-            None => left,
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should nest stacked unary operators from right to left"
+            )
         }
     }
 
-    /// Builds a factor.
-    ///
-    /// Production rule: `Factor -> Unary (("*" | "/") Unary)*`
-    fn factor(&mut self) -> ExpressionResult {
-        const FACTOR_OPERATORS: &[Token] = &[
-            Token::Operator(Operator::Star),
-            Token::Operator(Operator::Slash),
+    #[test]
+    fn test_mixed_precedence() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("1 + 2 * 3 ^ 2 - -4", "((1 + (2 * (3 ^ 2))) - (-4))"),
+            ("-2 ^ 2 * 3", "((-(2 ^ 2)) * 3)"),
+            ("2 ^ -3 ^ 2", "(2 ^ (-(3 ^ 2)))"),
+            ("1 - 2 - 3 * 4 // 5 % 6", "((1 - 2) - (((3 * 4) // 5) % 6))"),
+            ("-50% * 2x ^ 2", "(((-(50%)) * 2) * (x ^ 2))"),
         ];
 
-        let mut binary_expr: Option<Expression> = None;
-        let left = self.unary();
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
 
-        if left.is_err() {
-            return Err(left.unwrap_err());
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should nest operators by their precedence and associativity"
+            )
         }
+    }
+
+    #[test]
+    fn test_max_depth() {
+        // Arrange
+        // First level is taken by the whole expression, so each source is one level under and over the limit
+        let cases = [
+            (format!("{}1{}", "(".repeat(9), ")".repeat(9)), true),
+            (format!("{}1{}", "(".repeat(10), ")".repeat(10)), false),
+            (format!("{}1", "-".repeat(9)), true),
+            (format!("{}1", "-".repeat(10)), false),
+            (format!("{}1", "2 ^ ".repeat(9)), true),
+            (format!("{}1", "2 ^ ".repeat(10)), false),
+        ];
 
-        while let Some(operator) = match_concrete_token(FACTOR_OPERATORS, &mut self.tokens) {
-            let right = self.unary();
+        for (source, is_ok) in cases {
+            let tokens = Tokenizer::new(&source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied()).max_depth(10);
 
-            if right.is_err() {
-                return Err(right.unwrap_err());
-            }
+            // Act
+            let result = parser.program();
 
-            match binary_expr {
-                Some(prev_expr) => {
-                    binary_expr = Some(Expression::Binary(BinaryExpr::new(
-                        prev_expr,
-                        operator,
-                        right.unwrap(),
-                    )))
-                }
-                None => {
-                    binary_expr = Some(Expression::Binary(BinaryExpr::new(
-                        left.clone().unwrap(),
-                        operator.clone(),
-                        right.unwrap(),
-                    )))
-                }
+            // Assert
+            match is_ok {
+                true => assert!(
+                    result.is_ok(),
+                    "should parse expressions as deep as the limit"
+                ),
+                false => assert_eq!(
+                    result.unwrap_err().kind(),
+                    &ASTParseErrorKind::TooDeep { max_depth: 10 },
+                    "should fail with expressions deeper than the limit"
+                ),
             }
         }
-
-        match binary_expr {
-            Some(binary_expr) => Ok(binary_expr),
-            None => left,
-        }
     }
 
-    /// Builds an unary.
-    ///
-    /// Production rule: `"-" Literal | Literal`
-    fn unary(&mut self) -> ExpressionResult {
-        match peek(&mut self.tokens) {
-            Some(token) => match token {
-                Token::Operator(ref operator) => {
-                    if *operator == Operator::Minus {
-                        self.tokens.next();
-                        let literal = self.literal();
-
-                        if literal.is_err() {
-                            return Err(literal.unwrap_err());
-                        }
-
-                        return Ok(Expression::Unary(UnaryExpr::new(
-                            token.clone(),
-                            literal.unwrap(),
-                        )));
-                    }
+    #[test]
+    fn test_max_nodes() {
+        // Arrange
+        // Each source is paired with whether it fits in 10 nodes
+        let cases = [
+            ("1+1", true),
+            ("-(1 + 2)! * max(x, 3)", true),
+            ("1+1+1+1+1+1", false),
+            ("------------1", false),
+            ("1!!!!!!!!!!", false),
+            ("x = 1; y = 2; z = 3; x + y", false),
+        ];
 
-                    return Err(ASTParseError::new("syntax error in <unary> expression"));
-                }
-                Token::Number(_) => self.literal(),
-            },
-            None => return Err(ASTParseError::new("syntax error by uncomplete expression")),
-        }
-    }
+        for (source, expected_fits) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied()).max_nodes(10);
 
-    /// Builds a literal.
-    ///
-    /// Literal is a `terminal` symbol, so does not belongs to any production rule
-    fn literal(&mut self) -> ExpressionResult {
-        if let Some(number) =
-            match_token(&[mem::discriminant(&Token::Number(0.0))], &mut self.tokens)
-        {
-            return Ok(Expression::Literal(number));
+            // Act
+            let result = parser.statements();
+
+            // Assert
+            assert_eq!(
+                result.map_err(|err| err.kind().clone()),
+                match expected_fits {
+                    true => Ok(Parser::from_slice(&tokens).statements().unwrap()),
+                    false => Err(ASTParseErrorKind::LimitExceeded { max_nodes: 10 }),
+                },
+                "should fail once expressions have more nodes than allowed"
+            )
         }
 
-        Err(ASTParseError::new("invalid expression"))
+        assert_eq!(
+            Parser::from_slice(&Tokenizer::new("1+1+1+1+1+1").tokenize().unwrap())
+                .max_nodes(10)
+                .program()
+                .unwrap_err()
+                .to_string(),
+            "[AST PARSE ERROR]: expression has more than 10 nodes at 1:12",
+            "should tell the number of nodes allowed"
+        )
     }
-}
 
-#[cfg(test)]
-mod ast_parser_tests {
+    #[test]
+    fn test_max_depth_by_default() {
+        // Arrange
+        let sources = [
+            format!("{}1", "(".repeat(50_000)),
+            format!("{}1", "-".repeat(50_000)),
+        ];
 
-    use crate::{
-        ast::expressions::{BinaryExpr, Expression, UnaryExpr},
-        tokenizer::tokens::{Operator, Token},
-    };
+        for source in sources {
+            let tokens = Tokenizer::new(&source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.program().unwrap_err();
 
-    use super::Parser;
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                "[AST PARSE ERROR]: expression is nested deeper than 256 levels",
+                "should fail instead of exhausting the stack with deeply nested expressions"
+            )
+        }
+    }
 
     #[test]
-    fn test_literal_success() {
+    fn test_unary_many_stacked_operators() {
         // Arrange
-        let literal_token = Token::Number(10.0);
-        let tokens_source = [literal_token.clone()].into_iter();
+        let source = format!("{}5", "-".repeat(1000));
+        let tokens = Tokenizer::new(&source).tokenize().unwrap();
 
-        let mut parser = Parser::new(tokens_source);
-        let expected_expr = Expression::Literal(literal_token);
+        // Every operator is a level of nesting, so default depth is not enough
+        let mut parser = Parser::new(tokens.iter().copied()).max_depth(1001);
 
         // Act
-        let literal_expr = parser.literal();
+        let expr = parser.program();
 
         // Assert
         assert_eq!(
-            literal_expr.unwrap(),
-            expected_expr,
-            "should build literal expression for given token"
-        );
-
-        assert_eq!(
-            parser.tokens.count(),
-            0,
-            "should consume token from tokens source once expression was built"
+            expr.unwrap().eval().unwrap(),
+            5.0,
+            "should parse a long run of unary operators"
         )
     }
 
     #[test]
-    fn test_literal_fails() {
+    fn test_unary_over_grouping() {
         // Arrange
-        let non_literal_token = Token::Operator(Operator::Star);
-        let tokens_source = [non_literal_token].into_iter();
-        let mut parser = Parser::new(tokens_source);
+        let tokens = Tokenizer::new("-(1+2)").tokenize().unwrap();
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        let expected_expr = Expression::Unary(UnaryExpr::new(
+            Token::Operator(Operator::Minus),
+            Expression::Grouping(Box::new(Expression::Binary(BinaryExpr::new(
+                Expression::literal(Token::integer(1)),
+                Token::Operator(Operator::Plus),
+                Expression::literal(Token::integer(2)),
+            )))),
+        ));
 
         // Act
-        let result = parser.literal();
+        let unary_expr = parser.program();
 
         // Assert
-        assert!(
-            result.is_err(),
-            "should return error if cannot parse literal expression"
+        assert_eq!(
+            unary_expr.unwrap(),
+            expected_expr,
+            "should negate the whole grouped expression"
         )
     }
 
     #[test]
-    fn test_unary_with_operator_success() {
+    fn test_unary_binds_tighter_than_factor() {
         // Arrange
-        let operator_token = Token::Operator(Operator::Minus);
-        let literal_token = Token::Number(25.5);
-
-        let tokens_source = [operator_token.clone(), literal_token.clone()].into_iter();
-        let mut parser = Parser::new(tokens_source);
-
-        let expected_expr = Expression::Unary(UnaryExpr::new(
-            operator_token,
-            Expression::Literal(literal_token),
+        let tokens = Tokenizer::new("-(2) * 3").tokenize().unwrap();
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        let expected_expr = Expression::Binary(BinaryExpr::new(
+            Expression::Unary(UnaryExpr::new(
+                Token::Operator(Operator::Minus),
+                Expression::Grouping(Box::new(Expression::literal(Token::integer(2)))),
+            )),
+            Token::Operator(Operator::Star),
+            Expression::literal(Token::integer(3)),
         ));
 
         // Act
-        let unary_expr = parser.unary();
+        let expr = parser.program();
 
         // Assert
         assert_eq!(
+            expr.unwrap(),
             expected_expr,
-            unary_expr.unwrap(),
-            "should build unary expression for a valid stream of tokens"
-        );
+            "should negate only the grouping right after the operator"
+        )
     }
 
     #[test]
-    fn test_unary_for_literal_success() {
+    fn test_factor_implicit_multiplication() {
         // Arrange
-        let literal_token = Token::Number(29.9);
-        let tokens_source = [literal_token.clone()].into_iter();
+        // Each source is paired with its expected expression
+        let cases = [
+            ("2(3+4)", "(2 * (3 + 4))"),
+            ("2 (3)", "(2 * (3))"),
+            ("(2)(3)", "((2) * (3))"),
+            ("(1+1)(2+2)", "((1 + 1) * (2 + 2))"),
+            ("2pi", "(2 * pi)"),
+            ("1 + 2(3) * 4", "(1 + ((2 * (3)) * 4))"),
+            ("2(3)^2", "(2 * ((3) ^ 2))"),
+            ("2 -3", "(2 - 3)"),
+            ("3x + 1", "((3 * x) + 1)"),
+            ("2x y", "((2 * x) * y)"),
+            // Exponent markers starting a word are not part of the number
+            ("2exp(1)", "(2 * exp(1))"),
+            ("2 e", "(2 * e)"),
+            // Digits after letters are part of the identifier
+            ("x2", "x2"),
+        ];
 
-        let mut parser = Parser::new(tokens_source);
-        let expected_expr = Expression::Literal(literal_token);
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should multiply juxtaposed operands at the same precedence than explicit multiplication"
+            )
+        }
+    }
+
+    #[test]
+    fn test_primary_call() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("sqrt(16)", "sqrt(16)"),
+            ("max()", "max()"),
+            ("max(1, min(2, 3))", "max(1, min(2, 3))"),
+            ("2 * abs(1 - 3) ^ 2", "(2 * (abs((1 - 3)) ^ 2))"),
+            ("2sqrt(4)", "(2 * sqrt(4))"),
+        ];
+
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should build function calls with their arguments"
+            )
+        }
+    }
+
+    #[test]
+    fn test_primary_call_fails() {
+        // Arrange
+        // Each source is paired with its expected error
+        let cases = [
+            (
+                "max(1, 2",
+                "[AST PARSE ERROR]: missing ')' for '(', found end of input at 1:9",
+            ),
+            (
+                "max(1 2)",
+                "[AST PARSE ERROR]: expected ',' or ')', found '2'",
+            ),
+            (
+                "max(1,)",
+                "[AST PARSE ERROR]: expected a number, an identifier, '(' or '|', found ')'",
+            ),
+        ];
+
+        for (source, expected_error) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                expected_error,
+                "should report malformed function calls"
+            )
+        }
+    }
+
+    #[test]
+    fn test_primary_call_arity_fails() {
+        // Arrange
+        let min = Symbol::intern("min");
+        let sqrt = Symbol::intern("sqrt");
+
+        // Each source is paired with its expected error kind and where it is
+        let cases = [
+            (
+                "min()",
+                ASTParseErrorKind::WrongArity {
+                    name: min,
+                    expected: Arity::AtLeast(2),
+                    found: 0,
+                },
+                Position::new(1, 1),
+            ),
+            (
+                "1 + sqrt(1, 2)",
+                ASTParseErrorKind::WrongArity {
+                    name: sqrt,
+                    expected: Arity::Exact(1),
+                    found: 2,
+                },
+                Position::new(1, 5),
+            ),
+            (
+                "max(sqrt(), 1)",
+                ASTParseErrorKind::WrongArity {
+                    name: sqrt,
+                    expected: Arity::Exact(1),
+                    found: 0,
+                },
+                Position::new(1, 5),
+            ),
+        ];
+
+        for (source, expected_kind, expected_position) in cases {
+            let tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+            let mut parser =
+                Parser::new(tokens.iter().copied()).functions(FunctionRegistry::builtins());
+
+            // Act
+            let err = parser.program().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                (err.kind(), err.position()),
+                (&expected_kind, Some(expected_position)),
+                "should report calls with a number of arguments their function doesn't accept"
+            )
+        }
+
+        let tokens = Tokenizer::new("sqrt(1, 2)").tokenize_spanned().unwrap();
+        let mut parser =
+            Parser::new(tokens.iter().copied()).functions(FunctionRegistry::builtins());
+
+        assert_eq!(
+            parser.program().unwrap_err().to_string(),
+            "[AST PARSE ERROR]: function 'sqrt' expects 1 argument, found 2 at 1:1",
+            "should describe the accepted number of arguments"
+        )
+    }
+
+    #[test]
+    fn test_primary_call_unknown_function() {
+        // Arrange
+        // Each way of handling unknown functions is paired with the expected result of calling one
+        let cases = [
+            (UnknownFunctions::Defer, Ok("foo(1)".to_string())),
+            (
+                UnknownFunctions::Reject,
+                Err(ASTParseErrorKind::UnknownFunction(Symbol::intern("foo"))),
+            ),
+        ];
+
+        for (unknown_functions, expected_result) in cases {
+            let tokens = Tokenizer::new("foo(1)").tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied())
+                .functions(FunctionRegistry::builtins())
+                .unknown_functions(unknown_functions);
+
+            // Act
+            let result = parser.program();
+
+            // Assert
+            assert_eq!(
+                result
+                    .map(|expr| expr.to_string())
+                    .map_err(|err| err.kind().clone()),
+                expected_result,
+                "should only reject unknown functions when asked to"
+            )
+        }
+    }
+
+    #[test]
+    fn test_primary_call_unchecked_without_functions() {
+        // Arrange
+        let tokens = Tokenizer::new("min() + sqrt(1, 2) + foo(1)")
+            .tokenize()
+            .unwrap();
+        let mut parser =
+            Parser::new(tokens.iter().copied()).unknown_functions(UnknownFunctions::Reject);
 
         // Act
-        let literal_from_unary = parser.unary();
+        let expr = parser.program();
 
         // Assert
         assert_eq!(
-            expected_expr,
-            literal_from_unary.unwrap(),
-            "should build a literal expression for given tokens stream if operator does not exist"
+            expr.unwrap().to_string(),
+            "((min() + sqrt(1, 2)) + foo(1))",
+            "should defer checking calls to evaluation without functions"
         )
     }
 
+    #[test]
+    fn test_primary_variable() {
+        // Arrange
+        // Each source is paired with its expected expression
+        let cases = [
+            ("x", "x"),
+            ("x * 2 + y", "((x * 2) + y)"),
+            ("2x", "(2 * x)"),
+            ("x(2)", "x(2)"),
+        ];
+
+        for (source, expected_expr) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let expr = parser.program();
+
+            // Assert
+            assert_eq!(
+                expr.unwrap().to_string(),
+                expected_expr,
+                "should build variables from identifiers which are not followed by `(`"
+            )
+        }
+    }
+
     #[test]
     fn test_unary_fails_by_invalid_operator() {
         // Arrange
         let non_unary_operator = Token::Operator(Operator::Star);
-        let literal_token = Token::Number(99.9);
-        let tokens_source = [non_unary_operator.clone(), literal_token.clone()].into_iter();
+        let literal_token = Token::float(99.9);
+        let tokens_source = [non_unary_operator, literal_token].into_iter();
 
         let mut parser = Parser::new(tokens_source);
 
         // Act
-        let result = parser.unary();
+        let result = parser.program();
 
         // Assert
         assert!(
@@ -310,36 +3643,32 @@ mod ast_parser_tests {
     #[test]
     fn test_factor_success() {
         // Arrange
-        let left_literal = Token::Number(10.0);
-        let right_literal = Token::Number(20.0);
+        let left_literal = Token::float(10.0);
+        let right_literal = Token::float(20.0);
         let factor_operators = [
             Token::Operator(Operator::Star),
             Token::Operator(Operator::Slash),
+            Token::Operator(Operator::DoubleSlash),
         ];
 
         for operator in factor_operators {
-            let tokens_source = [
-                left_literal.clone(),
-                operator.clone(),
-                right_literal.clone(),
-            ]
-            .into_iter();
+            let tokens_source = [left_literal, operator, right_literal].into_iter();
 
             let mut parser = Parser::new(tokens_source);
             let expected_expr = Expression::Binary(BinaryExpr::new(
-                Expression::Literal(left_literal.clone()),
+                Expression::literal(left_literal),
                 operator,
-                Expression::Literal(right_literal.clone()),
+                Expression::literal(right_literal),
             ));
 
             // Act
-            let factor_expr = parser.factor();
+            let factor_expr = parser.program();
 
             // Assert
             assert_eq!(
                 factor_expr.unwrap(),
                 expected_expr,
-                "should build a binary expression from factor production rule"
+                "should build a binary expression for a factor operator"
             )
         }
     }
@@ -347,36 +3676,31 @@ mod ast_parser_tests {
     #[test]
     fn test_expression_success() {
         // Arrange
-        let left_literal = Token::Number(10.0);
-        let right_literal = Token::Number(20.0);
-        let factor_operators = [
+        let left_literal = Token::float(10.0);
+        let right_literal = Token::float(20.0);
+        let term_operators = [
             Token::Operator(Operator::Plus),
             Token::Operator(Operator::Minus),
         ];
 
-        for operator in factor_operators {
-            let tokens_source = [
-                left_literal.clone(),
-                operator.clone(),
-                right_literal.clone(),
-            ]
-            .into_iter();
+        for operator in term_operators {
+            let tokens_source = [left_literal, operator, right_literal].into_iter();
 
             let mut parser = Parser::new(tokens_source);
             let expected_expr = Expression::Binary(BinaryExpr::new(
-                Expression::Literal(left_literal.clone()),
+                Expression::literal(left_literal),
                 operator,
-                Expression::Literal(right_literal.clone()),
+                Expression::literal(right_literal),
             ));
 
             // Act
-            let factor_expr = parser.term();
+            let term_expr = parser.program();
 
             // Assert
             assert_eq!(
-                factor_expr.unwrap(),
+                term_expr.unwrap(),
                 expected_expr,
-                "should build a binary expression from factor production rule"
+                "should build a binary expression for a term operator"
             )
         }
     }
@@ -0,0 +1,207 @@
+#![allow(dead_code)]
+
+use std::{cmp::Ordering, fmt};
+
+#[cfg(feature = "big-decimal")]
+use super::decimal::Decimal;
+
+/// Result of evaluating an expression. Integers are kept exact while the operations applied to them give integers,
+/// like `3 + 4`, falling back to floats otherwise, like `1 / 3`.
+///
+/// Values are only equal if they are the same variant, so `Integer(7)` is not equal to `Float(7.0)`, but they can
+/// be compared to a float by their number.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    Float(f64),
+    /// Number of arbitrary precision, given by evaluating with `Precision::Big`.
+    #[cfg(feature = "big-decimal")]
+    Decimal(Decimal),
+}
+
+impl Value {
+    /// Creates the value of a condition, which is `1` when true and `0` when false.
+    pub fn from_bool(value: bool) -> Self {
+        Value::Integer(i64::from(value))
+    }
+
+    /// Number of the value as a float, which is rounded for integers too big to be represented exactly.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Value::Integer(value) => *value as f64,
+            Value::Float(value) => *value,
+            #[cfg(feature = "big-decimal")]
+            Value::Decimal(decimal) => decimal.to_f64(),
+        }
+    }
+
+    /// Number of the value as an `i64`, if it is an integer fitting in one, even if it is a float like `4.0`.
+    pub fn to_i64(&self) -> Option<i64> {
+        match self {
+            Value::Integer(value) => Some(*value),
+            // `i64::MAX` is rounded up to 2^63 as a float, which doesn't fit, so the upper bound is exclusive.
+            // Non-finite values have no integer part, so they are not integers either
+            Value::Float(value)
+                if value.fract() == 0.0
+                    && *value >= i64::MIN as f64
+                    && *value < i64::MAX as f64 =>
+            {
+                Some(*value as i64)
+            }
+            Value::Float(_) => None,
+            #[cfg(feature = "big-decimal")]
+            Value::Decimal(decimal) => decimal.to_i64(),
+        }
+    }
+
+    /// Exact decimal of the value, which is `None` for floats.
+    #[cfg(feature = "big-decimal")]
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        match self {
+            Value::Integer(value) => Some(Decimal::from(*value)),
+            Value::Float(_) => None,
+            Value::Decimal(decimal) => Some(decimal.clone()),
+        }
+    }
+
+    /// Whether the value is exactly zero, even a decimal too small to be anything but zero as a float.
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Value::Integer(value) => *value == 0,
+            Value::Float(value) => *value == 0.0,
+            #[cfg(feature = "big-decimal")]
+            Value::Decimal(decimal) => decimal.is_zero(),
+        }
+    }
+
+    /// Whether the value is true for conditionals and boolean operators, which is any value but zero, even NaN.
+    pub fn is_true(&self) -> bool {
+        !self.is_zero()
+    }
+
+    /// Whether the value is neither infinite nor NaN. Decimals always are, even if too big to be finite as a float.
+    pub fn is_finite(&self) -> bool {
+        match self {
+            Value::Float(value) => value.is_finite(),
+            _ => true,
+        }
+    }
+
+    pub fn is_nan(&self) -> bool {
+        matches!(self, Value::Float(value) if value.is_nan())
+    }
+
+    /// Compares the numbers of both values, which are compared exactly unless any of them is a float. It is `None`
+    /// if any of them is NaN.
+    pub fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Integer(left), Value::Integer(right)) => Some(left.cmp(right)),
+            #[cfg(feature = "big-decimal")]
+            (Value::Decimal(_), Value::Integer(_) | Value::Decimal(_))
+            | (Value::Integer(_), Value::Decimal(_)) => {
+                let (left, right) = (self.to_decimal()?, other.to_decimal()?);
+                Some(left.compare(&right))
+            }
+            (left, right) => left.to_f64().partial_cmp(&right.to_f64()),
+        }
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl PartialEq<f64> for Value {
+    fn eq(&self, other: &f64) -> bool {
+        self.to_f64() == *other
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Integer(value) => write!(f, "{value}"),
+            Value::Float(value) => write!(f, "{value}"),
+            #[cfg(feature = "big-decimal")]
+            Value::Decimal(decimal) => write!(f, "{decimal}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod ast_value_tests {
+    use std::cmp::Ordering;
+
+    use super::Value;
+
+    #[test]
+    fn test_display() {
+        // Arrange
+        // Each value is paired with its expected text
+        let cases = [
+            (Value::Integer(7), "7"),
+            (Value::Integer(-42), "-42"),
+            (Value::Float(0.5), "0.5"),
+            (Value::Float(f64::INFINITY), "inf"),
+        ];
+
+        for (value, expected_text) in cases {
+            // Act & Assert
+            assert_eq!(
+                value.to_string(),
+                expected_text,
+                "should display integers without decimals"
+            )
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        // Arrange
+        // Each pair of values is paired with their expected ordering
+        let cases = [
+            (Value::Integer(1), Value::Integer(2), Some(Ordering::Less)),
+            (
+                Value::Integer(2),
+                Value::Float(1.5),
+                Some(Ordering::Greater),
+            ),
+            (Value::Float(3.0), Value::Integer(3), Some(Ordering::Equal)),
+            (
+                Value::Integer(i64::MAX),
+                Value::Integer(i64::MAX - 1),
+                Some(Ordering::Greater),
+            ),
+            (Value::Float(f64::NAN), Value::Integer(1), None),
+        ];
+
+        for (left, right, expected_ordering) in cases {
+            // Act & Assert
+            assert_eq!(
+                left.compare(&right),
+                expected_ordering,
+                "should compare values by their numbers"
+            )
+        }
+
+        assert_ne!(
+            Value::Integer(7),
+            Value::Float(7.0),
+            "should tell integers apart from floats"
+        );
+
+        assert_eq!(
+            Value::Integer(7),
+            7.0,
+            "should compare values with floats by their numbers"
+        )
+    }
+}
@@ -1,10 +1,14 @@
 use std::io::{self, Write};
 
-use crate::{ast::parser::Parser, tokenizer::parser::Tokenizer};
+use crate::ast::{
+    environment::Environment,
+    options::{AngleMode, EvalOptions, Precision},
+    parser::{ParseOutcome, Parser},
+};
 
-fn display_caret(stdout: &mut io::Stdout) {
+fn display_caret(stdout: &mut io::Stdout, caret: &str) {
     stdout
-        .write("> ".as_bytes())
+        .write_all(caret.as_bytes())
         .expect("cannot write caret in stdout");
 
     stdout.flush().expect("cannot flush caret in stdout");
@@ -20,34 +24,1014 @@ fn read_input(stdin: &io::Stdin) -> String {
     input
 }
 
+/// Runs a command of the repl, written after `:`, which inspects or changes the environment used by the following
+/// lines. It gives the text to print, if any.
+///
+/// `:vars` lists the variables sorted by name, `:precision big` evaluates numbers as decimals of arbitrary
+/// precision, and `:precision standard` goes back to integers and floats. `:angle degrees` and `:angle radians`
+/// change the unit of the angles of trigonometric functions.
+fn run_command(command: &str, env: &mut Environment) -> Result<Option<String>, String> {
+    let mut words = command.split_whitespace();
+
+    match (words.next(), words.next(), words.next()) {
+        (Some("vars"), None, None) => {
+            let mut variables = env
+                .iter()
+                .map(|(name, value)| (name.as_str(), value))
+                .collect::<Vec<_>>();
+
+            variables.sort_by_key(|(name, _)| *name);
+
+            let lines = variables
+                .iter()
+                .map(|(name, value)| format!("{name} = {value}"))
+                .collect::<Vec<_>>();
+
+            Ok(Some(lines.join("\n")).filter(|text| !text.is_empty()))
+        }
+        (Some("precision"), Some(precision), None) => {
+            let precision = match precision {
+                "standard" => Precision::Standard,
+                #[cfg(feature = "big-decimal")]
+                "big" => Precision::Big,
+                _ => return Err(format!("unknown precision '{precision}'")),
+            };
+
+            env.set_options(EvalOptions {
+                precision,
+                ..*env.options()
+            });
+
+            Ok(None)
+        }
+        (Some("angle"), Some(angle_mode), None) => {
+            let angle_mode = match angle_mode {
+                "radians" => AngleMode::Radians,
+                "degrees" => AngleMode::Degrees,
+                _ => return Err(format!("unknown angle mode '{angle_mode}'")),
+            };
+
+            env.set_options(EvalOptions {
+                angle_mode,
+                ..*env.options()
+            });
+
+            Ok(None)
+        }
+        _ => Err(format!("unknown command ':{}'", command.trim())),
+    }
+}
+
 pub fn run() {
     let stdin = io::stdin();
     let mut stdout = io::stdout();
 
+    // Variables assigned in a line are kept for the following ones
+    let mut env = Environment::new();
+
     loop {
-        display_caret(&mut stdout);
-        let input = read_input(&stdin);
+        display_caret(&mut stdout, "> ");
+        let mut input = read_input(&stdin);
 
-        let mut tokenizer = Tokenizer::new(&input);
+        if let Some(command) = input.trim_start().strip_prefix(':') {
+            match run_command(command, &mut env) {
+                Ok(Some(output)) => println!("{output}"),
+                Ok(None) => {}
+                Err(err) => eprintln!("{err}"),
+            }
 
-        if let Err(err) = tokenizer.tokenize() {
-            eprintln!("{err}");
             continue;
         }
 
-        let mut parser = Parser::new(tokenizer.tokens.into_iter());
+        // While input is incomplete, like `1 +`, keeps reading lines instead of failing.
+        // Reading nothing means stdin reached its end, so input is parsed as it is
+        let program = loop {
+            match Parser::parse_or_incomplete_from_source(&input) {
+                Ok(ParseOutcome::Complete(program)) => break Ok(program),
+                Ok(ParseOutcome::NeedMoreInput) => {
+                    display_caret(&mut stdout, "... ");
+                    let line = read_input(&stdin);
 
-        match parser.program() {
-            Ok(ast) => {
-                println!("{}", ast);
+                    if line.is_empty() {
+                        break Parser::statements_from_source(&input);
+                    }
 
-                let result = ast.eval();
-                println!("{}", result);
+                    input.push_str(&line);
+                }
+                Err(err) => break Err(err),
             }
+        };
+
+        let program = match program {
+            Ok(program) => program,
             Err(err) => {
                 eprintln!("{err}");
                 continue;
             }
+        };
+
+        if program.statements().is_empty() {
+            continue;
+        }
+
+        println!("{program}");
+
+        // Statements are evaluated from left to right, but only the value of the last one is printed
+        match program.eval_with(&mut env) {
+            Ok(Some(result)) => println!("{result}"),
+            Ok(None) => {}
+            Err(err) => eprintln!("{err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod repl_tests {
+    use crate::{
+        ast::{
+            environment::Environment,
+            functions::Arity,
+            options::EvalOptions,
+            parser::{Parser, UnknownFunctions},
+            value::Value,
+        },
+        tokenizer::{parser::Tokenizer, symbol::Symbol},
+    };
+
+    use super::run_command;
+
+    /// Runs the whole pipeline used by the repl for the given source: tokenize, parse and evaluate.
+    fn eval_source(source: &str) -> Value {
+        Parser::from_source(source).unwrap().eval().unwrap()
+    }
+
+    #[test]
+    fn test_eval_statements() {
+        // Arrange
+        // Each source is paired with the expected values of its statements
+        let cases = [
+            ("1+1; 2*3", vec![2.0, 6.0]),
+            ("1; 2;", vec![1.0, 2.0]),
+            ("a = 2; b = a * 3; a + b", vec![2.0, 6.0, 8.0]),
+            ("", vec![]),
+        ];
+
+        for (source, expected_values) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let values = parser.statements().unwrap().eval_all();
+
+            // Assert
+            assert_eq!(
+                values.unwrap(),
+                expected_values,
+                "should evaluate every statement in order"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_statements_last_value() {
+        // Arrange
+        // Each source is paired with the expected value of its last statement
+        let cases = [
+            ("a = 2; a * 3", Some(Value::Integer(6))),
+            ("a = 2; b = a + 1; a * b", Some(Value::Integer(6))),
+            ("1;", Some(Value::Integer(1))),
+            ("", None),
+        ];
+
+        for (source, expected_value) in cases {
+            // Act
+            let value = Parser::statements_from_source(source).unwrap().eval();
+
+            // Assert
+            assert_eq!(
+                value.unwrap(),
+                expected_value,
+                "should evaluate every statement from left to right, returning the last value"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_assignments() {
+        // Arrange
+        let mut env = Environment::new();
+        let tokens = Tokenizer::new("x = 2; y = x * 3; y + 1; x = x + 1; x")
+            .tokenize()
+            .unwrap();
+        let mut parser = Parser::new(tokens.iter().copied());
+
+        // Act
+        let values = parser.statements().unwrap().eval_all_with(&mut env);
+
+        // Assert
+        assert_eq!(
+            values.unwrap(),
+            vec![2.0, 6.0, 7.0, 3.0, 3.0],
+            "should evaluate assignments to their value and use it in the following statements"
+        );
+
+        assert_eq!(
+            (env.get(Symbol::intern("x")), env.get(Symbol::intern("y"))),
+            (Some(Value::Integer(3)), Some(Value::Integer(6))),
+            "should keep the last value assigned to each variable"
+        )
+    }
+
+    #[test]
+    fn test_eval_function_definitions() {
+        // Arrange
+        let mut env = Environment::new();
+        env.define("k", 10.0);
+
+        // Each source is paired with its expected value. Sources are evaluated in order over the same environment
+        let cases = [
+            ("f(x) = x * x + 1", None),
+            ("f(3)", Some(Value::Integer(10))),
+            ("g(x, y) = f(x) + y * k", None),
+            ("g(2, 3)", Some(Value::Float(35.0))),
+            ("x = 100; f(2) + x", Some(Value::Integer(105))),
+            ("f(x) = 2 * x; f(3)", Some(Value::Integer(6))),
+            ("g(2, 3)", Some(Value::Float(34.0))),
+            ("sqrt(x) = -x; sqrt(4)", Some(Value::Integer(-4))),
+        ];
+
+        for (source, expected_value) in cases {
+            // Act
+            let value = Parser::statements_from_source(source)
+                .unwrap()
+                .eval_with(&mut env);
+
+            // Assert
+            assert_eq!(
+                value.unwrap(),
+                expected_value,
+                "should call functions with their last definition and global variables"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_function_definitions_fails() {
+        // Arrange
+        // Each source is paired with its expected error
+        let cases = [
+            (
+                "f(x) = x + 1; f(1, 2)",
+                "[EVAL ERROR]: function 'f' expects 1 argument, found 2",
+            ),
+            (
+                "f(x) = f(x - 1)",
+                "[EVAL ERROR]: function 'f' can't call itself",
+            ),
+            (
+                "f(x) = x; g(x) = f(x); f(x) = g(x)",
+                "[EVAL ERROR]: function 'f' can't call itself",
+            ),
+            ("f(x) = x + y; f(1)", "[EVAL ERROR]: unknown variable 'y'"),
+        ];
+
+        for (source, expected_err) in cases {
+            // Act
+            let err = Parser::statements_from_source(source)
+                .unwrap()
+                .eval()
+                .unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                expected_err,
+                "should reject recursive functions and calls with a wrong number of arguments"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_with_tabs_and_carriage_returns() {
+        // Arrange
+        let sources = ["3\t+\t4", "3 + 4\r\n", "\t3 +\r\n4\r\n"];
+
+        for source in sources {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                7.0,
+                "should treat tabs and carriage returns as whitespace"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_leading_dot_numbers() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            (".5", 0.5),
+            ("0.5", 0.5),
+            ("1 + .5", 1.5),
+            (".5 + .25", 0.75),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should evaluate numbers with a leading dot as decimals"
+            )
+        }
+
+        assert!(
+            Tokenizer::new(".").tokenize().is_err(),
+            "should not accept a bare dot as number"
+        )
+    }
+
+    #[test]
+    fn test_eval_trailing_dot_numbers() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [("5.", 5.0), ("5. + 1", 6.0), ("5.+1", 6.0), ("2.*.5", 1.0)];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should evaluate numbers with a trailing dot as numbers without decimals"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_with_comment() {
+        // Act & Assert
+        assert_eq!(
+            eval_source("3 * 4  # area\n"),
+            12.0,
+            "should evaluate the expression before the comment"
+        )
+    }
+
+    #[test]
+    fn test_eval_floor_division() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("7 // 2", 3.0),
+            ("-7 // 2", -4.0),
+            ("7 / 2", 3.5),
+            ("1 + 9 // 2 * 2", 9.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should evaluate floor division at the same precedence than division"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_hex_numbers() {
+        // Act & Assert
+        assert_eq!(
+            eval_source("0xff * 2"),
+            510.0,
+            "should evaluate expressions containing hexadecimal numbers"
+        );
+
+        assert_eq!(
+            eval_source("0xFF + 0x10"),
+            271.0,
+            "should evaluate expressions containing only hexadecimal numbers"
+        )
+    }
+
+    #[test]
+    fn test_eval_binary_numbers() {
+        // Act & Assert
+        assert_eq!(
+            eval_source("0b1010 + 0b1"),
+            11.0,
+            "should evaluate expressions containing binary numbers"
+        );
+    }
+
+    #[test]
+    fn test_eval_constants() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("2 * pi", std::f64::consts::TAU),
+            ("PI", std::f64::consts::PI),
+            ("tau / 2", std::f64::consts::PI),
+            ("-e", -std::f64::consts::E),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert!(
+                (eval_source(source).to_f64() - expected_result).abs() < 1e-12,
+                "should resolve constants to their values"
+            )
+        }
+    }
+
+    #[test]
+    fn test_display_values() {
+        // Arrange
+        // Each source is paired with its expected printed value
+        let cases = [
+            ("3 + 4", "7"),
+            ("10 / 4", "2.5"),
+            ("2 ^ 62", "4611686018427387904"),
+            ("0.1 + 0.2", "0.30000000000000004"),
+        ];
+
+        for (source, expected_text) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source).to_string(),
+                expected_text,
+                "should print integer results without decimals"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_mixed_integers_and_floats() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("7 / 2", 3.5),
+            ("7.0 / 2", 3.5),
+            ("1 + 0.5", 1.5),
+            ("-3", -3.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should promote integers to floats while evaluating"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_percent_suffix() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("50%", 0.5),
+            ("200 * 15%", 30.0),
+            ("15% + 5%", 0.2),
+            ("-50%", -0.5),
+            ("200 * 50%%", 1.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert!(
+                (eval_source(source).to_f64() - expected_result).abs() < 1e-12,
+                "should divide percent numbers by 100"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_unit_suffix() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("180deg", std::f64::consts::PI),
+            ("30deg * 2", std::f64::consts::FRAC_PI_3),
+            ("-90deg", -std::f64::consts::FRAC_PI_2),
+            ("2rad", 2.0),
+            ("90deg - 1.5rad", std::f64::consts::FRAC_PI_2 - 1.5),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert!(
+                (eval_source(source).to_f64() - expected_result).abs() < 1e-12,
+                "should convert degrees to radians and keep radians as they are"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_trigonometric_functions() {
+        // Arrange
+        let mut env = Environment::new();
+
+        // Each source is paired with its expected result in radians and in degrees
+        let cases = [
+            (
+                "sin(pi / 2)",
+                1.0,
+                std::f64::consts::FRAC_PI_2.to_radians().sin(),
+            ),
+            ("sin(90)", 90f64.sin(), 1.0),
+            ("sin(90deg)", 1.0, 1.0),
+            ("cos(180deg)", -1.0, -1.0),
+            ("2 * asin(1)", std::f64::consts::PI, 180.0),
+            ("atan2(1, 1) * 4", std::f64::consts::PI, 180.0),
+        ];
+
+        for (source, expected_radians, expected_degrees) in cases {
+            let program = Parser::statements_from_source(source).unwrap();
+
+            // Act
+            run_command("angle radians", &mut env).unwrap();
+            let radians = program.eval_with(&mut env).unwrap().unwrap();
+
+            run_command("angle degrees", &mut env).unwrap();
+            let degrees = program.eval_with(&mut env).unwrap().unwrap();
+
+            // Assert
+            assert!(
+                (radians.to_f64() - expected_radians).abs() < 1e-12,
+                "should evaluate '{source}' with angles in radians"
+            );
+            assert!(
+                (degrees.to_f64() - expected_degrees).abs() < 1e-12,
+                "should evaluate '{source}' with angles in degrees"
+            )
+        }
+
+        assert_eq!(
+            Parser::from_source("asin(2) + 1")
+                .unwrap()
+                .eval()
+                .unwrap_err()
+                .to_string(),
+            "[EVAL ERROR]: function 'asin' is not defined for 2",
+            "should fail for arguments out of the domain of the function"
+        )
+    }
+
+    #[test]
+    fn test_eval_logarithms() {
+        // Arrange
+        let allow_non_finite = Environment::with_options(EvalOptions {
+            allow_non_finite: true,
+            ..EvalOptions::default()
+        });
+
+        // Each source is paired with its expected result
+        let cases = [
+            ("e^1", std::f64::consts::E),
+            ("e^2 / exp(2)", 1.0),
+            ("ln(e^3)", 3.0),
+            ("log(8, 2)", 3.0),
+            ("log10(1e-3) + log2(1024)", 7.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert!(
+                (eval_source(source).to_f64() - expected_result).abs() < 1e-12,
+                "should evaluate '{source}'"
+            )
+        }
+
+        assert_eq!(
+            Parser::from_source("ln(0)")
+                .unwrap()
+                .eval()
+                .unwrap_err()
+                .to_string(),
+            "[EVAL ERROR]: function 'ln' is not defined for 0",
+            "should fail at the poles of logarithms by default"
+        );
+
+        assert_eq!(
+            Parser::from_source("ln(0)").unwrap().eval_with(&allow_non_finite),
+            Ok(Value::Float(f64::NEG_INFINITY)),
+            "should give negative infinity at the poles of logarithms if non-finite results are allowed"
+        );
+
+        assert_eq!(
+            Parser::from_source("ln(-1)")
+                .unwrap()
+                .eval_with(&allow_non_finite)
+                .unwrap_err()
+                .to_string(),
+            "[EVAL ERROR]: function 'ln' is not defined for -1",
+            "should always fail for negative numbers"
+        )
+    }
+
+    #[test]
+    fn test_eval_non_finite_numbers() {
+        // Act & Assert
+        assert_eq!(
+            eval_source("-inf + 1"),
+            f64::NEG_INFINITY,
+            "should evaluate the infinity keyword as infinity"
+        );
+
+        assert_eq!(
+            eval_source(&f64::INFINITY.to_string()),
+            f64::INFINITY,
+            "should evaluate printed infinity results typed back in"
+        );
+
+        assert!(
+            eval_source("NaN * 2").is_nan(),
+            "should evaluate the nan keyword as NaN"
+        )
+    }
+
+    #[test]
+    fn test_eval_grouping() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [("(1+2)*3", 9.0), ("((2))", 2.0), ("2 * (3 + 4) // 3", 4.0)];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should evaluate grouped expressions first"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_power() {
+        // Arrange
+        // Each source is paired with its expected result. Notice negation binds looser than exponentiation
+        let cases = [
+            ("2 ^ 3 ^ 2", 512.0),
+            ("2 ** 3 ** 2", 512.0),
+            ("2 * 3 ^ 2", 18.0),
+            ("-2 ^ 2", -4.0),
+            ("(-2) ^ 2", 4.0),
+            ("-(2 ^ 2)", -4.0),
+            ("2 ^ -1", 0.5),
+            ("2 ^ -3", 0.125),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should evaluate powers right associative"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_modulo() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("10 % 3 + 1", 2.0),
+            ("10%3", 1.0),
+            ("-7 % 3", -1.0),
+            ("2 * 7 % 4", 2.0),
+            ("200 * 15% % 7", 2.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should evaluate modulo at the same precedence than multiplication"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_unary_plus() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("+5", 5.0),
+            ("-+5", -5.0),
+            ("2 - +3", -1.0),
+            ("3 * +2", 6.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should evaluate unary plus as the operand itself"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_nested_unary_operators() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("--5", 5.0),
+            ("- -5", 5.0),
+            ("---5", -5.0),
+            ("2 - --3", -1.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should apply every stacked unary operator"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_unary_over_grouping() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [("-(1 + 2)", -3.0), ("-(2) * 3", -6.0), ("-(2 ^ 2)", -4.0)];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should negate the whole grouped expression"
+            )
         }
     }
+
+    #[test]
+    fn test_eval_implicit_multiplication() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("2(3+4)", 14.0),
+            ("(2)(3)", 6.0),
+            ("2 (3)", 6.0),
+            ("12 / 2(3)", 18.0),
+            ("2 -3", -1.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should multiply juxtaposed operands"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_function_calls() {
+        // Arrange
+        // Each source is paired with its expected result
+        let cases = [
+            ("sqrt(16)", 4.0),
+            ("abs(-2.5) * 2", 5.0),
+            ("max(1, min(2, 3))", 2.0),
+            ("min(4, 2, 8) + max(3, -3)", 5.0),
+            ("clamp(2^10, 0, 1000)", 1000.0),
+            ("2sqrt(9)", 6.0),
+            ("floor(2.7) + ceil(-1.5)", 1.0),
+            ("round(1.23456, 2)", 1.23),
+            ("round(1234, -2) + trunc(-0.5)", 1200.0),
+            ("gcd(12, 18, 24)", 6.0),
+            ("lcm(4, 6)", 12.0),
+            ("ncr(10, 3) + npr(5, 2)", 140.0),
+        ];
+
+        for (source, expected_result) in cases {
+            // Act & Assert
+            assert_eq!(
+                eval_source(source),
+                expected_result,
+                "should evaluate function calls"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_function_calls_fail() {
+        // Arrange
+        // Each source is paired with its expected error
+        let cases = [
+            ("foo(1)", "[EVAL ERROR]: unknown function 'foo'"),
+            (
+                "sqrt(1, 2)",
+                "[EVAL ERROR]: function 'sqrt' expects 1 argument, found 2",
+            ),
+            (
+                "max()",
+                "[EVAL ERROR]: function 'max' expects at least 2 arguments, found 0",
+            ),
+            (
+                "gcd(2.5, 5)",
+                "[EVAL ERROR]: function 'gcd' only works on integers fitting in 64 bits, found 2.5",
+            ),
+            (
+                "ncr(3, 5)",
+                "[EVAL ERROR]: function 'ncr' expects r between 0 and n, found r = 5 and n = 3",
+            ),
+        ];
+
+        for (source, expected_error) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied());
+
+            // Act
+            let err = parser.program().unwrap().eval().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.to_string(),
+                expected_error,
+                "should report calls to unknown functions or with invalid arguments"
+            )
+        }
+    }
+
+    #[test]
+    fn test_eval_registered_functions() {
+        // Arrange
+        let mut env = Environment::new();
+        let registry = env.registry_mut();
+
+        registry.register_fn("double", Arity::Exact(1), |args| {
+            Ok(Value::Float(args[0].to_f64() * 2.0))
+        });
+        registry.register_fn("abs", Arity::Exact(1), |args| Ok(args[0].clone()));
+
+        let registry = env.registry().clone();
+
+        // Each source is paired with its expected result
+        let cases = [
+            ("double(21)", Ok(Value::Float(42.0))),
+            ("double(sqrt(16)) + 1", Ok(Value::Float(9.0))),
+            ("abs(-2)", Ok(Value::Integer(-2))),
+            (
+                "double(1, 2)",
+                Err("[AST PARSE ERROR]: function 'double' expects 1 argument, found 2".to_string()),
+            ),
+            (
+                "triple(1)",
+                Err("[AST PARSE ERROR]: unknown function 'triple'".to_string()),
+            ),
+        ];
+
+        for (source, expected_result) in cases {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let mut parser = Parser::new(tokens.iter().copied())
+                .functions(registry.clone())
+                .unknown_functions(UnknownFunctions::Reject);
+
+            // Act
+            let result = parser
+                .program()
+                .map_err(|err| err.to_string())
+                .and_then(|expr| expr.eval_with(&env).map_err(|err| err.to_string()));
+
+            // Assert
+            assert_eq!(
+                result,
+                expected_result,
+                "should check calls against the registered closures and call them, overriding built-in functions"
+            )
+        }
+    }
+
+    #[test]
+    fn test_expression_display_keeps_number_lexemes() {
+        // Arrange
+        let source = "0.1 + 1_000 * 0xFF";
+        let spanned_tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+
+        // Act
+        let expr = Parser::new(spanned_tokens.iter().copied())
+            .source(source)
+            .program()
+            .unwrap();
+        let expr_without_source = Parser::new(tokens.iter().copied()).program().unwrap();
+
+        // Assert
+        assert_eq!(
+            expr.to_string(),
+            "(0.1 + (1_000 * 0xFF))",
+            "should print numbers exactly as they were written"
+        );
+
+        assert_eq!(
+            expr_without_source.to_string(),
+            "(0.1 + (1000 * 255))",
+            "should print numbers as their values if the source is unknown"
+        )
+    }
+
+    #[test]
+    #[cfg(feature = "big-decimal")]
+    fn test_precision_command() {
+        // Arrange
+        let mut env = Environment::new();
+        let sources = ["12345678901234567890 + 1", "2 ^ 64", "0.1 + 0.2"];
+
+        // Act
+        run_command("precision big", &mut env).unwrap();
+
+        let big_values = sources.map(|source| {
+            let program = Parser::statements_from_source(source).unwrap();
+            program.eval_with(&mut env).unwrap().unwrap().to_string()
+        });
+
+        run_command(" precision standard\n", &mut env).unwrap();
+
+        let standard_value = Parser::statements_from_source("1 + 1")
+            .unwrap()
+            .eval_with(&mut env)
+            .unwrap();
+
+        // Assert
+        assert_eq!(
+            big_values,
+            ["12345678901234567891", "18446744073709551616", "0.3"],
+            "should evaluate exactly with big precision"
+        );
+        assert_eq!(
+            standard_value,
+            Some(Value::Integer(2)),
+            "should evaluate integers again with standard precision"
+        )
+    }
+
+    #[test]
+    fn test_precision_command_fails() {
+        // Arrange
+        let cases = [
+            ("precision huge", "unknown precision 'huge'"),
+            ("precision", "unknown command ':precision'"),
+            (
+                "precision big standard",
+                "unknown command ':precision big standard'",
+            ),
+            ("unknown", "unknown command ':unknown'"),
+            ("vars x", "unknown command ':vars x'"),
+            ("angle gradians", "unknown angle mode 'gradians'"),
+        ];
+
+        for (command, expected_error) in cases {
+            let mut env = Environment::new();
+
+            // Act
+            let result = run_command(command, &mut env);
+
+            // Assert
+            assert_eq!(
+                result,
+                Err(expected_error.to_string()),
+                "should report unknown commands and precisions"
+            )
+        }
+    }
+
+    #[test]
+    fn test_vars_command() {
+        // Arrange
+        let mut env = Environment::new();
+
+        // Act
+        let empty_output = run_command("vars", &mut env);
+
+        Parser::statements_from_source("y = 2.5; x = 1; f(a) = a + x")
+            .unwrap()
+            .eval_with(&mut env)
+            .unwrap();
+
+        let output = run_command("vars", &mut env);
+
+        // Assert
+        assert_eq!(
+            empty_output,
+            Ok(None),
+            "should print nothing without variables"
+        );
+        assert_eq!(
+            output,
+            Ok(Some("x = 1\ny = 2.5".to_string())),
+            "should list the variables sorted by name"
+        )
+    }
 }
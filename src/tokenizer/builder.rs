@@ -0,0 +1,364 @@
+#![allow(dead_code)]
+
+use super::{
+    options::{DecimalSeparator, TokenizerOptions},
+    parser::Tokenizer,
+    tokens::Operator,
+};
+
+/// Builds a `Tokenizer` with custom settings. Settings which are not changed keep the defaults used by
+/// `Tokenizer::new`.
+///
+/// ```ignore
+/// let tokenizer = TokenizerBuilder::new()
+///     .disable_operator(Operator::Slash)
+///     .operator_alias('÷', Operator::Slash)
+///     .build("6 ÷ 3");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TokenizerBuilder {
+    options: TokenizerOptions,
+}
+
+impl TokenizerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn decimal_separator(mut self, decimal_separator: DecimalSeparator) -> Self {
+        self.options.decimal_separator = decimal_separator;
+        self
+    }
+
+    /// Stops recognizing the given operator from its symbols. Aliases registered for it are still recognized.
+    pub fn disable_operator(mut self, operator: Operator) -> Self {
+        if self.options.is_operator_enabled(operator) {
+            self.options.disabled_operators.push(operator);
+        }
+
+        self
+    }
+
+    /// Recognizes again an operator disabled before.
+    pub fn enable_operator(mut self, operator: Operator) -> Self {
+        self.options
+            .disabled_operators
+            .retain(|disabled| *disabled != operator);
+        self
+    }
+
+    /// Recognizes the given character as the given operator. Registering the same character again replaces
+    /// its operator.
+    pub fn operator_alias(mut self, alias: char, operator: Operator) -> Self {
+        self.options.operator_aliases.retain(|(c, _)| *c != alias);
+        self.options.operator_aliases.push((alias, operator));
+        self
+    }
+
+    /// Recognizes the given character as a custom operator, like `@`, tokenized as `Token::CustomOperator`.
+    /// It takes precedence over built-in operators and aliases using the same character.
+    pub fn custom_operator(mut self, symbol: char) -> Self {
+        if !self.options.is_custom_operator(symbol) {
+            self.options.custom_operators.push(symbol);
+        }
+
+        self
+    }
+
+    /// Toggles `#` line comments.
+    pub fn comments(mut self, enabled: bool) -> Self {
+        self.options.comments = enabled;
+        self
+    }
+
+    /// Toggles `_` as digit separator inside numbers.
+    pub fn digit_separators(mut self, enabled: bool) -> Self {
+        self.options.digit_separators = enabled;
+        self
+    }
+
+    /// Toggles producing whitespace and comments as tokens, so the source can be rebuilt from the tokens.
+    pub fn preserve_trivia(mut self, enabled: bool) -> Self {
+        self.options.preserve_trivia = enabled;
+        self
+    }
+
+    /// Sets the maximum bytes of the whole source.
+    pub fn max_source_length(mut self, max: usize) -> Self {
+        self.options.max_source_length = max;
+        self
+    }
+
+    /// Sets the maximum bytes of a single number literal.
+    pub fn max_number_length(mut self, max: usize) -> Self {
+        self.options.max_number_length = max;
+        self
+    }
+
+    /// Sets the maximum tokens produced from the whole source.
+    pub fn max_tokens(mut self, max: usize) -> Self {
+        self.options.max_tokens = max;
+        self
+    }
+
+    /// Options built so far, useful for tokenizers built from options like `StreamingTokenizer`.
+    pub fn options(&self) -> &TokenizerOptions {
+        &self.options
+    }
+
+    pub fn build(self, source: &str) -> Tokenizer<'_> {
+        Tokenizer::with_options(source, self.options)
+    }
+}
+
+#[cfg(test)]
+mod tokenizer_builder_tests {
+    use crate::tokenizer::{
+        parser::{Limit, TokenizerErrorKind},
+        symbol::Symbol,
+        tokens::{Operator, Position, Token},
+    };
+
+    use super::TokenizerBuilder;
+
+    #[test]
+    fn test_build_with_defaults() {
+        // Arrange
+        const SOURCE: &str = "1_000 * 2 # comment";
+        let builder = TokenizerBuilder::new();
+
+        // Act
+        let tokens = builder.build(SOURCE).tokenize();
+
+        // Assert
+        assert_eq!(
+            tokens.unwrap(),
+            vec![
+                Token::integer(1000),
+                Token::Operator(Operator::Star),
+                Token::integer(2),
+                Token::Eof(Position::new(1, 20)),
+            ],
+            "should keep the default settings if nothing is changed"
+        )
+    }
+
+    #[test]
+    fn test_build_with_disabled_operator() {
+        // Arrange
+        let builder = TokenizerBuilder::new().disable_operator(Operator::Star);
+
+        // Act
+        let disabled_result = builder.clone().build("2*3").tokenize();
+        let enabled_result = builder.build("2+3").tokenize();
+
+        // Assert
+        assert!(
+            disabled_result.is_err(),
+            "should return error if source contains a disabled operator"
+        );
+
+        assert_eq!(
+            enabled_result.unwrap(),
+            vec![
+                Token::integer(2),
+                Token::Operator(Operator::Plus),
+                Token::integer(3),
+                Token::Eof(Position::new(1, 4)),
+            ],
+            "should keep recognizing operators which are not disabled"
+        )
+    }
+
+    #[test]
+    fn test_build_with_disabled_unit_suffix() {
+        // Arrange
+        let builder = TokenizerBuilder::new().disable_operator(Operator::Degrees);
+
+        // Act
+        let tokens = builder.build("30deg").tokenize();
+
+        // Assert
+        assert_eq!(
+            tokens.unwrap(),
+            vec![
+                Token::integer(30),
+                Token::Identifier(Symbol::intern("deg")),
+                Token::Eof(Position::new(1, 6)),
+            ],
+            "should fall back to an identifier if the unit suffix is disabled"
+        )
+    }
+
+    #[test]
+    fn test_build_with_operator_alias() {
+        // Arrange
+        let builder = TokenizerBuilder::new()
+            .disable_operator(Operator::Slash)
+            .operator_alias('÷', Operator::Slash);
+
+        // Act
+        let alias_result = builder.clone().build("6 ÷ 3").tokenize();
+        let symbol_result = builder.build("6 / 3").tokenize();
+
+        // Assert
+        assert_eq!(
+            alias_result.unwrap(),
+            vec![
+                Token::integer(6),
+                Token::Operator(Operator::Slash),
+                Token::integer(3),
+                Token::Eof(Position::new(1, 6)),
+            ],
+            "should recognize alias as its operator even if operator symbol is disabled"
+        );
+
+        assert!(
+            symbol_result.is_err(),
+            "should not recognize the symbol of a disabled operator"
+        )
+    }
+
+    #[test]
+    fn test_build_with_custom_operator() {
+        // Arrange
+        let builder = TokenizerBuilder::new()
+            .custom_operator('@')
+            .custom_operator('*');
+
+        // Act
+        let result = builder.build("1 @ 3*2").tokenize();
+        let default_result = TokenizerBuilder::new().build("1 @ 3").tokenize();
+
+        // Assert
+        assert_eq!(
+            result.unwrap(),
+            vec![
+                Token::integer(1),
+                Token::CustomOperator('@'),
+                Token::integer(3),
+                Token::CustomOperator('*'),
+                Token::integer(2),
+                Token::Eof(Position::new(1, 8)),
+            ],
+            "should recognize custom operators, even over built-in ones"
+        );
+
+        assert!(
+            default_result.is_err(),
+            "should not recognize custom operators which are not registered"
+        )
+    }
+
+    #[test]
+    fn test_build_with_disabled_double_char_operator() {
+        // Arrange
+        let builder = TokenizerBuilder::new().disable_operator(Operator::DoubleSlash);
+
+        // Act
+        let tokens = builder.build("7//2").tokenize();
+
+        // Assert
+        assert_eq!(
+            tokens.unwrap(),
+            vec![
+                Token::integer(7),
+                Token::Operator(Operator::Slash),
+                Token::Operator(Operator::Slash),
+                Token::integer(2),
+                Token::Eof(Position::new(1, 5)),
+            ],
+            "should fall back to single character operators if double character one is disabled"
+        )
+    }
+
+    #[test]
+    fn test_build_without_comments_and_digit_separators() {
+        // Arrange
+        let builder = TokenizerBuilder::new()
+            .comments(false)
+            .digit_separators(false);
+
+        // Act
+        let comment_result = builder.clone().build("1 # comment").tokenize();
+        let separator_result = builder.build("1_000").tokenize();
+
+        // Assert
+        assert!(
+            comment_result.is_err(),
+            "should not skip comments if they are disabled"
+        );
+
+        assert!(
+            separator_result.is_err(),
+            "should not allow underscores in numbers if digit separators are disabled"
+        )
+    }
+
+    #[test]
+    fn test_build_with_limits() {
+        // Arrange
+        // Each builder is paired with a source exceeding its limit, and the expected error kind and position
+        let cases = [
+            (
+                TokenizerBuilder::new().max_source_length(8),
+                "1 + 2 + 3 + 4",
+                Limit::SourceLength,
+                8,
+                Position::new(1, 9),
+            ),
+            (
+                TokenizerBuilder::new().max_number_length(4),
+                "1 +\n 12345",
+                Limit::NumberLength,
+                4,
+                Position::new(2, 2),
+            ),
+            (
+                TokenizerBuilder::new().max_tokens(3),
+                "1 + 2 + 3",
+                Limit::TokenCount,
+                3,
+                Position::new(1, 7),
+            ),
+        ];
+
+        for (builder, source, expected_limit, expected_max, expected_position) in cases {
+            // Act
+            let err = builder.build(source).tokenize().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.kind(),
+                &TokenizerErrorKind::LimitExceeded {
+                    limit: expected_limit,
+                    max: expected_max
+                },
+                "should report which limit was exceeded"
+            );
+            assert_eq!(
+                err.position(),
+                expected_position,
+                "should report the position of the token exceeding the limit"
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_within_limits() {
+        // Arrange
+        let builder = TokenizerBuilder::new()
+            .max_source_length(9)
+            .max_number_length(4)
+            .max_tokens(5);
+
+        // Act
+        let tokens = builder.build("1234 + 56").tokenize();
+
+        // Assert
+        assert!(
+            tokens.is_ok(),
+            "should not fail if source reaches limits without exceeding them"
+        )
+    }
+}
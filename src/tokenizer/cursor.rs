@@ -0,0 +1,123 @@
+/// Scans the characters of a source keeping track of the byte offset of the next one.
+///
+/// Unlike cloning a `Chars` iterator, looking at the current character and checking for the end of the source
+/// are constant time operations, since they don't need to walk the remaining characters.
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    source: &'a str,
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self { source, offset: 0 }
+    }
+
+    /// Takes a look at the current character without consume it.
+    pub fn peek(&self) -> Option<char> {
+        self.remaining().chars().next()
+    }
+
+    /// Takes a look at the character `n` positions ahead of the current one without consume any character.
+    pub fn peek_nth(&self, n: usize) -> Option<char> {
+        self.remaining().chars().nth(n)
+    }
+
+    pub fn is_end(&self) -> bool {
+        self.offset == self.source.len()
+    }
+
+    /// Byte offset of the current character in the source.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Part of the source consumed from the given byte offset until the current character.
+    pub fn consumed_since(&self, start: usize) -> &'a str {
+        &self.source[start..self.offset]
+    }
+
+    /// Part of the source not consumed yet.
+    pub fn remaining(&self) -> &'a str {
+        &self.source[self.offset..]
+    }
+}
+
+impl Iterator for Cursor<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.offset += c.len_utf8();
+
+        Some(c)
+    }
+}
+
+#[cfg(test)]
+mod tokenizer_cursor_tests {
+    use super::Cursor;
+
+    const SOURCE: &str = "4*3-2+1";
+
+    #[test]
+    fn test_peek() {
+        // Arrange
+        let mut cursor = Cursor::new(SOURCE);
+
+        for c in SOURCE.chars() {
+            // Act
+            let peeked = cursor.peek().unwrap();
+
+            // Assert
+            assert_eq!(
+                peeked, c,
+                "should look at the current character and return it without consume it"
+            );
+
+            cursor.next();
+        }
+    }
+
+    #[test]
+    fn test_peek_nth() {
+        // Arrange
+        let cursor = Cursor::new(SOURCE);
+
+        // Act & Assert
+        assert_eq!(
+            cursor.peek_nth(2),
+            Some('3'),
+            "should look ahead of the current character"
+        );
+
+        assert_eq!(
+            cursor.offset(),
+            0,
+            "should not consume characters while looking ahead"
+        )
+    }
+
+    #[test]
+    fn test_next_tracks_offset() {
+        // Arrange
+        // Notice `π` takes two bytes, so offset moves by bytes instead of characters
+        let mut cursor = Cursor::new("π1");
+
+        // Act & Assert
+        assert_eq!(cursor.next(), Some('π'), "should consume current character");
+        assert_eq!(cursor.offset(), 2, "should move offset by character bytes");
+        assert_eq!(cursor.remaining(), "1", "should keep remaining characters");
+
+        assert_eq!(cursor.next(), Some('1'), "should consume current character");
+        assert!(
+            cursor.is_end(),
+            "should be at the end once all characters were consumed"
+        );
+        assert_eq!(
+            cursor.next(),
+            None,
+            "should not yield characters after the end"
+        )
+    }
+}
@@ -1,98 +1,513 @@
-use std::str::Chars;
+use std::borrow::Cow;
 
-use super::tokens::{Operator, Token};
+use super::{
+    cursor::Cursor,
+    options::{DecimalSeparator, TokenizerOptions},
+    parser::{InvalidNumberReason, Limit, TokenizerErrorKind},
+    symbol::{Symbol, MAX_INTERNED_BYTES},
+    tokens::{Constant, Operator, Token},
+};
 
-/// Takes a look at the current element of the iterator without consume it.
-pub fn peek(chars: &Chars) -> Option<char> {
-    chars.clone().next()
+/// Result of a parser: a token, `None` if the characters are skipped without producing a token (like whitespace),
+/// or the kind of error found.
+///
+/// Parsers which don't match the current character return error without consuming any character, so the
+/// next parser can be tried.
+pub type ParseResult = Result<Option<Token>, TokenizerErrorKind>;
+
+/// Signature shared by every parser, so they can be tried in sequence.
+pub type ParseFn = fn(&mut Cursor, &TokenizerOptions) -> ParseResult;
+
+/// Error returned by parsers when the current character doesn't match them.
+fn unexpected(cursor: &Cursor) -> TokenizerErrorKind {
+    match cursor.peek() {
+        Some(c) => TokenizerErrorKind::UnexpectedCharacter(c),
+        None => TokenizerErrorKind::UnexpectedEndOfInput,
+    }
+}
+
+/// Error returned by number parsers once the number is known to be malformed.
+fn invalid_number(
+    cursor: &Cursor,
+    start: usize,
+    reason: InvalidNumberReason,
+) -> TokenizerErrorKind {
+    TokenizerErrorKind::InvalidNumber {
+        lexeme: cursor.consumed_since(start).to_string(),
+        reason,
+    }
+}
+
+/// Checks the number started at `start` is not longer than the limit of the options. It is checked while the
+/// number is consumed, so a huge number fails as soon as it exceeds the limit.
+fn check_number_length(
+    cursor: &Cursor,
+    start: usize,
+    options: &TokenizerOptions,
+) -> Result<(), TokenizerErrorKind> {
+    if cursor.offset() - start > options.max_number_length {
+        return Err(TokenizerErrorKind::LimitExceeded {
+            limit: Limit::NumberLength,
+            max: options.max_number_length,
+        });
+    }
+
+    Ok(())
 }
 
-pub fn parse_operator<'a>(chars: &'a mut Chars) -> Result<Option<Token>, &'a str> {
-    let c = peek(chars);
+/// Parses an operator, either from its symbol or from an alias or custom operator registered in the options.
+///
+/// Disabled operators are not recognized from their symbols, but they are still recognized from their aliases.
+pub fn parse_operator(cursor: &mut Cursor, options: &TokenizerOptions) -> ParseResult {
+    let is_enabled = |operator| options.is_operator_enabled(operator);
+
+    if let Some(symbol) = cursor.peek().filter(|c| options.is_custom_operator(*c)) {
+        cursor.next();
+        return Ok(Some(Token::CustomOperator(symbol)));
+    }
 
-    let token = match c {
-        Some('+') => Ok(Some(Token::Operator(Operator::Plus))),
-        Some('-') => Ok(Some(Token::Operator(Operator::Minus))),
-        Some('*') => Ok(Some(Token::Operator(Operator::Star))),
-        Some('/') => Ok(Some(Token::Operator(Operator::Slash))),
-        Some('\n' | ' ') => Ok(None),
-        _ => Err("cannot parse operator"),
+    // Current and next characters are matched together, so operators made of two characters like `**`
+    // take precedence over their single character prefix
+    let (token, length) = match (cursor.peek(), cursor.peek_nth(1)) {
+        (Some('*'), Some('*')) if is_enabled(Operator::Caret) => (Some(Operator::Caret), 2),
+        (Some('/'), Some('/')) if is_enabled(Operator::DoubleSlash) => {
+            (Some(Operator::DoubleSlash), 2)
+        }
+        (Some('<'), Some('=')) if is_enabled(Operator::LessEqual) => (Some(Operator::LessEqual), 2),
+        (Some('<'), Some('<')) if is_enabled(Operator::LessLess) => (Some(Operator::LessLess), 2),
+        (Some('>'), Some('>')) if is_enabled(Operator::GreaterGreater) => {
+            (Some(Operator::GreaterGreater), 2)
+        }
+        (Some('>'), Some('=')) if is_enabled(Operator::GreaterEqual) => {
+            (Some(Operator::GreaterEqual), 2)
+        }
+        (Some('='), Some('=')) if is_enabled(Operator::EqualEqual) => {
+            (Some(Operator::EqualEqual), 2)
+        }
+        (Some('!'), Some('=')) if is_enabled(Operator::BangEqual) => (Some(Operator::BangEqual), 2),
+        (Some(c), _) if c.is_whitespace() => (None, 1),
+        (Some(c), _) => match (options.operator_alias(c), c) {
+            (Some(operator), _) => (Some(operator), 1),
+            (None, '+') if is_enabled(Operator::Plus) => (Some(Operator::Plus), 1),
+            (None, '-') if is_enabled(Operator::Minus) => (Some(Operator::Minus), 1),
+            (None, '*') if is_enabled(Operator::Star) => (Some(Operator::Star), 1),
+            (None, '/') if is_enabled(Operator::Slash) => (Some(Operator::Slash), 1),
+            (None, '^') if is_enabled(Operator::Caret) => (Some(Operator::Caret), 1),
+            (None, '%') if is_enabled(Operator::Percent) => (Some(Operator::Percent), 1),
+            (None, '!') if is_enabled(Operator::Bang) => (Some(Operator::Bang), 1),
+            (None, '<') if is_enabled(Operator::Less) => (Some(Operator::Less), 1),
+            (None, '>') if is_enabled(Operator::Greater) => (Some(Operator::Greater), 1),
+            (None, '&') if is_enabled(Operator::Ampersand) => (Some(Operator::Ampersand), 1),
+            _ => return Err(unexpected(cursor)),
+        },
+        _ => return Err(unexpected(cursor)),
     };
 
-    // If operator matches, consumes current character from iterator
+    // If operator matches, consumes its characters from iterator
+    cursor.nth(length - 1);
+
+    Ok(token.map(Token::Operator))
+}
+
+/// Parses the `%` suffix of percent numbers like `15%`. It is only tried right after a number token, since
+/// a `%` anywhere else is the modulo operator.
+///
+/// A `%` followed by something which starts an operand, like `10%3`, is still the modulo operator.
+pub fn parse_percent_suffix(cursor: &mut Cursor, options: &TokenizerOptions) -> ParseResult {
+    let decimal_mark = options.decimal_separator.decimal_mark();
+    let starts_operand = |c: char| c.is_alphanumeric() || c == '(' || c == decimal_mark;
+
+    if !options.is_operator_enabled(Operator::PercentSuffix)
+        || cursor.peek() != Some('%')
+        || cursor.peek_nth(1).is_some_and(starts_operand)
+    {
+        return Err(unexpected(cursor));
+    }
+
+    cursor.next();
+
+    Ok(Some(Token::Operator(Operator::PercentSuffix)))
+}
+
+/// Parses the `deg` and `rad` angle units written right after a number, like `30deg`. Longer words starting with
+/// them, like `30degrees`, are left to `parse_word`, so they are multiplied as any other identifier.
+pub fn parse_unit_suffix(cursor: &mut Cursor, options: &TokenizerOptions) -> ParseResult {
+    let continues_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    let operator = [("deg", Operator::Degrees), ("rad", Operator::Radians)]
+        .into_iter()
+        .find(|(unit, operator)| {
+            options.is_operator_enabled(*operator)
+                && unit
+                    .chars()
+                    .enumerate()
+                    .all(|(i, c)| cursor.peek_nth(i) == Some(c))
+                && !cursor.peek_nth(unit.len()).is_some_and(continues_word)
+        })
+        .map(|(_, operator)| operator)
+        .ok_or_else(|| unexpected(cursor))?;
+
+    // Units are three characters long
+    cursor.nth(2);
+
+    Ok(Some(Token::Operator(operator)))
+}
+
+pub fn parse_punctuation(cursor: &mut Cursor, options: &TokenizerOptions) -> ParseResult {
+    let argument_separator = options.decimal_separator.argument_separator();
+
+    let token = match cursor.peek() {
+        Some('(') => Ok(Some(Token::LeftParen)),
+        Some(')') => Ok(Some(Token::RightParen)),
+        Some(c) if c == argument_separator => Ok(Some(Token::Comma)),
+        Some('|') => Ok(Some(Token::Pipe)),
+        Some('?') => Ok(Some(Token::Question)),
+        Some(':') => Ok(Some(Token::Colon)),
+        // Argument separator is checked first, so in decimal comma mode `;` separates arguments instead
+        Some(';') => Ok(Some(Token::Semicolon)),
+        // A `=` followed by a comparison character is not an assignment, so `==` is left to operators and
+        // stray sequences like `=<` are rejected
+        Some('=') if !matches!(cursor.peek_nth(1), Some('=' | '<' | '>')) => {
+            Ok(Some(Token::Equals))
+        }
+        _ => Err(unexpected(cursor)),
+    };
+
+    // If punctuation matches, consumes current character from iterator
     if token.is_ok() {
-        chars.next();
+        cursor.next();
     }
 
     token
 }
 
-pub fn parse_number<'a>(chars: &'a mut Chars) -> Result<Option<Token>, &'a str> {
-    const CANNOT_PARSE_MSG: &'static str = "cannot parse number";
-    let mut str_number = String::new();
+/// Parses a word made of letters, digits and underscores starting with a letter, which is either the name of
+/// a constant like `pi`, a non-finite number like `inf`, a boolean operator like `and` or an identifier like `x`.
+///
+/// Whole word is matched against the constant names and keywords, so words which only start like one of them
+/// (`pie`, `information`, `order`) are identifiers. Constant names and keywords are reserved, so they are never
+/// identifiers, unless the boolean operator is disabled.
+pub fn parse_word(cursor: &mut Cursor, options: &TokenizerOptions) -> ParseResult {
+    if !cursor.peek().is_some_and(|c| c.is_alphabetic()) {
+        return Err(unexpected(cursor));
+    }
+
+    let start = cursor.offset();
+
+    while cursor
+        .peek()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_')
+    {
+        cursor.next();
+    }
+
+    let word = cursor.consumed_since(start);
+
+    if let Some(constant) = Constant::from_name(word) {
+        return Ok(Some(Token::Constant(constant)));
+    }
+
+    let operator = match word {
+        "and" => Some(Operator::And),
+        "or" => Some(Operator::Or),
+        "not" => Some(Operator::Not),
+        "bor" => Some(Operator::BitOr),
+        _ => None,
+    };
+
+    if let Some(operator) = operator.filter(|operator| options.is_operator_enabled(*operator)) {
+        return Ok(Some(Token::Operator(operator)));
+    }
+
+    // Non-finite numbers are written as printed by evaluation, so results like `inf` can be typed back in
+    let value = match word.to_lowercase().as_str() {
+        "inf" | "infinity" => f64::INFINITY,
+        "nan" => f64::NAN,
+        _ => {
+            let symbol = Symbol::try_intern(word).ok_or(TokenizerErrorKind::LimitExceeded {
+                limit: Limit::InternedNames,
+                max: MAX_INTERNED_BYTES,
+            })?;
 
-    while let Some(c) = peek(chars) {
-        // If first character is not numeric means parser doesnt match and return `None` immediately
-        if !c.is_numeric() && str_number.is_empty() {
-            return Err(CANNOT_PARSE_MSG);
+            return Ok(Some(Token::Identifier(symbol)));
         }
+    };
+
+    Ok(Some(Token::Float(value)))
+}
+
+/// Skips a comment, which starts with `#` and lasts until the end of the line. Comments only produce tokens
+/// if trivia is preserved.
+pub fn parse_comment(cursor: &mut Cursor, options: &TokenizerOptions) -> ParseResult {
+    if !options.comments || cursor.peek() != Some('#') {
+        return Err(unexpected(cursor));
+    }
+
+    // Line break is not part of the comment, so it is left to be skipped as whitespace
+    while cursor.peek().is_some_and(|c| c != '\n') {
+        cursor.next();
+    }
+
+    Ok(options.preserve_trivia.then_some(Token::Comment))
+}
+
+/// Skips a run of whitespace. Whitespace only produces tokens if trivia is preserved.
+pub fn parse_whitespace(cursor: &mut Cursor, options: &TokenizerOptions) -> ParseResult {
+    if !cursor.peek().is_some_and(char::is_whitespace) {
+        return Err(unexpected(cursor));
+    }
+
+    while cursor.peek().is_some_and(char::is_whitespace) {
+        cursor.next();
+    }
+
+    Ok(options.preserve_trivia.then_some(Token::Whitespace))
+}
+
+/// Parses an integer number written in the given `radix` and prefixed by `0` followed by one of the given `markers`.
+///
+/// If prefix does not match, returns error without consuming any character. Once prefix matches, every
+/// alphanumeric character is consumed as part of the number, so malformed numbers like `0xG1` are reported as errors.
+fn parse_prefixed_integer(
+    cursor: &mut Cursor,
+    options: &TokenizerOptions,
+    markers: [char; 2],
+    radix: u32,
+) -> ParseResult {
+    if cursor.peek() != Some('0') || !cursor.peek_nth(1).is_some_and(|c| markers.contains(&c)) {
+        return Err(unexpected(cursor));
+    }
 
-        // If current character is `.` so we must check if number string already has a `.`
-        if c == '.' {
-            // If it has, so it is an invalid number, because only one `.` character is allowed per number
-            if str_number.find('.').is_some() {
-                return Err(CANNOT_PARSE_MSG);
+    // Prefix matches, so consumes it from iterator
+    let start = cursor.offset();
+    cursor.nth(1);
+    let digits_start = cursor.offset();
+
+    while let Some(c) = cursor.peek() {
+        check_number_length(cursor, start, options)?;
+
+        if !c.is_alphanumeric() {
+            break;
+        }
+
+        cursor.next();
+    }
+
+    check_number_length(cursor, start, options)?;
+
+    let digits = cursor.consumed_since(digits_start);
+
+    if digits.is_empty() {
+        return Err(invalid_number(
+            cursor,
+            start,
+            InvalidNumberReason::MissingDigits,
+        ));
+    }
+
+    // Numbers too big for `i64` fall back to floats, losing precision instead of failing
+    match u64::from_str_radix(digits, radix) {
+        Ok(number) => Ok(Some(match i64::try_from(number) {
+            Ok(value) => Token::Integer(value),
+            Err(_) => Token::Float(number as f64),
+        })),
+        Err(_) => Err(invalid_number(
+            cursor,
+            start,
+            InvalidNumberReason::InvalidDigits { radix },
+        )),
+    }
+}
+
+/// Parses an hexadecimal integer number like `0xFF`.
+pub fn parse_hex_number(cursor: &mut Cursor, options: &TokenizerOptions) -> ParseResult {
+    parse_prefixed_integer(cursor, options, ['x', 'X'], 16)
+}
+
+/// Parses a binary integer number like `0b1010`.
+pub fn parse_binary_number(cursor: &mut Cursor, options: &TokenizerOptions) -> ParseResult {
+    parse_prefixed_integer(cursor, options, ['b', 'B'], 2)
+}
+
+/// Parses an octal integer number like `0o755`.
+pub fn parse_octal_number(cursor: &mut Cursor, options: &TokenizerOptions) -> ParseResult {
+    parse_prefixed_integer(cursor, options, ['o', 'O'], 8)
+}
+
+pub fn parse_number(cursor: &mut Cursor, options: &TokenizerOptions) -> ParseResult {
+    let start = cursor.offset();
+    let decimal_mark = options.decimal_separator.decimal_mark();
+    let mut has_decimal_mark = false;
+    let mut has_separators = false;
+
+    while let Some(c) = cursor.peek() {
+        check_number_length(cursor, start, options)?;
+
+        let is_first = cursor.offset() == start;
+
+        // Underscores are digit separators, so they are skipped as long as they are surrounded by digits
+        if c == '_' && options.digit_separators {
+            let previous_is_digit = cursor
+                .consumed_since(start)
+                .ends_with(|c: char| c.is_ascii_digit());
+            let next_is_digit = cursor.peek_nth(1).is_some_and(|c| c.is_ascii_digit());
+
+            // If underscore is not followed by a digit either, it has nothing to do with a number
+            if is_first && !next_is_digit {
+                return Err(unexpected(cursor));
+            }
+
+            cursor.next();
+
+            if !previous_is_digit || !next_is_digit {
+                return Err(invalid_number(
+                    cursor,
+                    start,
+                    InvalidNumberReason::MisplacedSeparator,
+                ));
+            }
+
+            has_separators = true;
+            continue;
+        }
+
+        // If first character is not numeric means parser doesnt match and return `None` immediately,
+        // unless it is a leading decimal mark followed by a digit, like `.5`
+        let is_leading_mark =
+            c == decimal_mark && cursor.peek_nth(1).is_some_and(|c| c.is_ascii_digit());
+
+        if !c.is_ascii_digit() && !is_leading_mark && is_first {
+            return Err(unexpected(cursor));
+        }
+
+        // If decimal mark is `,`, a `.` inside a number is a mistake rather than the end of the number
+        if c == '.' && options.decimal_separator == DecimalSeparator::Comma {
+            cursor.next();
+
+            return Err(invalid_number(
+                cursor,
+                start,
+                InvalidNumberReason::MisplacedDot,
+            ));
+        }
+
+        // If current character is the decimal mark so we must check if number already has one.
+        // Notice digits after the mark are optional, so a trailing dot like `5.` is intentionally accepted as `5.0`
+        if c == decimal_mark {
+            // If it has, so it is an invalid number, because only one decimal mark is allowed per number
+            if has_decimal_mark {
+                return Err(invalid_number(
+                    cursor,
+                    start,
+                    InvalidNumberReason::RepeatedDecimalMark,
+                ));
             }
 
-            chars.next();
-            str_number.push(c);
+            cursor.next();
+            has_decimal_mark = true;
             continue;
         }
 
         // If some characters already matched but reaches a non numeric character, it means
         // integer number has been ended
-        if !c.is_numeric() {
+        if !c.is_ascii_digit() {
             break;
         }
 
         // Keep updating iterator status while numeric characters are beign found
-        chars.next();
-        str_number.push(c);
+        cursor.next();
     }
 
-    let parsed_number = str_number.parse::<f64>().expect(CANNOT_PARSE_MSG);
-    let token = Token::Number(parsed_number);
+    // If number is followed by an exponent marker, the exponent part (optional sign and at least one digit)
+    // belongs to the number too, so `1.5e-3` is parsed as a single number. A marker starting a word ends the
+    // number instead, so `2exp(1)` is an implicit multiplication
+    let starts_word = cursor.peek_nth(1).is_some_and(char::is_alphabetic);
+    let has_exponent = matches!(cursor.peek(), Some('e' | 'E')) && !starts_word;
+
+    if has_exponent {
+        cursor.next();
+
+        if let Some('+' | '-') = cursor.peek() {
+            cursor.next();
+        }
+
+        let mut has_exponent_digits = false;
+
+        while let Some(c) = cursor.peek() {
+            check_number_length(cursor, start, options)?;
+
+            if !c.is_ascii_digit() {
+                break;
+            }
+
+            cursor.next();
+            has_exponent_digits = true;
+        }
 
-    Ok(Some(token))
+        // An exponent marker without digits, like `1e`, is not a valid number
+        if !has_exponent_digits {
+            return Err(TokenizerErrorKind::UnterminatedExponent);
+        }
+    }
+
+    check_number_length(cursor, start, options)?;
+
+    // Number is parsed straight from the source, unless it has to be normalized first, since `_` separators
+    // and `,` decimal mark are not understood by number parsing
+    let lexeme = cursor.consumed_since(start);
+    let number = if has_separators || (has_decimal_mark && decimal_mark != '.') {
+        Cow::Owned(
+            lexeme
+                .chars()
+                .filter(|&c| c != '_')
+                .map(|c| if c == decimal_mark { '.' } else { c })
+                .collect(),
+        )
+    } else {
+        Cow::Borrowed(lexeme)
+    };
+
+    // Numbers without decimal mark nor exponent are integers, unless they don't fit in `i64`, in which case
+    // they fall back to floats, losing precision instead of failing
+    if !has_decimal_mark && !has_exponent {
+        if let Ok(parsed_number) = number.parse::<i64>() {
+            return Ok(Some(Token::Integer(parsed_number)));
+        }
+    }
+
+    // Every character was checked above, but parsing may still fail (like an empty number at the end of the source),
+    // so failure is reported as error instead of aborting
+    match number.parse::<f64>() {
+        Ok(parsed_number) => Ok(Some(Token::Float(parsed_number))),
+        Err(_) if number.is_empty() => Err(unexpected(cursor)),
+        Err(_) => Err(invalid_number(
+            cursor,
+            start,
+            InvalidNumberReason::Unparseable,
+        )),
+    }
 }
 
 #[cfg(test)]
 mod tokenizer_helpers_tests {
     use crate::tokenizer::{
-        helpers::{parse_operator, peek},
-        tokens::{Operator, Token},
+        cursor::Cursor,
+        helpers::{
+            parse_binary_number, parse_comment, parse_hex_number, parse_octal_number,
+            parse_operator, parse_punctuation, parse_word,
+        },
+        options::{DecimalSeparator, TokenizerOptions},
+        parser::{InvalidNumberReason, TokenizerErrorKind},
+        symbol::Symbol,
+        tokens::{Constant, Operator, Token},
     };
 
     use super::parse_number;
 
-    #[test]
-    fn test_peek() {
-        // Arrange
-        const SOURCE: &str = "4*3-2+1";
-
-        for (i, c) in SOURCE.chars().enumerate() {
-            // Act
-            let peeked = peek(&SOURCE[i..].chars()).unwrap();
-
-            // Assert
-            assert_eq!(peeked, c, "should look at the current element of the iterator and return it without consume it")
-        }
-    }
-
     #[test]
     fn test_parse_operator_success() {
         // Arrange
-        let mut operator_chars = "+-*/  \n".chars();
+        let mut operator_chars = Cursor::new("+-*/^%!  \n\t\r");
 
         // `expected_operator_tokens` slice is based on the `VALID_SOURCE` input.
         // Any change on some of them should be reflected in the other in order to keep sync
@@ -102,13 +517,19 @@ mod tokenizer_helpers_tests {
             Some(Token::Operator(Operator::Minus)),
             Some(Token::Operator(Operator::Star)),
             Some(Token::Operator(Operator::Slash)),
+            Some(Token::Operator(Operator::Caret)),
+            Some(Token::Operator(Operator::Percent)),
+            Some(Token::Operator(Operator::Bang)),
+            None, // whitespace
             None, // whitespace
             None, // end of lines
+            None, // tabs
+            None, // carriage returns
         ];
 
         for token in expected_operator_tokens {
             // Act
-            let parsed = parse_operator(&mut operator_chars).unwrap();
+            let parsed = parse_operator(&mut operator_chars, &TokenizerOptions::default()).unwrap();
 
             // Assert
             assert_eq!(
@@ -118,13 +539,59 @@ mod tokenizer_helpers_tests {
         }
     }
 
+    #[test]
+    fn test_parse_double_star_operator() {
+        // Arrange
+        let mut operator_chars = Cursor::new("***");
+
+        // Act
+        let first = parse_operator(&mut operator_chars, &TokenizerOptions::default());
+        let second = parse_operator(&mut operator_chars, &TokenizerOptions::default());
+
+        // Assert
+        assert_eq!(
+            first.unwrap(),
+            Some(Token::Operator(Operator::Caret)),
+            "should parse `**` as power operator"
+        );
+
+        assert_eq!(
+            second.unwrap(),
+            Some(Token::Operator(Operator::Star)),
+            "should parse the remaining `*` as star operator"
+        )
+    }
+
+    #[test]
+    fn test_parse_double_slash_operator() {
+        // Arrange
+        let mut operator_chars = Cursor::new("/ //");
+
+        // `expected_operator_tokens` slice is based on the `operator_chars` input.
+        // Any change on some of them should be reflected in the other in order to keep sync
+        // the input and the expected set of tokens
+        let expected_operator_tokens = &[
+            Some(Token::Operator(Operator::Slash)),
+            None, // whitespace
+            Some(Token::Operator(Operator::DoubleSlash)),
+        ];
+
+        for token in expected_operator_tokens {
+            // Act
+            let parsed = parse_operator(&mut operator_chars, &TokenizerOptions::default()).unwrap();
+
+            // Assert
+            assert_eq!(*token, parsed, "should distinguish `/` from `//` operator")
+        }
+    }
+
     #[test]
     fn test_parse_operator_fail() {
         // Arrange
-        let mut non_operator_chars = "1<>(invalid".chars();
+        let mut non_operator_chars = Cursor::new("1<>(invalid");
 
         // Act
-        let result = parse_operator(&mut non_operator_chars);
+        let result = parse_operator(&mut non_operator_chars, &TokenizerOptions::default());
 
         // Assert
         assert!(
@@ -133,23 +600,190 @@ mod tokenizer_helpers_tests {
         );
     }
 
+    #[test]
+    fn test_parse_punctuation_success() {
+        // Arrange
+        let mut punctuation_chars = Cursor::new("(),|;=?:");
+
+        // `expected_punctuation_tokens` slice is based on the `punctuation_chars` input.
+        // Any change on some of them should be reflected in the other in order to keep sync
+        // the input and the expected set of tokens
+        let expected_punctuation_tokens = &[
+            Some(Token::LeftParen),
+            Some(Token::RightParen),
+            Some(Token::Comma),
+            Some(Token::Pipe),
+            Some(Token::Semicolon),
+            Some(Token::Equals),
+            Some(Token::Question),
+            Some(Token::Colon),
+        ];
+
+        for token in expected_punctuation_tokens {
+            // Act
+            let parsed =
+                parse_punctuation(&mut punctuation_chars, &TokenizerOptions::default()).unwrap();
+
+            // Assert
+            assert_eq!(
+                *token, parsed,
+                "should take current character and parse it as punctuation token"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_punctuation_fail() {
+        // Arrange
+        let mut non_punctuation_chars = Cursor::new("[1");
+
+        // Act
+        let result = parse_punctuation(&mut non_punctuation_chars, &TokenizerOptions::default());
+
+        // Assert
+        assert!(
+            result.is_err(),
+            "should return error if given character cannot be parsed as punctuation token"
+        );
+
+        assert_eq!(
+            non_punctuation_chars.count(),
+            2,
+            "should not consume character if it cannot be parsed as punctuation token"
+        )
+    }
+
+    #[test]
+    fn test_parse_comment() {
+        // Arrange
+        let mut comment_chars = Cursor::new("# area\n3");
+        let mut non_comment_chars = Cursor::new("3 # area");
+
+        // Act
+        let parsed = parse_comment(&mut comment_chars, &TokenizerOptions::default());
+        let result = parse_comment(&mut non_comment_chars, &TokenizerOptions::default());
+
+        // Assert
+        assert_eq!(
+            parsed.unwrap(),
+            None,
+            "should skip comment without producing a token"
+        );
+
+        assert_eq!(
+            comment_chars.remaining(),
+            "\n3",
+            "should consume comment until the end of the line"
+        );
+
+        assert!(
+            result.is_err(),
+            "should return error if current character does not start a comment"
+        )
+    }
+
+    #[test]
+    fn test_parse_word() {
+        // Arrange
+        let mut constant_chars = Cursor::new("PI*2");
+        let mut identifier_chars = Cursor::new("pin_2 = 1");
+        let mut non_word_chars = Cursor::new("2pi");
+
+        // Act
+        let constant = parse_word(&mut constant_chars, &TokenizerOptions::default());
+        let identifier = parse_word(&mut identifier_chars, &TokenizerOptions::default());
+        let result = parse_word(&mut non_word_chars, &TokenizerOptions::default());
+
+        // Assert
+        assert_eq!(
+            constant.unwrap(),
+            Some(Token::Constant(Constant::Pi)),
+            "should parse constant name ignoring case"
+        );
+
+        assert_eq!(
+            constant_chars.remaining(),
+            "*2",
+            "should consume only the characters of the constant name"
+        );
+
+        assert_eq!(
+            identifier.unwrap(),
+            Some(Token::Identifier(Symbol::intern("pin_2"))),
+            "should parse words which are not constant names as identifiers"
+        );
+
+        assert!(
+            result.is_err() && non_word_chars.remaining() == "2pi",
+            "should return error without consuming if current character does not start a word"
+        )
+    }
+
+    #[test]
+    fn test_parse_word_number_keywords() {
+        // Arrange
+        let words = [
+            "inf",
+            "Infinity",
+            "INF",
+            "information",
+            "nan",
+            "NaN",
+            "nano",
+        ];
+
+        // `expected_tokens` slice is based on the `words` input.
+        // Any change on some of them should be reflected in the other in order to keep sync
+        // the input and the expected set of tokens
+        let expected_tokens = [
+            Some(Token::float(f64::INFINITY)),
+            Some(Token::float(f64::INFINITY)),
+            Some(Token::float(f64::INFINITY)),
+            Some(Token::Identifier(Symbol::intern("information"))),
+            None,
+            None,
+            Some(Token::Identifier(Symbol::intern("nano"))),
+        ];
+
+        for (word, expected_token) in words.into_iter().zip(expected_tokens) {
+            let mut word_chars = Cursor::new(word);
+
+            // Act
+            let token = parse_word(&mut word_chars, &TokenizerOptions::default()).unwrap();
+
+            // Assert
+            match expected_token {
+                Some(expected_token) => assert_eq!(
+                    token,
+                    Some(expected_token),
+                    "should parse infinity keywords ignoring case, but only if they are the whole word"
+                ),
+                // NaN is never equal to itself, so NaN tokens are checked by their value
+                None => assert!(
+                    matches!(token, Some(Token::Float(value)) if value.is_nan()),
+                    "should parse nan keyword ignoring case"
+                ),
+            }
+        }
+    }
+
     #[test]
     fn test_parse_number_success() {
         // Arrange
-        let numbers_chars = vec!["10.25".chars(), "5".chars(), "0".chars()];
+        let numbers_chars = vec![Cursor::new("10.25"), Cursor::new("5"), Cursor::new("0")];
 
         // `expected_numbers_tokens` slice is based on the `numbers_chars` input.
         // Any change on some of them should be reflected in the other in order to keep sync
         // the input and the expected set of tokens
         let expected_numbers_tokens = &[
-            Some(Token::Number(10.25)),
-            Some(Token::Number(5.0)),
-            Some(Token::Number(0.0)),
+            Some(Token::float(10.25)),
+            Some(Token::integer(5)),
+            Some(Token::integer(0)),
         ];
 
         for (i, mut number_chars) in numbers_chars.into_iter().enumerate() {
             // Act
-            let parsed = parse_number(&mut number_chars);
+            let parsed = parse_number(&mut number_chars, &TokenizerOptions::default());
 
             // Assert
             assert_eq!(
@@ -160,14 +794,526 @@ mod tokenizer_helpers_tests {
         }
     }
 
+    #[test]
+    fn test_parse_number_with_leading_dot() {
+        // Arrange
+        let numbers_chars = vec![Cursor::new(".5"), Cursor::new(".25e2")];
+        let invalid_numbers_chars = vec![Cursor::new("."), Cursor::new("..5"), Cursor::new(". 5")];
+
+        // `expected_numbers_tokens` slice is based on the `numbers_chars` input.
+        // Any change on some of them should be reflected in the other in order to keep sync
+        // the input and the expected set of tokens
+        let expected_numbers_tokens = &[Some(Token::float(0.5)), Some(Token::float(25.0))];
+
+        for (i, mut number_chars) in numbers_chars.into_iter().enumerate() {
+            // Act
+            let parsed = parse_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert_eq!(
+                expected_numbers_tokens[i],
+                parsed.unwrap(),
+                "should parse a leading dot followed by digits as a decimal number"
+            )
+        }
+
+        for mut number_chars in invalid_numbers_chars {
+            // Act
+            let result = parse_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert!(
+                result.is_err(),
+                "should return error if leading dot is not followed by digits"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_number_with_trailing_dot() {
+        // Arrange
+        let mut number_chars = Cursor::new("5.+1");
+        let mut invalid_number_chars = Cursor::new("5..");
+
+        // Act
+        let parsed = parse_number(&mut number_chars, &TokenizerOptions::default());
+        let result = parse_number(&mut invalid_number_chars, &TokenizerOptions::default());
+
+        // Assert
+        assert_eq!(
+            parsed.unwrap(),
+            Some(Token::float(5.0)),
+            "should accept a trailing dot as a number without decimals"
+        );
+
+        assert_eq!(
+            number_chars.remaining(),
+            "+1",
+            "should stop parsing number right after the trailing dot"
+        );
+
+        assert!(
+            result.is_err(),
+            "should return error if trailing dot is followed by another dot"
+        )
+    }
+
+    #[test]
+    fn test_parse_number_with_decimal_separator() {
+        // Arrange
+        let dot_options = TokenizerOptions::default();
+        let comma_options = TokenizerOptions {
+            decimal_separator: DecimalSeparator::Comma,
+            ..Default::default()
+        };
+
+        // Each source is paired with its expected result in dot and comma mode.
+        // Notice the number ends before the character which is not the decimal mark
+        let cases = [
+            ("2.75", Ok(Token::float(2.75)), Err(())),
+            ("2,75", Ok(Token::integer(2)), Ok(Token::float(2.75))),
+            (".5", Ok(Token::float(0.5)), Err(())),
+            (",5", Err(()), Ok(Token::float(0.5))),
+            (
+                "1_000,25e1",
+                Ok(Token::integer(1000)),
+                Ok(Token::float(10002.5)),
+            ),
+        ];
+
+        for (source, expected_dot, expected_comma) in cases {
+            for (options, expected) in [
+                (&dot_options, expected_dot),
+                (&comma_options, expected_comma),
+            ] {
+                // Act
+                let parsed = parse_number(&mut Cursor::new(source), options);
+
+                // Assert
+                assert_eq!(
+                    parsed.map(|token| token.unwrap()).map_err(|_| ()),
+                    expected,
+                    "should parse numbers using the decimal mark of the given options"
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_number_with_exponent_success() {
+        // Arrange
+        let numbers_chars = vec![
+            Cursor::new("6.02e23"),
+            Cursor::new("1.5E-3"),
+            Cursor::new("1e+5"),
+            Cursor::new("1e-5"),
+            Cursor::new("2E3"),
+        ];
+
+        // `expected_numbers_tokens` slice is based on the `numbers_chars` input.
+        // Any change on some of them should be reflected in the other in order to keep sync
+        // the input and the expected set of tokens
+        let expected_numbers_tokens = &[
+            Some(Token::float(6.02e23)),
+            Some(Token::float(1.5e-3)),
+            Some(Token::float(1e5)),
+            Some(Token::float(1e-5)),
+            Some(Token::float(2e3)),
+        ];
+
+        for (i, mut number_chars) in numbers_chars.into_iter().enumerate() {
+            // Act
+            let parsed = parse_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert_eq!(
+                expected_numbers_tokens[i],
+                parsed.unwrap(),
+                "should take given stream of characters and parse it as number token with exponent"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_number_with_exponent_fail() {
+        // Arrange
+        let invalid_numbers_chars = vec![
+            Cursor::new("1e"),
+            Cursor::new("1e+"),
+            Cursor::new("e5"),
+            Cursor::new("2E-x"),
+        ];
+
+        for mut number_chars in invalid_numbers_chars {
+            // Act
+            let result = parse_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert!(
+                result.is_err(),
+                "should return error if exponent part is malformed or there is no mantissa"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_number_before_word() {
+        // Arrange
+        // Each source is paired with its expected number and the source left after it
+        let cases = [
+            ("2exp(1)", Token::integer(2), "exp(1)"),
+            ("3Ex", Token::integer(3), "Ex"),
+            ("1.5exp(2)", Token::float(1.5), "exp(2)"),
+        ];
+
+        for (source, expected_number, expected_rest) in cases {
+            let mut cursor = Cursor::new(source);
+
+            // Act
+            let parsed = parse_number(&mut cursor, &TokenizerOptions::default());
+
+            // Assert
+            assert_eq!(
+                (parsed, cursor.remaining()),
+                (Ok(Some(expected_number)), expected_rest),
+                "should end the number before an exponent marker starting a word"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_number_with_separators_success() {
+        // Arrange
+        let numbers_chars = vec![
+            Cursor::new("1_000_000"),
+            Cursor::new("1_000_000.5"),
+            Cursor::new("0.000_1"),
+        ];
+
+        // `expected_numbers_tokens` slice is based on the `numbers_chars` input.
+        // Any change on some of them should be reflected in the other in order to keep sync
+        // the input and the expected set of tokens
+        let expected_numbers_tokens = &[
+            Some(Token::integer(1000000)),
+            Some(Token::float(1000000.5)),
+            Some(Token::float(0.0001)),
+        ];
+
+        for (i, mut number_chars) in numbers_chars.into_iter().enumerate() {
+            // Act
+            let parsed = parse_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert_eq!(
+                expected_numbers_tokens[i],
+                parsed.unwrap(),
+                "should skip underscores placed between digits while parsing number token"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_number_with_separators_fail() {
+        // Arrange
+        let invalid_numbers_chars = vec![
+            Cursor::new("_1"),
+            Cursor::new("1_.5"),
+            Cursor::new("1._5"),
+            Cursor::new("1__0"),
+            Cursor::new("1_"),
+        ];
+
+        for mut number_chars in invalid_numbers_chars {
+            // Act
+            let result = parse_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert!(
+                matches!(
+                    result,
+                    Err(TokenizerErrorKind::InvalidNumber {
+                        reason: InvalidNumberReason::MisplacedSeparator,
+                        ..
+                    }) | Err(TokenizerErrorKind::UnexpectedCharacter('_'))
+                ),
+                "should return separator error if underscores are not placed between digits"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_number_large_generated_input() {
+        // Arrange
+        // Numbers are generated from a fixed seed, each written with and without separators and with both decimal
+        // marks, so every way of parsing a number is compared against the plain way of writing it
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move |max: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % max
+        };
+
+        for _ in 0..20_000 {
+            // Up to 24 digits, so integers which don't fit in `i64` are generated too
+            let integer_part = (0..1 + next(24))
+                .map(|_| char::from(b'0' + next(10) as u8))
+                .collect::<String>();
+            let fraction_part = next(1_000_000).to_string();
+            let exponent = next(40) as i64 - 20;
+
+            let plain = match next(4) {
+                0 => integer_part.clone(),
+                1 => format!("{integer_part}.{fraction_part}"),
+                2 => format!("{integer_part}e{exponent}"),
+                _ => format!("{integer_part}.{fraction_part}E{exponent}"),
+            };
+            let separated = integer_part
+                .chars()
+                .enumerate()
+                .flat_map(|(i, c)| (i > 0 && i % 3 == 0).then_some('_').into_iter().chain([c]))
+                .collect::<String>()
+                + &plain[integer_part.len()..];
+            let comma = separated.replace('.', ",");
+
+            let expected_integer = plain.parse::<i64>().ok();
+            let expected_float = plain.parse::<f64>().unwrap();
+
+            let comma_options = TokenizerOptions {
+                decimal_separator: DecimalSeparator::Comma,
+                ..Default::default()
+            };
+            let cases = [
+                (&plain, TokenizerOptions::default()),
+                (&separated, TokenizerOptions::default()),
+                (&comma, comma_options),
+            ];
+
+            for (source, options) in cases {
+                let mut number_chars = Cursor::new(source);
+
+                // Act
+                let parsed = parse_number(&mut number_chars, &options).unwrap().unwrap();
+
+                // Assert
+                let expected = match expected_integer {
+                    Some(value) => Token::integer(value),
+                    None => Token::float(expected_float),
+                };
+
+                assert_eq!(
+                    parsed, expected,
+                    "should parse numbers the same regardless of separators and decimal mark"
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_hex_number_success() {
+        // Arrange
+        let numbers_chars = vec![
+            Cursor::new("0xFF"),
+            Cursor::new("0x10"),
+            Cursor::new("0Xab"),
+            Cursor::new("0x0"),
+        ];
+
+        // `expected_numbers_tokens` slice is based on the `numbers_chars` input.
+        // Any change on some of them should be reflected in the other in order to keep sync
+        // the input and the expected set of tokens
+        let expected_numbers_tokens = &[
+            Some(Token::integer(255)),
+            Some(Token::integer(16)),
+            Some(Token::integer(171)),
+            Some(Token::integer(0)),
+        ];
+
+        for (i, mut number_chars) in numbers_chars.into_iter().enumerate() {
+            // Act
+            let parsed = parse_hex_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert_eq!(
+                expected_numbers_tokens[i],
+                parsed.unwrap(),
+                "should take given stream of characters and parse it as hexadecimal number token"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_hex_number_fail() {
+        // Arrange
+        let invalid_numbers_chars =
+            vec![Cursor::new("0x"), Cursor::new("0xG1"), Cursor::new("0x+1")];
+        let mut non_hex_chars = Cursor::new("10");
+
+        for mut number_chars in invalid_numbers_chars {
+            // Act
+            let result = parse_hex_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert!(
+                result.is_err(),
+                "should return error if hexadecimal number has no digits or invalid ones"
+            )
+        }
+
+        assert!(
+            parse_hex_number(&mut non_hex_chars, &TokenizerOptions::default()).is_err(),
+            "should return error if number has no hexadecimal prefix"
+        );
+
+        assert_eq!(
+            non_hex_chars.count(),
+            2,
+            "should not consume characters if number has no hexadecimal prefix"
+        )
+    }
+
+    #[test]
+    fn test_parse_binary_number_success() {
+        // Arrange
+        let numbers_chars = vec![
+            Cursor::new("0b1010"),
+            Cursor::new("0B1"),
+            Cursor::new("0b0"),
+        ];
+
+        // `expected_numbers_tokens` slice is based on the `numbers_chars` input.
+        // Any change on some of them should be reflected in the other in order to keep sync
+        // the input and the expected set of tokens
+        let expected_numbers_tokens = &[
+            Some(Token::integer(10)),
+            Some(Token::integer(1)),
+            Some(Token::integer(0)),
+        ];
+
+        for (i, mut number_chars) in numbers_chars.into_iter().enumerate() {
+            // Act
+            let parsed = parse_binary_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert_eq!(
+                expected_numbers_tokens[i],
+                parsed.unwrap(),
+                "should take given stream of characters and parse it as binary number token"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_binary_number_fail() {
+        // Arrange
+        let invalid_numbers_chars =
+            vec![Cursor::new("0b"), Cursor::new("0b102"), Cursor::new("0bx")];
+
+        for mut number_chars in invalid_numbers_chars {
+            // Act
+            let result = parse_binary_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert!(
+                result.is_err(),
+                "should return error if binary number has no digits or invalid ones"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_octal_number_success() {
+        // Arrange
+        let numbers_chars = vec![
+            Cursor::new("0o755"),
+            Cursor::new("0O17"),
+            Cursor::new("0o0"),
+        ];
+
+        // `expected_numbers_tokens` slice is based on the `numbers_chars` input.
+        // Any change on some of them should be reflected in the other in order to keep sync
+        // the input and the expected set of tokens
+        let expected_numbers_tokens = &[
+            Some(Token::integer(493)),
+            Some(Token::integer(15)),
+            Some(Token::integer(0)),
+        ];
+
+        for (i, mut number_chars) in numbers_chars.into_iter().enumerate() {
+            // Act
+            let parsed = parse_octal_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert_eq!(
+                expected_numbers_tokens[i],
+                parsed.unwrap(),
+                "should take given stream of characters and parse it as octal number token"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_octal_number_fail() {
+        // Arrange
+        let invalid_numbers_chars =
+            vec![Cursor::new("0o"), Cursor::new("0o8"), Cursor::new("0o79")];
+
+        for mut number_chars in invalid_numbers_chars {
+            // Act
+            let result = parse_octal_number(&mut number_chars, &TokenizerOptions::default());
+
+            // Assert
+            assert!(
+                result.is_err(),
+                "should return error if octal number has no digits or invalid ones"
+            )
+        }
+    }
+
+    #[test]
+    fn test_parse_number_non_ascii_digits() {
+        // Arrange
+        let mut number_chars = Cursor::new("1٣");
+        let mut non_ascii_chars = Cursor::new("٣");
+
+        // Act
+        let parsed = parse_number(&mut number_chars, &TokenizerOptions::default());
+        let result = parse_number(&mut non_ascii_chars, &TokenizerOptions::default());
+
+        // Assert
+        assert_eq!(
+            parsed.unwrap(),
+            Some(Token::integer(1)),
+            "should end number at the first non ASCII digit"
+        );
+
+        assert!(
+            result.is_err(),
+            "should not match non ASCII digits as number"
+        );
+
+        assert_eq!(
+            non_ascii_chars.remaining(),
+            "٣",
+            "should not consume non ASCII digits"
+        )
+    }
+
     #[test]
     fn test_parse_number_fail() {
         // Arrange
-        let invalid_numbers_chars = vec!["not a number".chars(), "3.20.49.9".chars()];
+        let invalid_numbers_chars = vec![
+            Cursor::new("not a number"),
+            Cursor::new("3.20.49.9"),
+            Cursor::new(""),
+            Cursor::new("."),
+            Cursor::new("٣"),
+            Cursor::new("३"),
+        ];
 
         for mut number_chars in invalid_numbers_chars {
             // Act
-            let result = parse_number(&mut number_chars);
+            let result = parse_number(&mut number_chars, &TokenizerOptions::default());
 
             // Assert
             assert!(
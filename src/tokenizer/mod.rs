@@ -1,3 +1,8 @@
+pub mod builder;
+mod cursor;
 mod helpers;
+pub mod options;
 pub mod parser;
+pub mod stream;
+pub mod symbol;
 pub mod tokens;
@@ -0,0 +1,88 @@
+use super::tokens::Operator;
+
+/// Character used as decimal mark inside numbers.
+///
+/// Since `,` is the arguments separator by default, in `Comma` mode arguments are separated by `;` instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecimalSeparator {
+    #[default]
+    Dot,
+    Comma,
+}
+
+impl DecimalSeparator {
+    /// Character used as decimal mark inside numbers.
+    pub fn decimal_mark(&self) -> char {
+        match self {
+            DecimalSeparator::Dot => '.',
+            DecimalSeparator::Comma => ',',
+        }
+    }
+
+    /// Character used for separating arguments, which is tokenized as `Token::Comma`.
+    pub fn argument_separator(&self) -> char {
+        match self {
+            DecimalSeparator::Dot => ',',
+            DecimalSeparator::Comma => ';',
+        }
+    }
+}
+
+/// Settings changing how a `Tokenizer` scans its source. `Default` holds the settings used by `Tokenizer::new`.
+#[derive(Debug, Clone)]
+pub struct TokenizerOptions {
+    pub decimal_separator: DecimalSeparator,
+    /// Operators which are not recognized from their symbols. Aliases are still recognized.
+    pub disabled_operators: Vec<Operator>,
+    /// Extra characters recognized as operators.
+    pub operator_aliases: Vec<(char, Operator)>,
+    /// Characters recognized as custom operators, whose meaning is given by the parser.
+    pub custom_operators: Vec<char>,
+    /// Whether `#` starts a comment.
+    pub comments: bool,
+    /// Whether `_` is allowed as digit separator inside numbers.
+    pub digit_separators: bool,
+    /// Whether whitespace and comments are produced as tokens instead of being skipped.
+    pub preserve_trivia: bool,
+    /// Maximum bytes of the whole source.
+    pub max_source_length: usize,
+    /// Maximum bytes of a single number literal.
+    pub max_number_length: usize,
+    /// Maximum tokens produced from the whole source.
+    pub max_tokens: usize,
+}
+
+impl TokenizerOptions {
+    pub fn is_operator_enabled(&self, operator: Operator) -> bool {
+        !self.disabled_operators.contains(&operator)
+    }
+
+    /// Operator registered as alias of the given character, if any.
+    pub fn operator_alias(&self, c: char) -> Option<Operator> {
+        self.operator_aliases
+            .iter()
+            .find(|(alias, _)| *alias == c)
+            .map(|(_, operator)| *operator)
+    }
+
+    pub fn is_custom_operator(&self, c: char) -> bool {
+        self.custom_operators.contains(&c)
+    }
+}
+
+impl Default for TokenizerOptions {
+    fn default() -> Self {
+        Self {
+            decimal_separator: DecimalSeparator::default(),
+            disabled_operators: Vec::new(),
+            operator_aliases: Vec::new(),
+            custom_operators: Vec::new(),
+            comments: true,
+            digit_separators: true,
+            preserve_trivia: false,
+            max_source_length: 64 * 1024 * 1024,
+            max_number_length: 1024,
+            max_tokens: 10_000_000,
+        }
+    }
+}
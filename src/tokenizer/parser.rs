@@ -1,98 +1,529 @@
+#![allow(dead_code)]
+
 use core::fmt;
-use std::{error::Error, str::Chars};
+use std::{error::Error, io, mem};
+
+use crate::tokenizer::{
+    cursor::Cursor,
+    helpers::{
+        parse_binary_number, parse_comment, parse_hex_number, parse_number, parse_octal_number,
+        parse_operator, parse_percent_suffix, parse_punctuation, parse_unit_suffix,
+        parse_whitespace, parse_word, ParseFn,
+    },
+    options::TokenizerOptions,
+};
+
+use super::tokens::{Operator, Position, Span, Spanned, Token};
+
+/// Reason why a number literal is malformed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvalidNumberReason {
+    /// `_` separator not placed between two digits.
+    MisplacedSeparator,
+    /// Decimal mark found more than once.
+    RepeatedDecimalMark,
+    /// `.` found in a number while decimal mark is `,`.
+    MisplacedDot,
+    /// Prefix like `0x` not followed by any digit.
+    MissingDigits,
+    /// Digits not valid for the radix of the number prefix.
+    InvalidDigits { radix: u32 },
+    /// Number matches the syntax but cannot be converted.
+    Unparseable,
+}
+
+impl fmt::Display for InvalidNumberReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MisplacedSeparator => write!(f, "`_` separator is only allowed between digits"),
+            Self::RepeatedDecimalMark => write!(f, "decimal mark is repeated"),
+            Self::MisplacedDot => write!(f, "unexpected `.`, decimal separator is `,`"),
+            Self::MissingDigits => write!(f, "missing digits after number prefix"),
+            Self::InvalidDigits { radix } => write!(f, "invalid digits for base {radix}"),
+            Self::Unparseable => write!(f, "cannot be parsed"),
+        }
+    }
+}
+
+/// Limit of the tokenizer which can be exceeded by a source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+    /// Bytes of the whole source.
+    SourceLength,
+    /// Bytes of a single number literal.
+    NumberLength,
+    /// Tokens produced from the whole source.
+    TokenCount,
+    /// Bytes of all the different names interned by the program, which are never freed, as told by
+    /// `MAX_INTERNED_BYTES`.
+    InternedNames,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SourceLength => write!(f, "source length"),
+            Self::NumberLength => write!(f, "number length"),
+            Self::TokenCount => write!(f, "token count"),
+            Self::InternedNames => write!(f, "length of all names"),
+        }
+    }
+}
 
-use crate::tokenizer::helpers::{parse_number, parse_operator};
+/// Kind of error found while tokenizing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenizerErrorKind {
+    /// Character which doesn't start any token.
+    UnexpectedCharacter(char),
+    /// Source ended while a token was expected.
+    UnexpectedEndOfInput,
+    /// Number literal which matches number syntax but is malformed.
+    InvalidNumber {
+        lexeme: String,
+        reason: InvalidNumberReason,
+    },
+    /// Exponent marker not followed by any digit, like `1e` or `1e+`.
+    UnterminatedExponent,
+    /// Source is not valid UTF-8.
+    InvalidUtf8,
+    /// Source could not be read.
+    ReadFailed(io::ErrorKind),
+    /// Source exceeds one of the limits set in the options.
+    LimitExceeded { limit: Limit, max: usize },
+}
 
-use super::tokens::Token;
+impl fmt::Display for TokenizerErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedCharacter(c) => write!(f, "unexpected character '{c}'"),
+            Self::UnexpectedEndOfInput => write!(f, "unexpected end of input"),
+            Self::InvalidNumber { lexeme, reason } => {
+                write!(f, "invalid number '{lexeme}': {reason}")
+            }
+            Self::UnterminatedExponent => write!(f, "missing digits in number exponent"),
+            Self::InvalidUtf8 => write!(f, "invalid UTF-8 sequence in source"),
+            Self::ReadFailed(kind) => write!(f, "cannot read source: {kind}"),
+            Self::LimitExceeded { limit, max } => write!(f, "{limit} exceeds the limit of {max}"),
+        }
+    }
+}
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TokenizerError {
-    message: &'static str,
+    kind: TokenizerErrorKind,
+    position: Position,
+    offset: usize,
 }
 
 impl TokenizerError {
-    pub fn new(message: &'static str) -> Self {
-        Self { message }
+    pub fn new(kind: TokenizerErrorKind, position: Position, offset: usize) -> Self {
+        Self {
+            kind,
+            position,
+            offset,
+        }
+    }
+
+    /// Kind of the error, with the details of what was found.
+    pub fn kind(&self) -> &TokenizerErrorKind {
+        &self.kind
+    }
+
+    /// Position in the source where the failing token starts.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Byte offset in the source where the failing token starts.
+    pub fn offset(&self) -> usize {
+        self.offset
     }
 }
 
 impl fmt::Display for TokenizerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[TOKENIZER ERROR]: {}", self.message)
+        write!(
+            f,
+            "[TOKENIZER ERROR] at {} (offset {}): {}",
+            self.position, self.offset, self.kind
+        )
     }
 }
 
 impl Error for TokenizerError {}
 
-#[derive(Debug)]
+/// Tells if a source is a whole expression, needs more input to be one, or can't be one at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Completeness {
+    Complete,
+    /// Source ends in the middle of an expression, like after a binary operator or with unclosed parens.
+    Incomplete,
+    /// Source contains tokenizer errors or closes parens which were never opened.
+    Invalid,
+}
+
+/// Progress of a tokenizer over the whole source, so a tokenizer over a fragment of it can continue where
+/// the previous one stopped.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct Checkpoint {
+    pub position: Position,
+    pub offset: usize,
+    /// Whether the fragment comes right after a number token.
+    pub after_number: bool,
+    /// Tokens produced before the fragment.
+    pub tokens: usize,
+}
+
+/// Converts a source into a stream of tokens.
+///
+/// Tokens are produced lazily through the `Iterator` implementation, which yields the tokens as characters
+/// are consumed and stops right after yielding the first error.
+#[derive(Debug, Clone)]
 pub struct Tokenizer<'a> {
-    chars: Chars<'a>,
-    pub tokens: Vec<Token>,
+    cursor: Cursor<'a>,
+    options: TokenizerOptions,
+    position: Position,
+    offset: usize,
+    after_number: bool,
+    tokens: usize,
+    failed: bool,
 }
 
 impl<'a> Tokenizer<'a> {
     pub fn new(source: &'a str) -> Self {
+        Self::with_options(source, TokenizerOptions::default())
+    }
+
+    pub fn with_options(source: &'a str, options: TokenizerOptions) -> Self {
+        Self::resume(source, options, Checkpoint::default())
+    }
+
+    /// Creates a tokenizer for a source which is a fragment of a bigger one, starting at the given `checkpoint`
+    /// of the whole source, so positions, spans and limits are relative to the whole source.
+    pub(super) fn resume(
+        source: &'a str,
+        options: TokenizerOptions,
+        checkpoint: Checkpoint,
+    ) -> Self {
         Self {
-            chars: source.chars(),
-            tokens: Vec::new(),
+            cursor: Cursor::new(source),
+            options,
+            position: checkpoint.position,
+            offset: checkpoint.offset,
+            after_number: checkpoint.after_number,
+            tokens: checkpoint.tokens,
+            failed: false,
         }
     }
 
+    /// Position of the next character to be consumed.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Progress over the whole source, to resume tokenizing from the next character.
+    pub(super) fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            position: self.position,
+            offset: self.offset + self.cursor.offset(),
+            after_number: self.after_number,
+            tokens: self.tokens,
+        }
+    }
+
+    /// Part of the source not consumed yet.
+    pub(super) fn remaining(&self) -> &'a str {
+        self.cursor.remaining()
+    }
+
+    /// Checks if all characters were consumed. It takes constant time, so it is safe to call it once per token.
     fn is_end(&self) -> bool {
-        self.chars.clone().count() == 0
+        self.cursor.is_end()
+    }
+
+    /// Checks the piece of the source scanned at `span` doesn't exceed the limits of the options, counting it
+    /// if it is a token.
+    fn check_limits(&mut self, result: &Option<Token>, span: Span) -> Result<(), TokenizerError> {
+        let exceeded = if span.end > self.options.max_source_length {
+            Some((Limit::SourceLength, self.options.max_source_length))
+        } else if result.is_some() && self.tokens >= self.options.max_tokens {
+            Some((Limit::TokenCount, self.options.max_tokens))
+        } else {
+            None
+        };
+
+        match exceeded {
+            Some((limit, max)) => Err(TokenizerError::new(
+                TokenizerErrorKind::LimitExceeded { limit, max },
+                self.position,
+                span.start,
+            )),
+            None => {
+                self.tokens += usize::from(result.is_some());
+                Ok(())
+            }
+        }
     }
 
-    fn scan_token(&mut self) -> Result<(), TokenizerError> {
-        let parsers = [parse_number, parse_operator];
+    /// Scans the next piece of the source, returning `None` if it is not meaningful as token (like whitespace).
+    fn scan_token(&mut self) -> Result<Option<Spanned<Token>>, TokenizerError> {
+        // Prefixed numbers must be tried before regular numbers, otherwise their leading `0` is taken as a number
+        let parsers: [ParseFn; 9] = [
+            parse_whitespace,
+            parse_comment,
+            parse_hex_number,
+            parse_binary_number,
+            parse_octal_number,
+            parse_number,
+            parse_word,
+            parse_operator,
+            parse_punctuation,
+        ];
+
+        // A `%` right after a number, without whitespace in between, is a percent suffix rather than modulo, and
+        // a `deg` or `rad` is an angle unit rather than an identifier
+        let suffix_parsers = if mem::take(&mut self.after_number) {
+            &[parse_percent_suffix as ParseFn, parse_unit_suffix][..]
+        } else {
+            &[]
+        };
+
+        let start = self.cursor.offset();
+        let remaining = self.cursor.remaining();
+
+        for p in suffix_parsers.iter().chain(&parsers) {
+            match p(&mut self.cursor, &self.options) {
+                // Check if token parsing was successful
+                Ok(result) => {
+                    let consumed = self.cursor.offset() - start;
+                    let span = Span::new(self.offset + start, self.offset + start + consumed);
+
+                    self.check_limits(&result, span)?;
+
+                    let position = self.position;
+
+                    self.after_number = matches!(
+                        result,
+                        Some(Token::Integer { .. })
+                            | Some(Token::Float { .. })
+                            | Some(Token::Operator(Operator::PercentSuffix))
+                    );
+
+                    // Keeps position in sync with the characters consumed by the parser
+                    self.position.advance(&remaining[..consumed]);
+
+                    // If result returns a token, pair it with its span
+                    return Ok(result.map(|token| Spanned::new(token, span, position)));
+                }
+                // If parser consumed some characters before failing, the input matched its syntax but it is malformed,
+                // so its error is reported instead of trying with the next parser
+                Err(kind) if self.cursor.offset() != start => {
+                    return Err(TokenizerError::new(
+                        kind,
+                        self.position,
+                        self.offset + start,
+                    ));
+                }
+                Err(_) => {}
+            }
+        }
+
+        let unexpected = remaining.chars().next().map_or(
+            TokenizerErrorKind::UnexpectedEndOfInput,
+            TokenizerErrorKind::UnexpectedCharacter,
+        );
+
+        Err(TokenizerError::new(
+            unexpected,
+            self.position,
+            self.offset + start,
+        ))
+    }
 
-        for p in parsers {
-            // Check if token parsing was successful
-            if let Ok(result) = p(&mut self.chars) {
-                // If result returns a token, push it in the `Tokens` register
-                if let Some(token) = result {
-                    self.tokens.push(token);
+    /// Yields the next token paired with the span of the source it was built from.
+    pub fn next_spanned(&mut self) -> Option<Result<Spanned<Token>, TokenizerError>> {
+        while !self.failed && !self.is_end() {
+            match self.scan_token() {
+                Ok(Some(token)) => return Some(Ok(token)),
+                Ok(None) => continue,
+                Err(err) => {
+                    // Once an error is found, iteration stops
+                    self.failed = true;
+                    return Some(Err(err));
                 }
+            }
+        }
+
+        None
+    }
+
+    /// Consumes the remaining source and returns all its tokens ending with `Token::Eof`, or the first error found.
+    pub fn tokenize(&mut self) -> Result<Vec<Token>, TokenizerError> {
+        let tokens = self.tokenize_spanned()?;
+
+        Ok(tokens.into_iter().map(|token| token.value).collect())
+    }
+
+    /// Same as `tokenize` but pairing every token with the span of the source it was built from.
+    pub fn tokenize_spanned(&mut self) -> Result<Vec<Spanned<Token>>, TokenizerError> {
+        let mut tokens =
+            std::iter::from_fn(|| self.next_spanned()).collect::<Result<Vec<_>, _>>()?;
+        tokens.push(self.eof());
+
+        Ok(tokens)
+    }
+
+    /// Builds the `Token::Eof` closing the tokens, with an empty span at the end of the source.
+    ///
+    /// Iteration yields the tokens only, so it is up to the methods collecting them to append it.
+    pub(super) fn eof(&self) -> Spanned<Token> {
+        let offset = self.checkpoint().offset;
 
-                // Since parse was successful, early returns breaking for loop and avoiding below `panic!`
-                return Ok(());
+        Spanned::new(
+            Token::Eof(self.position),
+            Span::new(offset, offset),
+            self.position,
+        )
+    }
+
+    /// Checks if the given source is complete, so an interactive entry knows if it should ask for more lines
+    /// instead of reporting an error.
+    ///
+    /// It is a lightweight scan of the tokens, only counting the parens depth and checking the last token, so
+    /// complete sources can still fail while parsing.
+    pub fn completeness(source: &str) -> Completeness {
+        let mut depth = 0usize;
+        let mut last_token = None;
+
+        for token in Tokenizer::new(source) {
+            match token {
+                Ok(Token::LeftParen) => depth += 1,
+                Ok(Token::RightParen) if depth == 0 => return Completeness::Invalid,
+                Ok(Token::RightParen) => depth -= 1,
+                Ok(_) => {}
+                Err(_) => return Completeness::Invalid,
             }
+
+            last_token = token.ok();
         }
 
-        Err(TokenizerError::new("unexpected token"))
+        // Every operator but the postfix ones expects an operand after it, as well as the arguments separator, the
+        // assignment and the conditional punctuation
+        let expects_operand = match last_token {
+            Some(Token::Operator(
+                Operator::Bang | Operator::PercentSuffix | Operator::Degrees | Operator::Radians,
+            )) => false,
+            Some(
+                Token::Operator(_)
+                | Token::CustomOperator(_)
+                | Token::Comma
+                | Token::Equals
+                | Token::Question
+                | Token::Colon,
+            ) => true,
+            _ => false,
+        };
+
+        if depth > 0 || expects_operand {
+            Completeness::Incomplete
+        } else {
+            Completeness::Complete
+        }
     }
 
-    pub fn tokenize(&mut self) -> Result<(), TokenizerError> {
+    /// Consumes the remaining source and returns all its tokens, or every error found.
+    ///
+    /// Unlike `tokenize`, errors don't stop tokenizing: the characters of the malformed token (or the offending
+    /// character) are skipped and tokenizing continues right after them.
+    pub fn tokenize_all(&mut self) -> Result<Vec<Token>, Vec<TokenizerError>> {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+
         while !self.is_end() {
-            if let Err(err) = self.scan_token() {
-                return Err(err);
+            let start = self.cursor.offset();
+            let remaining = self.cursor.remaining();
+
+            match self.scan_token() {
+                Ok(Some(token)) => tokens.push(token.value),
+                Ok(None) => {}
+                // Once a limit is exceeded, the rest of the source is not tokenized
+                Err(
+                    err @ TokenizerError {
+                        kind: TokenizerErrorKind::LimitExceeded { .. },
+                        ..
+                    },
+                ) => {
+                    errors.push(err);
+                    break;
+                }
+                Err(err) => {
+                    errors.push(err);
+
+                    // Unexpected characters are not consumed by any parser, so they are skipped here
+                    if self.cursor.offset() == start {
+                        self.cursor.next();
+                    }
+
+                    self.position
+                        .advance(&remaining[..self.cursor.offset() - start]);
+                }
             }
         }
 
-        return Ok(());
+        if errors.is_empty() {
+            tokens.push(self.eof().value);
+            Ok(tokens)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Iterator for Tokenizer<'_> {
+    type Item = Result<Token, TokenizerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_spanned()
+            .map(|result| result.map(|token| token.value))
     }
 }
 
 #[cfg(test)]
 mod tokenizer_parser_tests {
-    use crate::tokenizer::tokens::{Operator, Token};
+    use crate::tokenizer::{
+        options::{DecimalSeparator, TokenizerOptions},
+        symbol::Symbol,
+        tokens::{Constant, Operator, Position, Span, Spanned, Token},
+    };
 
-    use super::Tokenizer;
+    use super::{Completeness, InvalidNumberReason, Tokenizer, TokenizerErrorKind};
 
     const SOURCE: &str = "3 + 4.33 / 5";
 
+    /// Checks the tokens end with `Token::Eof`, returning the ones before it.
+    fn without_eof(mut tokens: Vec<Token>) -> Vec<Token> {
+        assert!(
+            matches!(tokens.pop(), Some(Token::Eof(_))),
+            "should end tokens with the end of input"
+        );
+
+        tokens
+    }
+
     #[test]
     fn test_new_tokenizer() {
         // Act
         let tokenizer = Tokenizer::new(SOURCE);
 
         // Assert
-        assert!(
-            tokenizer.tokens.is_empty(),
-            "tokenizer should be instantiated with an empty array of tokens"
+        assert_eq!(
+            tokenizer.position(),
+            Position::default(),
+            "tokenizer should be instantiated at the start of the source"
         );
 
         assert_eq!(
-            tokenizer.chars.count(),
+            tokenizer.cursor.count(),
             SOURCE.chars().count(),
             "tokenizer should be instantiated with an stream of characters based on the source"
         )
@@ -125,39 +556,1552 @@ mod tokenizer_parser_tests {
         // Any change on some of them should be reflected in the other in order to keep sync
         // the input and the expected set of tokens
         let expected_tokens = vec![
-            Token::Number(3.0),
+            Token::integer(3),
             Token::Operator(Operator::Plus),
-            Token::Number(4.33),
+            Token::float(4.33),
             Token::Operator(Operator::Slash),
-            Token::Number(5.0),
+            Token::integer(5),
+            Token::Eof(Position::new(1, 13)),
         ];
 
         // Act
-        tokenizer.tokenize().unwrap();
+        let tokens = tokenizer.tokenize().unwrap();
 
         // Assert
         assert_eq!(
-            tokenizer.tokens, expected_tokens,
+            tokens, expected_tokens,
             "should take source characters stream and convert it into a stream of tokens"
         );
 
         assert_eq!(
-            tokenizer.chars.count(),
+            tokenizer.cursor.count(),
             0,
             "once all characters are converte into tokens, iterator should consume items and should be empty"
         )
     }
 
     #[test]
-    fn test_tokenize_fails() {
+    fn test_iterator() {
         // Arrange
-        const INVALID_SOURCE: &str = "invalid source";
-        let mut tokenizer = Tokenizer::new(INVALID_SOURCE);
+        let mut tokenizer = Tokenizer::new(SOURCE);
+
+        // Act & Assert
+        assert_eq!(
+            tokenizer.next().unwrap().unwrap(),
+            Token::integer(3),
+            "should yield the first token of the source"
+        );
+
+        assert_eq!(
+            tokenizer.cursor.remaining(),
+            " + 4.33 / 5",
+            "should consume only the characters of the yielded token"
+        );
+
+        assert_eq!(
+            tokenizer.count(),
+            4,
+            "should yield the remaining tokens of the source"
+        )
+    }
+
+    #[test]
+    fn test_iterator_stops_at_first_error() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("1 + $ 2 ~");
 
         // Act & Assert
+        assert_eq!(
+            tokenizer.next().unwrap().unwrap(),
+            Token::integer(1),
+            "should yield tokens found before the error"
+        );
+
+        assert_eq!(
+            tokenizer.next().unwrap().unwrap(),
+            Token::Operator(Operator::Plus),
+            "should yield tokens found before the error"
+        );
+
+        assert_eq!(
+            tokenizer.next().unwrap().unwrap_err().position(),
+            Position::new(1, 5),
+            "should yield the first error found"
+        );
+
         assert!(
-            tokenizer.tokenize().is_err(),
-            "should return error if given source cannot be parsed"
+            tokenizer.next().is_none(),
+            "should stop iteration once an error was yielded"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_large_source() {
+        // Arrange
+        // Around 1MB of source. Since peeking characters and checking the end of the source don't walk
+        // the remaining characters, tokenizing it takes linear time
+        const TERMS: usize = 500_000;
+        let source = format!("{}1", "1+".repeat(TERMS));
+        let mut tokenizer = Tokenizer::new(&source);
+
+        // Act
+        let tokens = tokenizer.tokenize().unwrap();
+
+        // Assert
+        assert_eq!(
+            tokens.len(),
+            TERMS * 2 + 2,
+            "should tokenize every number and operator of the source, followed by the end of input"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_parens_success() {
+        // Arrange
+        // Each source is paired with its expected tokens. Notice unbalanced parens are still
+        // tokenized, since checking their balance is a responsibility of the parser
+        let cases = vec![
+            (
+                "((2))",
+                vec![
+                    Token::LeftParen,
+                    Token::LeftParen,
+                    Token::integer(2),
+                    Token::RightParen,
+                    Token::RightParen,
+                ],
+            ),
+            (
+                "(2 + 3",
+                vec![
+                    Token::LeftParen,
+                    Token::integer(2),
+                    Token::Operator(Operator::Plus),
+                    Token::integer(3),
+                ],
+            ),
+            (
+                "))(",
+                vec![Token::RightParen, Token::RightParen, Token::LeftParen],
+            ),
+            (
+                " ( 2 + 3 ) * 4\n",
+                vec![
+                    Token::LeftParen,
+                    Token::integer(2),
+                    Token::Operator(Operator::Plus),
+                    Token::integer(3),
+                    Token::RightParen,
+                    Token::Operator(Operator::Star),
+                    Token::integer(4),
+                ],
+            ),
+        ];
+
+        for (source, expected_tokens) in cases {
+            let mut tokenizer = Tokenizer::new(source);
+
+            // Act
+            let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should tokenize parens regardless of their nesting, balance or surrounding whitespace"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_number_separators() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("1_000_000 + 2_5");
+        let mut invalid_tokenizer = Tokenizer::new("1__000 + 2");
+
+        let expected_tokens = vec![
+            Token::integer(1000000),
+            Token::Operator(Operator::Plus),
+            Token::integer(25),
+        ];
+
+        // Act
+        let tokens = without_eof(tokenizer.tokenize().unwrap());
+        let err = invalid_tokenizer.tokenize().unwrap_err();
+
+        // Assert
+        assert_eq!(
+            tokens, expected_tokens,
+            "should tokenize numbers with underscores between digits as regular numbers"
+        );
+
+        assert!(
+            err.to_string().contains("separator"),
+            "should report misplaced underscore separators instead of a generic error"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_commas() {
+        // Arrange
+        let sources = ["1, 2, 3", "1,2,3", " 1 ,2 , 3 "];
+
+        let expected_tokens = vec![
+            Token::integer(1),
+            Token::Comma,
+            Token::integer(2),
+            Token::Comma,
+            Token::integer(3),
+        ];
+
+        for source in sources {
+            let mut tokenizer = Tokenizer::new(source);
+
+            // Act
+            let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should tokenize commas regardless of their surrounding whitespace"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_pipes() {
+        // Arrange
+        // Each source is paired with its expected tokens. Notice pipes are emitted as they are found, since
+        // telling opening bars from closing ones is left to the parser
+        let cases = [
+            (
+                "|5 - 8| * 2",
+                vec![
+                    Token::Pipe,
+                    Token::integer(5),
+                    Token::Operator(Operator::Minus),
+                    Token::integer(8),
+                    Token::Pipe,
+                    Token::Operator(Operator::Star),
+                    Token::integer(2),
+                ],
+            ),
+            (
+                "||-1||",
+                vec![
+                    Token::Pipe,
+                    Token::Pipe,
+                    Token::Operator(Operator::Minus),
+                    Token::integer(1),
+                    Token::Pipe,
+                    Token::Pipe,
+                ],
+            ),
+        ];
+
+        for (source, expected_tokens) in cases {
+            // Act
+            let tokens = without_eof(Tokenizer::new(source).tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should tokenize every bar as a pipe, even adjacent ones"
+            )
+        }
+
+        assert_eq!(
+            Token::Pipe.to_string(),
+            "|",
+            "should display pipe as its symbol"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_semicolons() {
+        // Arrange
+        // Each source is paired with its expected tokens
+        let cases = [
+            (
+                "1;2",
+                vec![Token::integer(1), Token::Semicolon, Token::integer(2)],
+            ),
+            ("; ;", vec![Token::Semicolon, Token::Semicolon]),
+            (
+                "1+2; 3*4",
+                vec![
+                    Token::integer(1),
+                    Token::Operator(Operator::Plus),
+                    Token::integer(2),
+                    Token::Semicolon,
+                    Token::integer(3),
+                    Token::Operator(Operator::Star),
+                    Token::integer(4),
+                ],
+            ),
+        ];
+
+        for (source, expected_tokens) in cases {
+            // Act
+            let tokens = without_eof(Tokenizer::new(source).tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should tokenize semicolons regardless of their surrounding whitespace"
+            )
+        }
+
+        assert_eq!(
+            Token::Semicolon.to_string(),
+            ";",
+            "should display semicolon as its symbol"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_conditional() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("x>0?x:-x");
+
+        let expected_tokens = vec![
+            Token::Identifier(Symbol::intern("x")),
+            Token::Operator(Operator::Greater),
+            Token::integer(0),
+            Token::Question,
+            Token::Identifier(Symbol::intern("x")),
+            Token::Colon,
+            Token::Operator(Operator::Minus),
+            Token::Identifier(Symbol::intern("x")),
+        ];
+
+        // Act
+        let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+        // Assert
+        assert_eq!(
+            tokens, expected_tokens,
+            "should tokenize the punctuation of conditionals"
+        );
+
+        assert_eq!(
+            (Token::Question.to_string(), Token::Colon.to_string()),
+            ("?".to_string(), ":".to_string()),
+            "should display conditional punctuation as its symbols"
         )
     }
+
+    #[test]
+    fn test_tokenize_boolean_operators() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("x > 0 and not order or android");
+        let mut disabled_tokenizer = Tokenizer::with_options(
+            "not x",
+            TokenizerOptions {
+                disabled_operators: vec![Operator::Not],
+                ..TokenizerOptions::default()
+            },
+        );
+
+        let expected_tokens = vec![
+            Token::Identifier(Symbol::intern("x")),
+            Token::Operator(Operator::Greater),
+            Token::integer(0),
+            Token::Operator(Operator::And),
+            Token::Operator(Operator::Not),
+            Token::Identifier(Symbol::intern("order")),
+            Token::Operator(Operator::Or),
+            Token::Identifier(Symbol::intern("android")),
+        ];
+
+        // Act
+        let tokens = without_eof(tokenizer.tokenize().unwrap());
+        let disabled_tokens = without_eof(disabled_tokenizer.tokenize().unwrap());
+
+        // Assert
+        assert_eq!(
+            tokens, expected_tokens,
+            "should tokenize whole words and, or and not as operators"
+        );
+
+        assert_eq!(
+            disabled_tokens,
+            vec![
+                Token::Identifier(Symbol::intern("not")),
+                Token::Identifier(Symbol::intern("x")),
+            ],
+            "should tokenize disabled boolean operators as identifiers"
+        );
+    }
+
+    #[test]
+    fn test_tokenize_bitwise_operators() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("1<<20 >> 2 & 0xFF bor 3 <= 4");
+
+        let expected_tokens = vec![
+            Token::integer(1),
+            Token::Operator(Operator::LessLess),
+            Token::integer(20),
+            Token::Operator(Operator::GreaterGreater),
+            Token::integer(2),
+            Token::Operator(Operator::Ampersand),
+            Token::integer(255),
+            Token::Operator(Operator::BitOr),
+            Token::integer(3),
+            Token::Operator(Operator::LessEqual),
+            Token::integer(4),
+        ];
+
+        // Act
+        let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+        // Assert
+        assert_eq!(
+            tokens, expected_tokens,
+            "should tokenize shifts, '&' and the bor keyword"
+        );
+    }
+
+    #[test]
+    fn test_tokenize_comparisons() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("1 <= 2 == 3 != 4 > 5 < 6 >= 7!");
+        let invalid_sources = ["1 =< 2", "1 => 2"];
+
+        // Act
+        let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+        // Assert
+        assert_eq!(
+            tokens,
+            vec![
+                Token::integer(1),
+                Token::Operator(Operator::LessEqual),
+                Token::integer(2),
+                Token::Operator(Operator::EqualEqual),
+                Token::integer(3),
+                Token::Operator(Operator::BangEqual),
+                Token::integer(4),
+                Token::Operator(Operator::Greater),
+                Token::integer(5),
+                Token::Operator(Operator::Less),
+                Token::integer(6),
+                Token::Operator(Operator::GreaterEqual),
+                Token::integer(7),
+                Token::Operator(Operator::Bang),
+            ],
+            "should tell comparison operators from their single character prefixes"
+        );
+
+        for source in invalid_sources {
+            assert!(
+                Tokenizer::new(source).tokenize().is_err(),
+                "should reject stray comparison characters"
+            )
+        }
+    }
+
+    #[test]
+    fn test_comparison_operators_display_round_trip() {
+        // Arrange
+        let operators = [
+            Operator::Less,
+            Operator::LessEqual,
+            Operator::Greater,
+            Operator::GreaterEqual,
+            Operator::EqualEqual,
+            Operator::BangEqual,
+        ];
+
+        for operator in operators {
+            // Act
+            let tokens = without_eof(Tokenizer::new(&operator.to_string()).tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens,
+                vec![Token::Operator(operator)],
+                "should tokenize displayed operator back into the same operator"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_caret() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("2 ^ 10");
+        let mut adjacent_tokenizer = Tokenizer::new("2^^3");
+
+        let expected_tokens = vec![
+            Token::integer(2),
+            Token::Operator(Operator::Caret),
+            Token::integer(10),
+        ];
+
+        // Notice `^^` has no meaning by itself, so it is tokenized as two separated carets.
+        // Rejecting it is a responsibility of the parser
+        let expected_adjacent_tokens = vec![
+            Token::integer(2),
+            Token::Operator(Operator::Caret),
+            Token::Operator(Operator::Caret),
+            Token::integer(3),
+        ];
+
+        // Act
+        let tokens = without_eof(tokenizer.tokenize().unwrap());
+        let adjacent_tokens = without_eof(adjacent_tokenizer.tokenize().unwrap());
+
+        // Assert
+        assert_eq!(
+            tokens, expected_tokens,
+            "should tokenize caret as an operator"
+        );
+
+        assert_eq!(
+            adjacent_tokens, expected_adjacent_tokens,
+            "should tokenize adjacent carets as independent operators"
+        );
+
+        assert_eq!(
+            Token::Operator(Operator::Caret).to_string(),
+            "^",
+            "should display caret operator as its symbol"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_double_star() {
+        // Arrange
+        // Each source is paired with its expected tokens. Notice `**` is only recognized without whitespace
+        // between its stars, and `***` is taken greedily as `**` followed by `*`
+        let cases = vec![
+            (
+                "2 * 3",
+                vec![
+                    Token::integer(2),
+                    Token::Operator(Operator::Star),
+                    Token::integer(3),
+                ],
+            ),
+            (
+                "2**3",
+                vec![
+                    Token::integer(2),
+                    Token::Operator(Operator::Caret),
+                    Token::integer(3),
+                ],
+            ),
+            (
+                "2 * *3",
+                vec![
+                    Token::integer(2),
+                    Token::Operator(Operator::Star),
+                    Token::Operator(Operator::Star),
+                    Token::integer(3),
+                ],
+            ),
+            (
+                "2***3",
+                vec![
+                    Token::integer(2),
+                    Token::Operator(Operator::Caret),
+                    Token::Operator(Operator::Star),
+                    Token::integer(3),
+                ],
+            ),
+        ];
+
+        for (source, expected_tokens) in cases {
+            let mut tokenizer = Tokenizer::new(source);
+
+            // Act
+            let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should tokenize `**` as power operator without breaking `*`"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_percent() {
+        // Arrange
+        // Notice `%` does not need surrounding whitespace, so it is tokenized the same way adjacent to numbers
+        let sources = ["10 % 3", "10%3"];
+
+        let expected_tokens = vec![
+            Token::integer(10),
+            Token::Operator(Operator::Percent),
+            Token::integer(3),
+        ];
+
+        for source in sources {
+            let mut tokenizer = Tokenizer::new(source);
+
+            // Act
+            let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should tokenize percent as an operator regardless of surrounding whitespace"
+            )
+        }
+
+        assert_eq!(
+            Token::Operator(Operator::Percent).to_string(),
+            "%",
+            "should display percent operator as its symbol"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_bang() {
+        // Arrange
+        // Each source is paired with its expected tokens. Notice a leading bang is tokenized too,
+        // since rejecting it is a responsibility of the parser
+        let cases = vec![
+            (
+                "5!",
+                vec![Token::integer(5), Token::Operator(Operator::Bang)],
+            ),
+            (
+                "!5",
+                vec![Token::Operator(Operator::Bang), Token::integer(5)],
+            ),
+            (
+                "3!!",
+                vec![
+                    Token::integer(3),
+                    Token::Operator(Operator::Bang),
+                    Token::Operator(Operator::Bang),
+                ],
+            ),
+        ];
+
+        for (source, expected_tokens) in cases {
+            let mut tokenizer = Tokenizer::new(source);
+
+            // Act
+            let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should tokenize bang as an operator"
+            )
+        }
+
+        assert_eq!(
+            Token::Operator(Operator::Bang).to_string(),
+            "!",
+            "should display bang operator as its symbol"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_with_decimal_separator() {
+        // Arrange
+        let comma_options = TokenizerOptions {
+            decimal_separator: DecimalSeparator::Comma,
+            ..Default::default()
+        };
+
+        // Each source is paired with its expected tokens in dot and comma mode.
+        // Notice in comma mode arguments are separated by `;`
+        let cases = [
+            (
+                "2.75 * 2",
+                Some(vec![
+                    Token::float(2.75),
+                    Token::Operator(Operator::Star),
+                    Token::integer(2),
+                ]),
+                None,
+            ),
+            (
+                "2,75 * 2",
+                Some(vec![
+                    Token::integer(2),
+                    Token::Comma,
+                    Token::integer(75),
+                    Token::Operator(Operator::Star),
+                    Token::integer(2),
+                ]),
+                Some(vec![
+                    Token::float(2.75),
+                    Token::Operator(Operator::Star),
+                    Token::integer(2),
+                ]),
+            ),
+            (
+                "(1,5; 2)",
+                Some(vec![
+                    Token::LeftParen,
+                    Token::integer(1),
+                    Token::Comma,
+                    Token::integer(5),
+                    Token::Semicolon,
+                    Token::integer(2),
+                    Token::RightParen,
+                ]),
+                Some(vec![
+                    Token::LeftParen,
+                    Token::float(1.5),
+                    Token::Comma,
+                    Token::integer(2),
+                    Token::RightParen,
+                ]),
+            ),
+        ];
+
+        for (source, expected_dot, expected_comma) in cases {
+            // Act
+            let dot_tokens = Tokenizer::new(source).tokenize().ok().map(without_eof);
+            let comma_tokens = Tokenizer::with_options(source, comma_options.clone())
+                .tokenize()
+                .ok()
+                .map(without_eof);
+
+            // Assert
+            assert_eq!(
+                dot_tokens, expected_dot,
+                "should tokenize numbers with `.` as decimal mark and `,` as arguments separator"
+            );
+
+            assert_eq!(
+                comma_tokens, expected_comma,
+                "should tokenize numbers with `,` as decimal mark and `;` as arguments separator"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_comments() {
+        // Arrange
+        // Each source is paired with its expected tokens
+        let cases = vec![
+            (
+                "3 * 4  # area",
+                vec![
+                    Token::integer(3),
+                    Token::Operator(Operator::Star),
+                    Token::integer(4),
+                ],
+            ),
+            (
+                "# full comment line\n1 + 2# trailing\n# another one\n",
+                vec![
+                    Token::integer(1),
+                    Token::Operator(Operator::Plus),
+                    Token::integer(2),
+                ],
+            ),
+            ("#only comment", vec![]),
+        ];
+
+        for (source, expected_tokens) in cases {
+            let mut tokenizer = Tokenizer::new(source);
+
+            // Act
+            let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should skip comments until the end of the line"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_preserving_trivia() {
+        // Arrange
+        let options = TokenizerOptions {
+            preserve_trivia: true,
+            ..Default::default()
+        };
+        let mut tokenizer = Tokenizer::with_options("3 *\t4  # area\n", options);
+
+        // Act
+        let tokens = tokenizer.tokenize().unwrap();
+
+        // Assert
+        assert_eq!(
+            tokens,
+            vec![
+                Token::integer(3),
+                Token::Whitespace,
+                Token::Operator(Operator::Star),
+                Token::Whitespace,
+                Token::integer(4),
+                Token::Whitespace,
+                Token::Comment,
+                Token::Whitespace,
+                Token::Eof(Position::new(2, 1)),
+            ],
+            "should produce whitespace runs and comments as tokens"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_preserving_trivia_round_trips() {
+        // Arrange
+        let sources = [
+            "  1_000.25 + 6.02e23\u{a0}* (0xFF -\r\n 42)\u{2003}/ 0b1010 # comment 1 + 2\n",
+            "x = 2 ** 3;\t|-5| # absolute\n\n  # trailing comment",
+            "15%% + 10%3 - 5 %\n 2 <= pi",
+            "",
+        ];
+        let options = TokenizerOptions {
+            preserve_trivia: true,
+            ..Default::default()
+        };
+
+        for source in sources {
+            let mut tokenizer = Tokenizer::with_options(source, options.clone());
+
+            // Act
+            let tokens = tokenizer.tokenize_spanned().unwrap();
+            let rebuilt: String = tokens
+                .iter()
+                .map(|token| &source[token.span.start..token.span.end])
+                .collect();
+
+            // Assert
+            assert_eq!(
+                rebuilt, source,
+                "should rebuild the source byte-for-byte from the lexemes of its tokens"
+            );
+
+            assert_eq!(
+                tokens
+                    .iter()
+                    .filter(|token| { !matches!(token.value, Token::Whitespace | Token::Comment) })
+                    .copied()
+                    .collect::<Vec<_>>(),
+                Tokenizer::new(source).tokenize_spanned().unwrap(),
+                "should produce the same tokens than the default mode besides trivia"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_hex_numbers() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("0xFF + 0x10");
+        let invalid_sources = ["0x", "0xG1 + 1"];
+
+        let expected_tokens = vec![
+            Token::integer(255),
+            Token::Operator(Operator::Plus),
+            Token::integer(16),
+        ];
+
+        // Act
+        let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+        // Assert
+        assert_eq!(
+            tokens, expected_tokens,
+            "should tokenize hexadecimal numbers as regular numbers"
+        );
+
+        for source in invalid_sources {
+            assert!(
+                Tokenizer::new(source).tokenize().is_err(),
+                "should return error if hexadecimal number is malformed"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_tracks_position() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("12.5 +\n 300");
+
+        // Act
+        tokenizer.tokenize().unwrap();
+
+        // Assert
+        assert_eq!(
+            tokenizer.position(),
+            Position::new(2, 5),
+            "should move position across lines and multi-character numbers while consuming characters"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_error_position() {
+        // Arrange
+        // Each source is paired with the position of its offending character
+        let cases = [
+            ("3 + 4 $", Position::new(1, 7)),
+            ("12345 ~", Position::new(1, 7)),
+            ("1 +\n 22 $", Position::new(2, 5)),
+            ("1 + 2__0", Position::new(1, 5)),
+        ];
+
+        for (source, expected_position) in cases {
+            // Act
+            let err = Tokenizer::new(source).tokenize().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.position(),
+                expected_position,
+                "should report the position where the failing token starts"
+            )
+        }
+
+        assert_eq!(
+            Tokenizer::new("3 + 4 $")
+                .tokenize()
+                .unwrap_err()
+                .to_string(),
+            "[TOKENIZER ERROR] at 1:7 (offset 6): unexpected character '$'",
+            "should include position, offset and offending character in error message"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_error_kinds() {
+        // Arrange
+        // Each source is paired with the expected error kind and the byte offset where it occurred
+        let cases = [
+            ("3 + ~", TokenizerErrorKind::UnexpectedCharacter('~'), 4),
+            (
+                "1 + 2.5.1",
+                TokenizerErrorKind::InvalidNumber {
+                    lexeme: "2.5".to_string(),
+                    reason: InvalidNumberReason::RepeatedDecimalMark,
+                },
+                4,
+            ),
+            ("¿ + 1", TokenizerErrorKind::UnexpectedCharacter('¿'), 0),
+            (
+                "2 * 1__0",
+                TokenizerErrorKind::InvalidNumber {
+                    lexeme: "1_".to_string(),
+                    reason: InvalidNumberReason::MisplacedSeparator,
+                },
+                4,
+            ),
+            ("1e+ 2", TokenizerErrorKind::UnterminatedExponent, 0),
+            (
+                "0xZ",
+                TokenizerErrorKind::InvalidNumber {
+                    lexeme: "0xZ".to_string(),
+                    reason: InvalidNumberReason::InvalidDigits { radix: 16 },
+                },
+                0,
+            ),
+        ];
+
+        for (source, expected_kind, expected_offset) in cases {
+            // Act
+            let err = Tokenizer::new(source).tokenize().unwrap_err();
+
+            // Assert
+            assert_eq!(
+                err.kind(),
+                &expected_kind,
+                "should report the kind of error with its details"
+            );
+            assert_eq!(
+                err.offset(),
+                expected_offset,
+                "should report the byte offset where the error occurred"
+            );
+        }
+    }
+
+    #[test]
+    fn test_spanned_tokens() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("10 + 2.5");
+
+        let expected_tokens = vec![
+            Spanned::new(Token::integer(10), Span::new(0, 2), Position::new(1, 1)),
+            Spanned::new(
+                Token::Operator(Operator::Plus),
+                Span::new(3, 4),
+                Position::new(1, 4),
+            ),
+            Spanned::new(Token::float(2.5), Span::new(5, 8), Position::new(1, 6)),
+            Spanned::new(
+                Token::Eof(Position::new(1, 9)),
+                Span::new(8, 8),
+                Position::new(1, 9),
+            ),
+        ];
+
+        // Act
+        let tokens = tokenizer.tokenize_spanned().unwrap();
+
+        // Assert
+        assert_eq!(
+            tokens, expected_tokens,
+            "should pair every token with the byte offsets and the position of the source it was built from"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_ends_with_eof() {
+        // Arrange
+        // Each source is paired with the expected position of its end
+        let cases = [
+            ("2 +", Position::new(1, 4)),
+            ("", Position::new(1, 1)),
+            ("1\n  ", Position::new(2, 3)),
+            ("1 # comment", Position::new(1, 12)),
+        ];
+
+        for (source, expected_position) in cases {
+            // Act
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+
+            // Assert
+            assert_eq!(
+                tokens.last(),
+                Some(&Token::Eof(expected_position)),
+                "should end tokens with the position right after the last character"
+            )
+        }
+
+        assert_eq!(
+            Tokenizer::new("1 +").filter_map(Result::ok).last(),
+            Some(Token::Operator(Operator::Plus)),
+            "should not yield end of input while iterating"
+        )
+    }
+
+    #[test]
+    fn test_spanned_tokens_multiline() {
+        // Arrange
+        // Notice the non-breaking space takes two bytes, so offsets are not the same as characters count
+        let mut tokenizer = Tokenizer::new("1 +\n22\u{a0}*\r\n3");
+
+        let expected_spans = vec![
+            Span::new(0, 1),
+            Span::new(2, 3),
+            Span::new(4, 6),
+            Span::new(8, 9),
+            Span::new(11, 12),
+            Span::new(12, 12),
+        ];
+
+        // Act
+        let tokens = tokenizer.tokenize_spanned().unwrap();
+
+        // Assert
+        assert_eq!(
+            tokens
+                .into_iter()
+                .map(|token| token.span)
+                .collect::<Vec<Span>>(),
+            expected_spans,
+            "should compute byte offsets across lines"
+        )
+    }
+
+    #[test]
+    fn test_spanned_tokens_positions_multiline() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("1 +\n22\u{a0}*\r\n3");
+
+        let expected_positions = vec![
+            Position::new(1, 1),
+            Position::new(1, 3),
+            Position::new(2, 1),
+            Position::new(2, 4),
+            Position::new(3, 1),
+            Position::new(3, 2),
+        ];
+
+        // Act
+        let tokens = tokenizer.tokenize_spanned().unwrap();
+
+        // Assert
+        assert_eq!(
+            tokens
+                .into_iter()
+                .map(|token| token.position)
+                .collect::<Vec<Position>>(),
+            expected_positions,
+            "should compute the line and column where every token starts"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_malformed_numbers_fail() {
+        // Arrange
+        let invalid_sources = [".", "1 + .", "٣ + ٤", "1.2.3"];
+
+        for source in invalid_sources {
+            // Act & Assert
+            assert!(
+                Tokenizer::new(source).tokenize().is_err(),
+                "should return error instead of panicking if number cannot be parsed"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_non_ascii_digits_fail() {
+        // Arrange
+        let sources = ["٣ + ٤", "1 + ३"];
+
+        for source in sources {
+            // Act
+            let err = Tokenizer::new(source).tokenize().unwrap_err();
+
+            // Assert
+            assert!(
+                err.to_string().contains("unexpected character"),
+                "should report non ASCII digits as unexpected characters instead of panicking"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_fails() {
+        // Arrange
+        const INVALID_SOURCE: &str = "invalid $ source";
+        let mut tokenizer = Tokenizer::new(INVALID_SOURCE);
+
+        // Act & Assert
+        assert!(
+            tokenizer.tokenize().is_err(),
+            "should return error if given source cannot be parsed"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_invalid_input_never_panics() {
+        // Arrange
+        // Every printable ASCII character is tried alone and glued to a number, plus some malformed sources
+        let mut sources: Vec<String> = (' '..='~')
+            .flat_map(|c| [c.to_string(), format!("1{c}"), format!("{c}1")])
+            .collect();
+        sources.extend(
+            [
+                "0x", "0b2", "1e", "1e+", "_", "1__", "..", "1.2.3", "٣", "\u{0}", "$$$",
+            ]
+            .map(String::from),
+        );
+
+        for source in &sources {
+            // Act
+            let result = std::panic::catch_unwind(|| Tokenizer::new(source).tokenize());
+
+            // Assert
+            assert!(
+                result.is_ok(),
+                "should return a result instead of panicking for source {source:?}"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_all_collects_every_error() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("1 + $ 2\n* ~ 3");
+
+        // Act
+        let errors = tokenizer.tokenize_all().unwrap_err();
+
+        // Assert
+        assert_eq!(
+            errors
+                .iter()
+                .map(|err| (err.kind().clone(), err.position()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    TokenizerErrorKind::UnexpectedCharacter('$'),
+                    Position::new(1, 5)
+                ),
+                (
+                    TokenizerErrorKind::UnexpectedCharacter('~'),
+                    Position::new(2, 3)
+                ),
+            ],
+            "should report every invalid character with its position"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_all_skips_malformed_numbers() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("1_ + 2.5.1 + 3");
+
+        // Act
+        let errors = tokenizer.tokenize_all().unwrap_err();
+
+        // Assert
+        assert_eq!(
+            errors.iter().map(|err| err.offset()).collect::<Vec<_>>(),
+            vec![0, 5],
+            "should continue after the characters of malformed numbers"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_all_without_errors() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new(SOURCE);
+
+        // Act
+        let tokens = tokenizer.tokenize_all();
+
+        // Assert
+        assert_eq!(
+            tokens,
+            Tokenizer::new(SOURCE).tokenize().map_err(|err| vec![err]),
+            "should return the same tokens than tokenize if source is valid"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_constants() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("2 * pi + E - Tau");
+
+        // Act
+        let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+        // Assert
+        assert_eq!(
+            tokens,
+            vec![
+                Token::integer(2),
+                Token::Operator(Operator::Star),
+                Token::Constant(Constant::Pi),
+                Token::Operator(Operator::Plus),
+                Token::Constant(Constant::E),
+                Token::Operator(Operator::Minus),
+                Token::Constant(Constant::Tau),
+            ],
+            "should tokenize constant names ignoring case"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_identifiers() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("pie * x + tau2 - e_ + π");
+
+        // Act
+        let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+        // Assert
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Identifier(Symbol::intern("pie")),
+                Token::Operator(Operator::Star),
+                Token::Identifier(Symbol::intern("x")),
+                Token::Operator(Operator::Plus),
+                Token::Identifier(Symbol::intern("tau2")),
+                Token::Operator(Operator::Minus),
+                Token::Identifier(Symbol::intern("e_")),
+                Token::Operator(Operator::Plus),
+                Token::Identifier(Symbol::intern("π")),
+            ],
+            "should tokenize words which are not constant names as identifiers"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_exponent_marker_without_digits() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("2exp(1) + 2 e");
+
+        // Act
+        let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+        // Assert
+        assert_eq!(
+            tokens,
+            vec![
+                Token::integer(2),
+                Token::Identifier(Symbol::intern("exp")),
+                Token::LeftParen,
+                Token::integer(1),
+                Token::RightParen,
+                Token::Operator(Operator::Plus),
+                Token::integer(2),
+                Token::Constant(Constant::E),
+            ],
+            "should end numbers before an exponent marker starting a word"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_equals() {
+        // Arrange
+        // Each source is paired with its expected tokens
+        let cases = [
+            (
+                "x = 5",
+                vec![
+                    Token::Identifier(Symbol::intern("x")),
+                    Token::Equals,
+                    Token::integer(5),
+                ],
+            ),
+            ("= 5", vec![Token::Equals, Token::integer(5)]),
+        ];
+
+        for (source, expected_tokens) in cases {
+            // Act
+            let tokens = without_eof(Tokenizer::new(source).tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should tokenize a single `=` as equals"
+            )
+        }
+
+        assert_eq!(
+            without_eof(Tokenizer::new("x == 5").tokenize().unwrap()),
+            vec![
+                Token::Identifier(Symbol::intern("x")),
+                Token::Operator(Operator::EqualEqual),
+                Token::integer(5),
+            ],
+            "should tokenize `==` as equality operator instead of two equals"
+        );
+
+        assert_eq!(
+            Token::Equals.to_string(),
+            "=",
+            "should display equals as its symbol"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_integers_and_floats() {
+        // Arrange
+        let mut tokenizer = Tokenizer::new("7 7.0 7. 7e0 0x7");
+
+        // Act
+        let tokens = without_eof(tokenizer.tokenize().unwrap());
+
+        // Assert
+        assert_eq!(
+            tokens,
+            vec![
+                Token::integer(7),
+                Token::float(7.0),
+                Token::float(7.0),
+                Token::float(7.0),
+                Token::integer(7),
+            ],
+            "should tokenize numbers without decimal mark nor exponent as integers"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_integer_overflow_falls_back_to_float() {
+        // Arrange
+        // Each source is paired with its expected token
+        let cases = [
+            ("9223372036854775807", Token::integer(i64::MAX)),
+            ("9223372036854775808", Token::float(9223372036854775808.0)),
+            ("0xFFFFFFFFFFFFFFFF", Token::float(u64::MAX as f64)),
+        ];
+
+        for (source, expected_token) in cases {
+            // Act
+            let tokens = without_eof(Tokenizer::new(source).tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens,
+                vec![expected_token],
+                "should fall back to float if integer does not fit in i64"
+            )
+        }
+    }
+
+    #[test]
+    fn test_number_tokens_spans_round_trip() {
+        // Arrange
+        let sources = [
+            "7",
+            "7.0",
+            "0.5",
+            "1234567",
+            "2.75",
+            "1_000",
+            "0xFF",
+            "6.02E23",
+            ".5",
+            "5.",
+            "0.30000000000000004",
+        ];
+
+        for source in sources {
+            // Act
+            let tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+            let lexeme = &source[tokens[0].span.start..tokens[0].span.end];
+
+            // Assert
+            assert_eq!(
+                lexeme, source,
+                "should span number tokens over the text they were typed with"
+            );
+            assert_eq!(
+                Tokenizer::new(&tokens[0].value.to_string())
+                    .tokenize()
+                    .unwrap()[0],
+                tokens[0].value,
+                "should tokenize displayed number back into the same token"
+            );
+        }
+    }
+
+    #[test]
+    fn test_tokenize_percent_suffix() {
+        // Arrange
+        // Each source is paired with its expected tokens
+        let cases = [
+            (
+                "15% + 5%",
+                vec![
+                    Token::integer(15),
+                    Token::Operator(Operator::PercentSuffix),
+                    Token::Operator(Operator::Plus),
+                    Token::integer(5),
+                    Token::Operator(Operator::PercentSuffix),
+                ],
+            ),
+            (
+                "50%%",
+                vec![
+                    Token::integer(50),
+                    Token::Operator(Operator::PercentSuffix),
+                    Token::Operator(Operator::PercentSuffix),
+                ],
+            ),
+            (
+                "15 %",
+                vec![Token::integer(15), Token::Operator(Operator::Percent)],
+            ),
+            (
+                "(15)%",
+                vec![
+                    Token::LeftParen,
+                    Token::integer(15),
+                    Token::RightParen,
+                    Token::Operator(Operator::Percent),
+                ],
+            ),
+        ];
+
+        for (source, expected_tokens) in cases {
+            // Act
+            let tokens = without_eof(Tokenizer::new(source).tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should tokenize `%` right after a number as percent suffix and anywhere else as modulo"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_unit_suffix() {
+        // Arrange
+        // Each source is paired with its expected tokens
+        let cases = [
+            (
+                "30deg * 2",
+                vec![
+                    Token::integer(30),
+                    Token::Operator(Operator::Degrees),
+                    Token::Operator(Operator::Star),
+                    Token::integer(2),
+                ],
+            ),
+            (
+                "1.5rad",
+                vec![Token::float(1.5), Token::Operator(Operator::Radians)],
+            ),
+            (
+                "30 deg",
+                vec![Token::integer(30), Token::Identifier(Symbol::intern("deg"))],
+            ),
+            (
+                "30degrees",
+                vec![
+                    Token::integer(30),
+                    Token::Identifier(Symbol::intern("degrees")),
+                ],
+            ),
+            (
+                "x deg",
+                vec![
+                    Token::Identifier(Symbol::intern("x")),
+                    Token::Identifier(Symbol::intern("deg")),
+                ],
+            ),
+        ];
+
+        for (source, expected_tokens) in cases {
+            // Act
+            let tokens = without_eof(Tokenizer::new(source).tokenize().unwrap());
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should tokenize `deg` and `rad` right after a number as units and anywhere else as identifiers"
+            )
+        }
+
+        assert_eq!(
+            Token::Operator(Operator::Degrees).to_string(),
+            "deg",
+            "should display degrees suffix as its unit"
+        )
+    }
+
+    #[test]
+    fn test_completeness() {
+        // Arrange
+        // Each source is paired with its expected completeness
+        let cases = [
+            ("1 + 2", Completeness::Complete),
+            ("(1 + 2) * 3", Completeness::Complete),
+            ("15%", Completeness::Complete),
+            ("30deg", Completeness::Complete),
+            ("", Completeness::Complete),
+            ("1 +", Completeness::Incomplete),
+            ("(1 + 2", Completeness::Incomplete),
+            ("((1 + 2) *\n", Completeness::Incomplete),
+            ("2 * # comment", Completeness::Incomplete),
+            ("x > 0 ?", Completeness::Incomplete),
+            ("x > 0 ? x :", Completeness::Incomplete),
+            ("1 + 2)", Completeness::Invalid),
+            ("1 + $", Completeness::Invalid),
+            ("(1 + 2__0", Completeness::Invalid),
+        ];
+
+        for (source, expected_completeness) in cases {
+            // Act
+            let completeness = Tokenizer::completeness(source);
+
+            // Assert
+            assert_eq!(
+                completeness, expected_completeness,
+                "should classify source {source:?} by its completeness"
+            )
+        }
+    }
 }
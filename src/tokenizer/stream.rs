@@ -0,0 +1,336 @@
+#![allow(dead_code)]
+
+use std::{
+    io::{ErrorKind, Read},
+    str,
+};
+
+use super::{
+    options::TokenizerOptions,
+    parser::{Checkpoint, Tokenizer, TokenizerError, TokenizerErrorKind},
+    tokens::{Spanned, Token},
+};
+
+const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Converts the source provided by a reader into a stream of tokens, reading it incrementally.
+///
+/// Produces the same tokens as `Tokenizer` does for the whole source, but only keeps in memory the characters
+/// of the token being scanned plus one buffer of input.
+#[derive(Debug)]
+pub struct StreamingTokenizer<R: Read> {
+    reader: R,
+    options: TokenizerOptions,
+    buffer_size: usize,
+    /// Decoded characters which are not tokenized yet.
+    pending: String,
+    /// Trailing bytes of an UTF-8 sequence split across reads.
+    undecoded: Vec<u8>,
+    /// Progress over the whole source until the first pending character.
+    checkpoint: Checkpoint,
+    eof: bool,
+    invalid_utf8: bool,
+    failed: bool,
+}
+
+impl<R: Read> StreamingTokenizer<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_buffer_size(reader, DEFAULT_BUFFER_SIZE)
+    }
+
+    pub fn with_buffer_size(reader: R, buffer_size: usize) -> Self {
+        Self::with_options(reader, buffer_size, TokenizerOptions::default())
+    }
+
+    pub fn with_options(reader: R, buffer_size: usize, options: TokenizerOptions) -> Self {
+        Self {
+            reader,
+            options,
+            buffer_size: buffer_size.max(1),
+            pending: String::new(),
+            undecoded: Vec::new(),
+            checkpoint: Checkpoint::default(),
+            eof: false,
+            invalid_utf8: false,
+            failed: false,
+        }
+    }
+
+    /// Reads the next buffer of input and appends its decoded characters to the pending ones.
+    ///
+    /// If input contains an invalid UTF-8 sequence, reading stops there, so the characters before it can still
+    /// be tokenized before reporting the error.
+    fn fill(&mut self) -> Result<(), TokenizerError> {
+        let mut buffer = vec![0; self.buffer_size];
+
+        let read = loop {
+            match self.reader.read(&mut buffer) {
+                Ok(read) => break read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(self.error(TokenizerErrorKind::ReadFailed(err.kind()))),
+            }
+        };
+
+        if read == 0 {
+            // Reaching the end with bytes still waiting for the rest of their sequence means source is truncated
+            self.eof = true;
+            self.invalid_utf8 = !self.undecoded.is_empty();
+
+            return Ok(());
+        }
+
+        self.undecoded.extend_from_slice(&buffer[..read]);
+
+        let valid = match str::from_utf8(&self.undecoded) {
+            Ok(decoded) => decoded.len(),
+            // If sequence is just incomplete, its remaining bytes will come with the next read
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => {
+                self.eof = true;
+                self.invalid_utf8 = true;
+                err.valid_up_to()
+            }
+        };
+
+        let decoded =
+            str::from_utf8(&self.undecoded[..valid]).expect("bytes were already validated");
+        self.pending.push_str(decoded);
+        self.undecoded.drain(..valid);
+
+        // Once the source is longer than its limit, reading stops there, so the tokenizer reports the limit
+        // when reaching it instead of buffering the rest of the source
+        if self.checkpoint.offset + self.pending.len() > self.options.max_source_length {
+            self.eof = true;
+        }
+
+        Ok(())
+    }
+
+    fn error(&mut self, kind: TokenizerErrorKind) -> TokenizerError {
+        self.failed = true;
+        TokenizerError::new(kind, self.checkpoint.position, self.checkpoint.offset)
+    }
+
+    /// Yields the next token paired with the span of the source it was built from.
+    pub fn next_spanned(&mut self) -> Option<Result<Spanned<Token>, TokenizerError>> {
+        while !self.failed {
+            let mut tokenizer =
+                Tokenizer::resume(&self.pending, self.options.clone(), self.checkpoint);
+            let result = tokenizer.next_spanned();
+
+            // A token (or error) is only final if something was left after it, otherwise the characters
+            // of the next read could still be part of it, like the rest of a number split across reads
+            let remaining = tokenizer.remaining().len();
+            let is_final = self.eof || remaining > 0;
+
+            match result {
+                Some(Ok(token)) if is_final => {
+                    self.checkpoint = tokenizer.checkpoint();
+                    self.pending.drain(..self.pending.len() - remaining);
+
+                    return Some(Ok(token));
+                }
+                Some(Err(err)) if is_final => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+                None if self.eof && self.invalid_utf8 => {
+                    self.checkpoint = tokenizer.checkpoint();
+                    return Some(Err(self.error(TokenizerErrorKind::InvalidUtf8)));
+                }
+                None if self.eof => {
+                    // Trailing characters without tokens are consumed too, so progress reaches the end of the source
+                    self.checkpoint = tokenizer.checkpoint();
+                    self.pending.clear();
+
+                    return None;
+                }
+                // Pending characters don't produce tokens (like whitespace and comments), so they can be discarded
+                // before reading more, except for the last line, which could be a comment continuing in the next read
+                None => {
+                    let discarded = self.pending.rfind('\n').map_or(0, |i| i + 1);
+
+                    self.checkpoint.position.advance(&self.pending[..discarded]);
+                    self.checkpoint.offset += discarded;
+                    self.checkpoint.after_number &= discarded == 0;
+                    self.pending.drain(..discarded);
+                }
+                _ => {}
+            }
+
+            if let Err(err) = self.fill() {
+                return Some(Err(err));
+            }
+        }
+
+        None
+    }
+
+    /// Consumes the remaining source and returns all its tokens ending with `Token::Eof`, or the first error found.
+    pub fn tokenize(&mut self) -> Result<Vec<Token>, TokenizerError> {
+        let tokens = self.tokenize_spanned()?;
+
+        Ok(tokens.into_iter().map(|token| token.value).collect())
+    }
+
+    /// Same as `tokenize` but pairing every token with the span of the source it was built from.
+    pub fn tokenize_spanned(&mut self) -> Result<Vec<Spanned<Token>>, TokenizerError> {
+        let mut tokens =
+            std::iter::from_fn(|| self.next_spanned()).collect::<Result<Vec<_>, _>>()?;
+
+        // Every character was consumed, so nothing is pending but the end of the source
+        tokens.push(Tokenizer::resume("", self.options.clone(), self.checkpoint).eof());
+
+        Ok(tokens)
+    }
+}
+
+impl<R: Read> Iterator for StreamingTokenizer<R> {
+    type Item = Result<Token, TokenizerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_spanned()
+            .map(|result| result.map(|token| token.value))
+    }
+}
+
+#[cfg(test)]
+mod tokenizer_stream_tests {
+    use std::io::{Cursor, Read};
+
+    use crate::tokenizer::{
+        options::TokenizerOptions,
+        parser::{Limit, Tokenizer, TokenizerErrorKind},
+        tokens::Position,
+    };
+
+    use super::StreamingTokenizer;
+
+    // Notice the source contains multi-byte characters (`\u{a0}` and `\u{2003}` whitespaces) so small buffers
+    // split their UTF-8 sequences across reads, as well as numbers and percent suffixes, which are split across
+    // reads too
+    const SOURCE: &str =
+        "1_000.25 + 6.02e23\u{a0}* (0xFF -\n 42)\u{2003}/ 0b1010 # comment 1 + 2\n + 3.5E-2 \
+        * 15%% + 10%3 - 5 %\n 2exp(1) + 4E-2";
+
+    #[test]
+    fn test_tokenize_same_as_tokenizer() {
+        // Arrange
+        let expected_tokens = Tokenizer::new(SOURCE).tokenize_spanned().unwrap();
+
+        for buffer_size in 1..=16 {
+            let reader = Cursor::new(SOURCE.as_bytes().to_vec());
+            let mut tokenizer = StreamingTokenizer::with_buffer_size(reader, buffer_size);
+
+            // Act
+            let tokens = tokenizer.tokenize_spanned().unwrap();
+
+            // Assert
+            assert_eq!(
+                tokens, expected_tokens,
+                "should produce the same tokens and spans than tokenizing the whole source at once"
+            )
+        }
+    }
+
+    #[test]
+    fn test_tokenize_ends_same_as_tokenizer() {
+        // Arrange
+        // Notice the characters after the last token don't produce tokens, but still move the end of input
+        let sources = ["1 + 2  \n\t", "3 # trailing comment", ""];
+
+        for source in sources {
+            let expected_tokens = Tokenizer::new(source).tokenize_spanned().unwrap();
+
+            for buffer_size in 1..=4 {
+                let reader = Cursor::new(source.as_bytes().to_vec());
+                let mut tokenizer = StreamingTokenizer::with_buffer_size(reader, buffer_size);
+
+                // Act
+                let tokens = tokenizer.tokenize_spanned().unwrap();
+
+                // Assert
+                assert_eq!(
+                    tokens, expected_tokens,
+                    "should end tokens at the same position than tokenizing the whole source at once"
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn test_tokenize_fails_same_as_tokenizer() {
+        // Arrange
+        let sources = ["1 + 2 $ 3", "12 +\n 1__0", "3 + 1e"];
+
+        for source in sources {
+            let expected_error = Tokenizer::new(source).tokenize().unwrap_err();
+
+            for buffer_size in 1..=4 {
+                let reader = Cursor::new(source.as_bytes().to_vec());
+                let mut tokenizer = StreamingTokenizer::with_buffer_size(reader, buffer_size);
+
+                // Act
+                let err = tokenizer.tokenize().unwrap_err();
+
+                // Assert
+                assert_eq!(
+                    err.to_string(),
+                    expected_error.to_string(),
+                    "should report the same error than tokenizing the whole source at once"
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn test_tokenize_fails_by_invalid_utf8() {
+        // Arrange
+        let reader = Cursor::new(vec![b'1', b' ', 0xFF, b'2']);
+        let truncated_reader = Cursor::new(vec![b'1', b' ', 0xC2]);
+
+        // Act
+        let err = StreamingTokenizer::new(reader).tokenize().unwrap_err();
+        let truncated_err = StreamingTokenizer::new(truncated_reader)
+            .tokenize()
+            .unwrap_err();
+
+        // Assert
+        assert_eq!(
+            err.position(),
+            Position::new(1, 3),
+            "should return error at the position of the invalid UTF-8 sequence"
+        );
+
+        assert!(
+            truncated_err.to_string().contains("UTF-8"),
+            "should return error if source ends in the middle of an UTF-8 sequence"
+        )
+    }
+
+    #[test]
+    fn test_tokenize_stops_reading_at_source_length_limit() {
+        // Arrange
+        // Source is endless, so tokenizing it only finishes if reading stops at the limit
+        let reader = std::io::repeat(b'1').take(u64::MAX);
+        let options = TokenizerOptions {
+            max_source_length: 64,
+            max_number_length: usize::MAX,
+            ..Default::default()
+        };
+        let mut tokenizer = StreamingTokenizer::with_options(reader, 16, options);
+
+        // Act
+        let err = tokenizer.tokenize().unwrap_err();
+
+        // Assert
+        assert_eq!(
+            err.kind(),
+            &TokenizerErrorKind::LimitExceeded {
+                limit: Limit::SourceLength,
+                max: 64
+            },
+            "should report source length limit instead of reading the whole source"
+        )
+    }
+}
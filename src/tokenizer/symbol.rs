@@ -0,0 +1,168 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{OnceLock, PoisonError, RwLock},
+};
+
+/// Bytes of all the different texts the table of symbols can hold. Interned texts are never freed, so the table is
+/// capped, and sources with new names past it fail to tokenize instead of growing it without bound.
+pub const MAX_INTERNED_BYTES: usize = 1 << 24;
+
+/// Name interned in a table shared by the whole program, so identifiers are cheap to copy, compare and hash.
+///
+/// Interned texts are never freed, so only names (of variables, functions and the like) are interned, being few
+/// and repeated in a calculator source, while open ended texts like number lexemes or comments are left in the
+/// source. Memory taken by the table is still bounded by `MAX_INTERNED_BYTES`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+#[derive(Default)]
+struct Interner {
+    symbols: HashMap<&'static str, Symbol>,
+    texts: Vec<&'static str>,
+    /// Bytes of all the texts.
+    bytes: usize,
+}
+
+impl Interner {
+    /// Finds the symbol of the given text, interning it if it was never seen before. It is `None` if the text is
+    /// new and interning it would take more than `max_bytes` for all the texts.
+    fn intern(&mut self, text: &str, max_bytes: usize) -> Option<Symbol> {
+        if let Some(&symbol) = self.symbols.get(text) {
+            return Some(symbol);
+        }
+
+        let bytes = self.bytes + text.len();
+
+        if bytes > max_bytes {
+            return None;
+        }
+
+        // Every text takes a byte at least, except the empty one, so there are fewer texts than `u32::MAX`
+        let text: &'static str = Box::leak(text.into());
+        let symbol = Symbol(self.texts.len() as u32);
+
+        self.texts.push(text);
+        self.symbols.insert(text, symbol);
+        self.bytes = bytes;
+
+        Some(symbol)
+    }
+}
+
+fn interner() -> &'static RwLock<Interner> {
+    static INTERNER: OnceLock<RwLock<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(Default::default)
+}
+
+impl Symbol {
+    /// Finds the symbol of the given text, interning it if it was never seen before. Names known by the program,
+    /// like the ones of built-in functions, are interned with it, while the ones from a source go through
+    /// `try_intern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the text is new and the table already holds `MAX_INTERNED_BYTES`.
+    pub fn intern(text: &str) -> Self {
+        Self::try_intern(text).expect("cannot intern more than MAX_INTERNED_BYTES bytes of texts")
+    }
+
+    /// Same as `intern`, but `None` if the text is new and the table already holds `MAX_INTERNED_BYTES`.
+    pub fn try_intern(text: &str) -> Option<Self> {
+        // Interner is never left half updated, so it is still usable if a thread panicked while holding it
+        let known = interner()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .symbols
+            .get(text)
+            .copied();
+
+        if known.is_some() {
+            return known;
+        }
+
+        // Another thread may have interned the same text between releasing the read lock and taking this one, which
+        // is found again by the interner
+        interner()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .intern(text, MAX_INTERNED_BYTES)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        let interner = interner().read().unwrap_or_else(PoisonError::into_inner);
+        interner.texts[self.0 as usize]
+    }
+}
+
+// Debug shows the text instead of its index, which would be meaningless in test failures
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tokenizer_symbol_tests {
+    use super::{Interner, Symbol};
+
+    #[test]
+    fn test_intern() {
+        // Arrange
+        let symbol = Symbol::intern("rate_2");
+
+        // Act
+        let same_symbol = Symbol::intern(&String::from("rate_2"));
+        let other_symbol = Symbol::intern("rate_3");
+
+        // Assert
+        assert_eq!(
+            symbol, same_symbol,
+            "should return the same symbol for equal texts"
+        );
+
+        assert_ne!(
+            symbol, other_symbol,
+            "should return different symbols for different texts"
+        );
+
+        assert_eq!(
+            (symbol.as_str(), other_symbol.to_string().as_str()),
+            ("rate_2", "rate_3"),
+            "should give back the interned texts"
+        )
+    }
+
+    #[test]
+    fn test_intern_up_to_max_bytes() {
+        // Arrange
+        let mut interner = Interner::default();
+
+        // Act
+        let symbols = ["ab", "cde", "f"].map(|text| interner.intern(text, 6));
+
+        // Assert
+        assert!(
+            symbols.iter().all(Option::is_some),
+            "should intern texts up to the maximum bytes"
+        );
+
+        assert_eq!(
+            interner.intern("g", 6),
+            None,
+            "should not intern new texts past the maximum bytes"
+        );
+
+        assert_eq!(
+            interner.intern("cde", 6),
+            symbols[1],
+            "should still find the texts already interned"
+        )
+    }
+}
@@ -1,39 +1,387 @@
+#![allow(dead_code)]
+
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq)]
+use super::symbol::Symbol;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Operator {
     Minus,
     Plus,
     Star,
     Slash,
+    DoubleSlash,
+    Caret,
+    Percent,
+    PercentSuffix,
+    /// The `deg` suffix right after a number, converting degrees to radians.
+    Degrees,
+    /// The `rad` suffix right after a number, which keeps it as radians.
+    Radians,
+    Bang,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    EqualEqual,
+    BangEqual,
+    /// The `and` keyword.
+    And,
+    /// The `or` keyword.
+    Or,
+    /// The `not` keyword.
+    Not,
+    /// The `<<` left shift.
+    LessLess,
+    /// The `>>` right shift.
+    GreaterGreater,
+    /// The `&` bitwise and.
+    Ampersand,
+    /// The `bor` keyword for bitwise or, since `|` is taken by absolute values.
+    BitOr,
 }
 
 impl fmt::Display for Operator {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let operator: &str;
+        let operator = match self {
+            Operator::Plus => "+",
+            Operator::Minus => "-",
+            Operator::Star => "*",
+            Operator::Slash => "/",
+            Operator::DoubleSlash => "//",
+            Operator::Caret => "^",
+            Operator::Percent | Operator::PercentSuffix => "%",
+            Operator::Degrees => "deg",
+            Operator::Radians => "rad",
+            Operator::Bang => "!",
+            Operator::Less => "<",
+            Operator::LessEqual => "<=",
+            Operator::Greater => ">",
+            Operator::GreaterEqual => ">=",
+            Operator::EqualEqual => "==",
+            Operator::BangEqual => "!=",
+            Operator::And => "and",
+            Operator::Or => "or",
+            Operator::Not => "not",
+            Operator::LessLess => "<<",
+            Operator::GreaterGreater => ">>",
+            Operator::Ampersand => "&",
+            Operator::BitOr => "bor",
+        };
+
+        write!(f, "{operator}")
+    }
+}
+
+/// Well known mathematical constants, written by their case-insensitive names.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constant {
+    Pi,
+    E,
+    Tau,
+}
+
+impl Constant {
+    /// Finds the constant with the given name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "pi" => Some(Constant::Pi),
+            "e" => Some(Constant::E),
+            "tau" => Some(Constant::Tau),
+            _ => None,
+        }
+    }
 
+    pub fn value(&self) -> f64 {
         match self {
-            Operator::Plus => operator = "+",
-            Operator::Minus => operator = "-",
-            Operator::Star => operator = "*",
-            Operator::Slash => operator = "/",
+            Constant::Pi => std::f64::consts::PI,
+            Constant::E => std::f64::consts::E,
+            Constant::Tau => std::f64::consts::TAU,
         }
+    }
+}
 
-        write!(f, "{operator}")
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Constant::Pi => "pi",
+            Constant::E => "e",
+            Constant::Tau => "tau",
+        };
+
+        write!(f, "{name}")
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Piece of the source with meaning for the parser.
+///
+/// Tokens are `Copy`, so they hold no text: names are interned as `Symbol`, while the texts of numbers and trivia
+/// are left in the source, where they are found by the spans of the tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Token {
-    Number(f64),
+    Integer(i64),
+    Float(f64),
+    Constant(Constant),
     Operator(Operator),
+    /// Operator registered by the user, like `@`, whose meaning is given by the parser.
+    CustomOperator(char),
+    LeftParen,
+    RightParen,
+    Comma,
+    /// The `|` bar, used in pairs around absolute values.
+    Pipe,
+    /// The `;` character, used for separating statements.
+    Semicolon,
+    /// The `=` character, used for assignments.
+    Equals,
+    /// The `?` character, used in conditionals between the condition and its first branch.
+    Question,
+    /// The `:` character, used in conditionals between their branches.
+    Colon,
+    /// Name of a variable or function, like `x`.
+    Identifier(Symbol),
+    /// Run of whitespace, only produced if trivia is preserved.
+    Whitespace,
+    /// Comment including its leading `#`, only produced if trivia is preserved.
+    Comment,
+    /// End of the source, at the position right after its last character.
+    Eof(Position),
+}
+
+impl Token {
+    pub fn integer(value: i64) -> Self {
+        Token::Integer(value)
+    }
+
+    pub fn float(value: f64) -> Self {
+        Token::Float(value)
+    }
+
+    /// Kind of the token, leaving out the values it carries.
+    pub fn kind(&self) -> TokenKind {
+        match self {
+            Token::Integer(_) | Token::Float(_) => TokenKind::Number,
+            Token::Constant(_) => TokenKind::Constant,
+            Token::Operator(operator) => TokenKind::Operator(*operator),
+            Token::CustomOperator(_) => TokenKind::CustomOperator,
+            Token::LeftParen => TokenKind::LeftParen,
+            Token::RightParen => TokenKind::RightParen,
+            Token::Comma => TokenKind::Comma,
+            Token::Pipe => TokenKind::Pipe,
+            Token::Semicolon => TokenKind::Semicolon,
+            Token::Equals => TokenKind::Equals,
+            Token::Question => TokenKind::Question,
+            Token::Colon => TokenKind::Colon,
+            Token::Identifier(_) => TokenKind::Identifier,
+            Token::Whitespace => TokenKind::Whitespace,
+            Token::Comment => TokenKind::Comment,
+            Token::Eof(_) => TokenKind::Eof,
+        }
+    }
 }
 
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Token::Number(number) => write!(f, "{}", number),
+            Token::Integer(value) => write!(f, "{value}"),
+            // Debug format keeps the decimal part of integral floats, so `7.0` is not written as `7`
+            Token::Float(value) => write!(f, "{value:?}"),
+            Token::Constant(constant) => write!(f, "{}", constant),
             Token::Operator(operator) => write!(f, "{}", operator),
+            Token::CustomOperator(symbol) => write!(f, "{symbol}"),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+            Token::Pipe => write!(f, "|"),
+            Token::Semicolon => write!(f, ";"),
+            Token::Equals => write!(f, "="),
+            Token::Question => write!(f, "?"),
+            Token::Colon => write!(f, ":"),
+            Token::Identifier(name) => write!(f, "{name}"),
+            // Texts of trivia are only in the source, so they are displayed by the character starting them
+            Token::Whitespace => write!(f, " "),
+            Token::Comment => write!(f, "#"),
+            // End of input has no characters, so printing tokens back still reproduces the source
+            Token::Eof(_) => Ok(()),
+        }
+    }
+}
+
+/// Kind of a token, used for matching tokens without caring about their values.
+///
+/// Integers and floats are both numbers, and operators keep which operator they are, so `+` and `-` are told
+/// apart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenKind {
+    Number,
+    Constant,
+    Operator(Operator),
+    CustomOperator,
+    LeftParen,
+    RightParen,
+    Comma,
+    Pipe,
+    Semicolon,
+    Equals,
+    Question,
+    Colon,
+    Identifier,
+    Whitespace,
+    Comment,
+    Eof,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Number => write!(f, "a number"),
+            TokenKind::Constant => write!(f, "a constant"),
+            TokenKind::Operator(operator) => write!(f, "'{operator}'"),
+            TokenKind::CustomOperator => write!(f, "a custom operator"),
+            TokenKind::LeftParen => write!(f, "'('"),
+            TokenKind::RightParen => write!(f, "')'"),
+            TokenKind::Comma => write!(f, "','"),
+            TokenKind::Pipe => write!(f, "'|'"),
+            TokenKind::Semicolon => write!(f, "';'"),
+            TokenKind::Equals => write!(f, "'='"),
+            TokenKind::Question => write!(f, "'?'"),
+            TokenKind::Colon => write!(f, "':'"),
+            TokenKind::Identifier => write!(f, "an identifier"),
+            TokenKind::Whitespace => write!(f, "whitespace"),
+            TokenKind::Comment => write!(f, "a comment"),
+            TokenKind::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+/// Location of a character in the source, both line and column start at `1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+
+    /// Moves the position forward over the given consumed characters, jumping to the next line on each `\n`.
+    pub fn advance(&mut self, consumed: &str) {
+        for c in consumed.chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
         }
     }
 }
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new(1, 1)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Range of bytes of the source covered by some element, `start` is inclusive and `end` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Smallest span covering both this span and the other one.
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
+
+/// Wraps a value together with the span of the source it was built from and the position where it starts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+    pub position: Position,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(value: T, span: Span, position: Position) -> Self {
+        Self {
+            value,
+            span,
+            position,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tokenizer_tokens_tests {
+    use super::{Constant, Operator, Position, Symbol, Token, TokenKind};
+
+    #[test]
+    fn test_token_kind() {
+        // Arrange
+        // Each token is paired with its expected kind and how the kind is described
+        let cases = [
+            (Token::integer(1), TokenKind::Number, "a number"),
+            (Token::float(1.5), TokenKind::Number, "a number"),
+            (
+                Token::Constant(Constant::Pi),
+                TokenKind::Constant,
+                "a constant",
+            ),
+            (
+                Token::Operator(Operator::Plus),
+                TokenKind::Operator(Operator::Plus),
+                "'+'",
+            ),
+            (Token::LeftParen, TokenKind::LeftParen, "'('"),
+            (Token::Colon, TokenKind::Colon, "':'"),
+            (
+                Token::Identifier(Symbol::intern("x")),
+                TokenKind::Identifier,
+                "an identifier",
+            ),
+            (
+                Token::Eof(Position::new(1, 1)),
+                TokenKind::Eof,
+                "end of input",
+            ),
+        ];
+
+        for (token, expected_kind, expected_description) in cases {
+            // Act
+            let kind = token.kind();
+
+            // Assert
+            assert_eq!(
+                kind, expected_kind,
+                "should leave out the values of the token"
+            );
+            assert_eq!(
+                kind.to_string(),
+                expected_description,
+                "should describe the kind as it is expected in errors"
+            );
+        }
+
+        assert_ne!(
+            Token::Operator(Operator::Plus).kind(),
+            Token::Operator(Operator::Minus).kind(),
+            "should tell operators apart"
+        );
+    }
+}